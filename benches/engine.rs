@@ -0,0 +1,114 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use stelsalto::ai::{AlphaBeta, Bot, GreedyBot};
+use stelsalto::record::GameRecord;
+use stelsalto::{positions, Board, Config, Game, Player, StartLayout};
+
+fn two_players() -> Board {
+    Board::new(Config::default().with_layout(StartLayout::TwoPlayers))
+}
+
+/// A game some way in, so that there are jumps to find.
+fn midgame() -> Game {
+    let mut game = Game::new(two_players(), vec![Player::Head, Player::Tail]);
+    let mut bots: Vec<Box<dyn Bot>> = vec![Box::new(GreedyBot), Box::new(GreedyBot)];
+    game.play_bots(&mut bots, 30, |_, _| {}).unwrap();
+    game
+}
+
+/// A game from a bundled position, with a seat for each player who has
+/// pieces in it.
+fn position(name: &str) -> Game {
+    let board = positions::by_name(name).unwrap();
+    let seats = board
+        .config()
+        .layout()
+        .players()
+        .into_iter()
+        .filter(|x| board.pieces_of(*x).next().is_some())
+        .collect();
+    Game::new(board, seats)
+}
+
+fn move_generation(c: &mut Criterion) {
+    for name in positions::NAMES.iter() {
+        let game = position(name);
+        let player = game.seats()[0];
+        c.bench_function(&format!("legal_turns/{}", name), |b| {
+            b.iter(|| black_box(&game.board).legal_turns(player))
+        });
+    }
+}
+
+fn search(c: &mut Criterion) {
+    let mut group = c.benchmark_group("search");
+    group.sample_size(10);
+    for name in positions::NAMES.iter() {
+        let game = position(name);
+        group.bench_function(*name, |b| {
+            b.iter(|| AlphaBeta::new(2).choose(black_box(&game)))
+        });
+    }
+    group.finish();
+}
+
+fn make_unmake(c: &mut Criterion) {
+    let mut board = positions::by_name("traffic-jam").unwrap();
+    let turns = board.legal_turns(Player::Head);
+    c.bench_function("make_unmake", |b| {
+        b.iter(|| {
+            for turn in &turns {
+                let captured = board.make_turn(turn);
+                board.unmake_turn(turn, &captured);
+            }
+        })
+    });
+}
+
+fn self_play(c: &mut Criterion) {
+    let mut group = c.benchmark_group("self_play");
+    group.sample_size(10);
+    group.bench_function("greedy", |b| {
+        b.iter(|| {
+            let mut game = Game::new(two_players(), vec![Player::Head, Player::Tail]);
+            let mut bots: Vec<Box<dyn Bot>> = vec![Box::new(GreedyBot), Box::new(GreedyBot)];
+            game.play_bots(&mut bots, 200, |_, _| {}).unwrap();
+            game
+        })
+    });
+    group.bench_function("alpha_beta", |b| {
+        b.iter(|| {
+            let mut game = Game::new(two_players(), vec![Player::Head, Player::Tail]);
+            let mut bots: Vec<Box<dyn Bot>> =
+                vec![Box::new(AlphaBeta::new(2)), Box::new(AlphaBeta::new(2))];
+            game.play_bots(&mut bots, 40, |_, _| {}).unwrap();
+            game
+        })
+    });
+    group.finish();
+}
+
+fn serialization(c: &mut Criterion) {
+    let game = midgame();
+    let fen = game.board.to_fen(Some(Player::Head));
+    let record = GameRecord::from_game(&game).to_string();
+    c.bench_function("fen/write", |b| {
+        b.iter(|| black_box(&game.board).to_fen(Some(Player::Head)))
+    });
+    c.bench_function("fen/read", |b| b.iter(|| Board::from_fen(black_box(&fen))));
+    c.bench_function("record/write", |b| {
+        b.iter(|| GameRecord::from_game(black_box(&game)).to_string())
+    });
+    c.bench_function("record/read", |b| {
+        b.iter(|| GameRecord::parse(black_box(&record)))
+    });
+}
+
+criterion_group!(
+    benches,
+    move_generation,
+    search,
+    make_unmake,
+    self_play,
+    serialization
+);
+criterion_main!(benches);
@@ -0,0 +1,1763 @@
+//! Computer players.
+
+use crate::{Board, Game, Player, Turn, Variant};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::HashSet;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Something that can choose turns, such as a search or a remote player.
+pub trait Bot {
+    /// Pick a turn for whoever is to move in a game, or `None` if the game
+    /// is over or they have no legal turn.
+    fn choose(&mut self, game: &Game) -> Option<Turn>;
+}
+
+/// Random numbers for a bot to choose a turn with. With `Config::with_seed`,
+/// they depend only on the seed and how many turns have been played.
+fn rng(game: &Game) -> StdRng {
+    match game.board.config().seed() {
+        Some(seed) => StdRng::seed_from_u64(seed.wrapping_add(game.history().len() as u64)),
+        None => StdRng::from_entropy(),
+    }
+}
+
+/// Number of threads to search with, where 0 means one for each core.
+#[cfg(feature = "parallel")]
+fn thread_count(threads: usize) -> usize {
+    match threads {
+        0 => rayon::current_num_threads(),
+        n => n,
+    }
+}
+
+#[cfg(feature = "parallel")]
+fn thread_pool(threads: usize) -> rayon::ThreadPool {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .build()
+        .expect("the thread pool can be built")
+}
+
+/// Score for a finished player, well beyond what the evaluators give.
+const WIN: i32 = 1_000_000;
+
+/// Total number of steps between a player's pieces and the tip of their
+/// target corner. Lower is better, and a finished player has the minimum.
+pub fn distance_to_goal(board: &Board, player: Player) -> i32 {
+    let tip = board.tip(board.config().target_of(player));
+    board
+        .pieces_of(player)
+        .map(|point| point.hex_distance(tip))
+        .sum()
+}
+
+/// `Sync` with the `parallel` feature, so that threads can share an
+/// evaluator, and nothing otherwise.
+#[cfg(feature = "parallel")]
+pub trait MaybeSync: Sync {}
+#[cfg(feature = "parallel")]
+impl<T: Sync + ?Sized> MaybeSync for T {}
+#[cfg(not(feature = "parallel"))]
+pub trait MaybeSync {}
+#[cfg(not(feature = "parallel"))]
+impl<T: ?Sized> MaybeSync for T {}
+
+/// Scores how well a player is doing on a board, where higher is better.
+/// Any `Fn(&Board, Player) -> i32` can be used as an evaluator, which makes
+/// it easy to blend the built-in ones with custom weights.
+pub trait Evaluator: MaybeSync {
+    fn evaluate(&self, board: &Board, player: Player) -> i32;
+}
+
+impl<F: Fn(&Board, Player) -> i32 + MaybeSync> Evaluator for F {
+    fn evaluate(&self, board: &Board, player: Player) -> i32 {
+        self(board, player)
+    }
+}
+
+/// Rewards getting pieces close to the target, using `distance_to_goal`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct DistanceToGoal;
+
+impl Evaluator for DistanceToGoal {
+    fn evaluate(&self, board: &Board, player: Player) -> i32 {
+        -distance_to_goal(board, player)
+    }
+}
+
+/// Rewards keeping pieces close together, so that they can jump over
+/// each other. Scores the negated total distance between every pair.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Cohesion;
+
+impl Evaluator for Cohesion {
+    fn evaluate(&self, board: &Board, player: Player) -> i32 {
+        let pieces: Vec<_> = board.pieces_of(player).collect();
+        pieces
+            .iter()
+            .enumerate()
+            .flat_map(|(i, a)| pieces[i + 1..].iter().map(move |b| -a.hex_distance(*b)))
+            .sum()
+    }
+}
+
+/// Penalizes the piece that is farthest from the target, so that
+/// stragglers are not left behind.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BackmostPiece;
+
+impl Evaluator for BackmostPiece {
+    fn evaluate(&self, board: &Board, player: Player) -> i32 {
+        let tip = board.tip(board.config().target_of(player));
+        -board
+            .pieces_of(player)
+            .map(|point| point.hex_distance(tip))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// How much closer to the target tip a turn brings its piece.
+fn progress(board: &Board, player: Player, turn: &Turn) -> i32 {
+    let tip = board.tip(board.config().target_of(player));
+    turn.source().hex_distance(tip) - turn.target().hex_distance(tip)
+}
+
+/// Plays any legal turn at random. Useful as the weakest possible
+/// opponent when measuring stronger bots.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct RandomBot;
+
+impl Bot for RandomBot {
+    fn choose(&mut self, game: &Game) -> Option<Turn> {
+        let me = game.current_player()?;
+        game.board.legal_turns(me).choose(&mut rng(game)).cloned()
+    }
+}
+
+/// Plays whichever turn moves a piece the farthest toward the target,
+/// without looking ahead.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct GreedyBot;
+
+impl Bot for GreedyBot {
+    fn choose(&mut self, game: &Game) -> Option<Turn> {
+        let me = game.current_player()?;
+        let mut best: Option<(i32, Turn)> = None;
+        for turn in game.board.legal_turns(me) {
+            let gain = progress(&game.board, me, &turn);
+            match &best {
+                Some((x, _)) if gain <= *x => {}
+                _ => best = Some((gain, turn)),
+            }
+        }
+        best.map(|(_, turn)| turn)
+    }
+}
+
+/// How long a search may think about one turn.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ThinkBudget {
+    /// Look this many turns ahead, counting every player's turn.
+    Depth(usize),
+    /// Stop after visiting this many positions.
+    Nodes(u64),
+    /// Stop after this many milliseconds of wall-clock time.
+    Millis(u64),
+}
+
+/// Deepest that a search goes when only limited by nodes or time.
+const MAX_DEPTH: usize = 32;
+
+/// Preset budgets for frontends to offer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub fn budget(self) -> ThinkBudget {
+        match self {
+            Difficulty::Easy => ThinkBudget::Depth(1),
+            Difficulty::Medium => ThinkBudget::Depth(2),
+            Difficulty::Hard => ThinkBudget::Millis(2000),
+        }
+    }
+}
+
+impl std::str::FromStr for Difficulty {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "medium" => Ok(Difficulty::Medium),
+            "hard" => Ok(Difficulty::Hard),
+            _ => Err(format!("Unknown difficulty: {}", s)),
+        }
+    }
+}
+
+/// Lets a frontend stop a search early from another thread or an async
+/// task, such as with a "stop thinking" button. The search then gives the
+/// best turn that it has found so far. Clones share the same flag.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// How far a search has come, for frontends to show while it runs.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Progress {
+    /// For `AlphaBeta`, the depth that was just finished. For `Mcts`, how
+    /// many turns deep the tree has grown.
+    pub depth: usize,
+    /// Positions searched by `AlphaBeta`, or playouts run by `Mcts`.
+    pub nodes: u64,
+    /// The turn that the search would choose if it stopped now.
+    pub best: Option<Turn>,
+    pub elapsed: Duration,
+}
+
+/// Tracks how much of a budget has been used during one search.
+struct Limits {
+    nodes: u64,
+    max_nodes: Option<u64>,
+    deadline: Option<Instant>,
+    cancel: CancelToken,
+}
+
+impl Limits {
+    fn new(budget: ThinkBudget, cancel: &CancelToken) -> Self {
+        Self {
+            cancel: cancel.clone(),
+            nodes: 0,
+            max_nodes: match budget {
+                ThinkBudget::Nodes(nodes) => Some(nodes),
+                _ => None,
+            },
+            deadline: match budget {
+                ThinkBudget::Millis(millis) => Some(Instant::now() + Duration::from_millis(millis)),
+                _ => None,
+            },
+        }
+    }
+
+    /// Count a visited position, and report whether the budget is used up.
+    fn spend(&mut self) -> bool {
+        self.nodes += 1;
+        #[cfg(feature = "profile")]
+        crate::profile::count_searched();
+        if let Some(max) = self.max_nodes {
+            if self.nodes > max {
+                return true;
+            }
+        }
+        // Checking the clock is slow compared to visiting a position.
+        if self.nodes & 255 != 0 {
+            return false;
+        }
+        self.cancel.is_cancelled() || self.deadline.is_some_and(|x| Instant::now() >= x)
+    }
+}
+
+/// Memory that `AlphaBeta` gives its transposition table by default.
+pub const DEFAULT_TABLE_BYTES: usize = 4 << 20;
+
+/// How a stored score relates to the real one, since alpha-beta cutoffs
+/// stop a search before it knows the exact value.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Bound {
+    Exact,
+    /// The real score is at least this much.
+    Lower,
+    /// The real score is at most this much.
+    Upper,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct Entry {
+    key: u64,
+    score: i32,
+    /// Index of the best turn among the sorted legal turns.
+    best: u16,
+    depth: u8,
+    bound: Bound,
+    generation: u8,
+}
+
+/// Scores of positions that were already searched, keyed by their Zobrist
+/// hash, so that a position reached by different orders of turns is only
+/// searched once. The table has a fixed number of slots, as many as fit in
+/// the memory it was given, and allocates them on first use. When two
+/// positions want the same slot, the newcomer wins unless the other one is
+/// from the same search and was searched deeper.
+#[derive(Clone, Eq, PartialEq)]
+pub struct TranspositionTable {
+    slots: usize,
+    entries: Vec<Option<Entry>>,
+    generation: u8,
+}
+
+impl TranspositionTable {
+    /// A table that uses at most `bytes` of memory. Too little memory for
+    /// a single entry gives a table that never stores anything.
+    pub fn new(bytes: usize) -> Self {
+        let fit = bytes / std::mem::size_of::<Option<Entry>>();
+        Self {
+            // A power of two, so that a slot is picked by masking the key.
+            slots: if fit == 0 { 0 } else { 1 << fit.ilog2() },
+            entries: vec![],
+            generation: 0,
+        }
+    }
+
+    /// Number of positions the table can hold.
+    pub fn capacity(&self) -> usize {
+        self.slots
+    }
+
+    /// Number of positions the table holds right now.
+    pub fn used(&self) -> usize {
+        self.entries.iter().filter(|x| x.is_some()).count()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Mark every entry as coming from an older search, so that they are
+    /// replaced first.
+    fn next_generation(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+    }
+
+    fn probe(&self, key: u64) -> Option<Entry> {
+        let entry = (*self
+            .entries
+            .get(key as usize & self.slots.wrapping_sub(1))?)?;
+        if entry.key == key {
+            Some(entry)
+        } else {
+            None
+        }
+    }
+
+    fn store(&mut self, entry: Entry) {
+        if self.slots == 0 {
+            return;
+        }
+        if self.entries.is_empty() {
+            self.entries = vec![None; self.slots];
+        }
+        let slot = &mut self.entries[entry.key as usize & (self.slots - 1)];
+        let replace = match slot {
+            Some(old) => {
+                old.key == entry.key
+                    || old.generation != entry.generation
+                    || old.depth <= entry.depth
+            }
+            None => true,
+        };
+        if replace {
+            *slot = Some(entry);
+        }
+    }
+}
+
+impl Default for TranspositionTable {
+    fn default() -> Self {
+        Self::new(DEFAULT_TABLE_BYTES)
+    }
+}
+
+impl std::fmt::Debug for TranspositionTable {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // Listing every slot would drown out everything else.
+        f.debug_struct("TranspositionTable")
+            .field("capacity", &self.capacity())
+            .field("used", &self.used())
+            .finish()
+    }
+}
+
+/// Finish a hash with SplitMix64, to spread out small inputs.
+fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// Key of a position and who is to move in it, for spotting repetitions.
+fn repetition_key(board: &Board, to_move: Player) -> u64 {
+    mix_repetition(board.hash(), to_move)
+}
+
+fn mix_repetition(hash: u64, to_move: Player) -> u64 {
+    hash ^ mix(to_move as u64 + 1)
+}
+
+/// What `AlphaBeta::with_contempt` needs during a search.
+#[derive(Clone, Debug)]
+struct Contempt {
+    value: i32,
+    /// Positions from earlier in the game and on the line being searched,
+    /// by `repetition_key`.
+    seen: HashSet<u64>,
+}
+
+/// Key of a position during a search. The same pieces are a different
+/// position when someone else is to move or the turn order has changed,
+/// since scores are from the point of view of `players[0]`.
+fn position_key(board: &Board, players: &[Player], mover: usize) -> u64 {
+    let order = players
+        .iter()
+        .fold(mover as u64, |key, player| mix(key ^ (*player as u64 + 1)));
+    board.hash() ^ mix(order)
+}
+
+/// Settings for `AlphaBeta::with_opening_variety`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct OpeningVariety {
+    /// Rounds of the game, counted by `Game::rounds`, to vary turns in.
+    pub rounds: usize,
+    /// How far below the best score a turn can be and still be picked.
+    pub margin: i32,
+}
+
+/// Alpha-beta search with iterative deepening, by default over
+/// `DistanceToGoal`. With more than two players, the opponents are assumed
+/// to work together against the player to move, so that there is a single
+/// score to maximize and minimize.
+///
+/// Searched positions are remembered in `table` from one turn to the next.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AlphaBeta<E = DistanceToGoal> {
+    pub budget: ThinkBudget,
+    pub evaluator: E,
+    pub table: TranspositionTable,
+    /// How to vary the opening, if at all.
+    pub variety: Option<OpeningVariety>,
+    /// How much the bot holds repetitions and stalling against itself
+    /// while it is ahead, as in `with_contempt`.
+    pub contempt: i32,
+    /// Threads to share the turns at the root between, or 0 for one per
+    /// core. Each thread beyond the first has a fresh table of the same
+    /// size, which is dropped after the search.
+    #[cfg(feature = "parallel")]
+    pub threads: usize,
+}
+
+impl AlphaBeta {
+    pub fn new(depth: usize) -> Self {
+        Self::with_evaluator(depth, DistanceToGoal)
+    }
+
+    pub fn with_difficulty(difficulty: Difficulty) -> Self {
+        Self::default().with_budget(difficulty.budget())
+    }
+}
+
+impl<E: Evaluator> AlphaBeta<E> {
+    pub fn with_evaluator(depth: usize, evaluator: E) -> Self {
+        Self {
+            budget: ThinkBudget::Depth(depth),
+            evaluator,
+            table: TranspositionTable::default(),
+            variety: None,
+            contempt: 0,
+            #[cfg(feature = "parallel")]
+            threads: 1,
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    pub fn with_budget(mut self, budget: ThinkBudget) -> Self {
+        self.budget = budget;
+        self
+    }
+
+    /// Pick at random among the turns that score within `margin` of the
+    /// best for the first `rounds` rounds of a game, so that games against
+    /// the same bot do not all open the same way. With a seed in the
+    /// config, the picks are the same every time.
+    pub fn with_opening_variety(mut self, rounds: usize, margin: i32) -> Self {
+        self.variety = Some(OpeningVariety { rounds, margin });
+        self
+    }
+
+    /// Avoid drawing a game that the bot is winning. While it scores better
+    /// than its opponents, going back to a position from earlier in the
+    /// game, which is how repetition draws come about, counts as `value`
+    /// worse than an even position, and so does a turn that gets no closer
+    /// to its target. Zero turns this off.
+    pub fn with_contempt(mut self, value: i32) -> Self {
+        self.contempt = value;
+        self
+    }
+
+    /// Cap the memory of the transposition table. Zero turns it off.
+    pub fn with_table_memory(mut self, bytes: usize) -> Self {
+        self.table = TranspositionTable::new(bytes);
+        self
+    }
+
+    /// Score of a position for `me`, from how much better `me` is doing
+    /// than the opponents. In the misère variant, getting home is bad,
+    /// so the score is flipped.
+    pub(crate) fn evaluate(&self, board: &Board, me: Player, players: &[Player]) -> i32 {
+        let opponents = players.iter().filter(|x| **x != me);
+        let theirs: i32 = opponents
+            .clone()
+            .map(|x| self.evaluator.evaluate(board, *x))
+            .sum();
+        let score = self.evaluator.evaluate(board, me) * opponents.count() as i32 - theirs;
+        match board.config().variant() {
+            Variant::Standard => score,
+            Variant::Misere => -score,
+        }
+    }
+
+    /// Score of a position, or `None` if the budget ran out first. With
+    /// `contempt`, a position that came up before in the game or on the
+    /// line being searched is scored as a draw.
+    #[allow(clippy::too_many_arguments)]
+    fn search(
+        &self,
+        board: &mut Board,
+        players: &[Player],
+        mover: usize,
+        depth: usize,
+        alpha: i32,
+        beta: i32,
+        limits: &mut Limits,
+        table: &mut TranspositionTable,
+        contempt: &mut Option<Contempt>,
+    ) -> Option<i32> {
+        let key = repetition_key(board, players[mover]);
+        match contempt {
+            Some(contempt) if contempt.seen.contains(&key) => return Some(-contempt.value),
+            Some(contempt) => {
+                contempt.seen.insert(key);
+            }
+            None => {}
+        }
+        let score = self.search_position(
+            board, players, mover, depth, alpha, beta, limits, table, contempt,
+        );
+        if let Some(contempt) = contempt {
+            contempt.seen.remove(&key);
+        }
+        score
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn search_position(
+        &self,
+        board: &mut Board,
+        players: &[Player],
+        mover: usize,
+        depth: usize,
+        mut alpha: i32,
+        mut beta: i32,
+        limits: &mut Limits,
+        table: &mut TranspositionTable,
+        contempt: &mut Option<Contempt>,
+    ) -> Option<i32> {
+        if limits.spend() {
+            return None;
+        }
+        let me = players[0];
+        let misere = board.config().variant() == Variant::Misere;
+        for player in players {
+            if board.has_player_finished(*player) {
+                // Prefer quick wins and slow losses.
+                let score = WIN + depth as i32;
+                return Some(if (*player == me) != misere {
+                    score
+                } else {
+                    -score
+                });
+            }
+        }
+        if depth == 0 {
+            return Some(self.evaluate(board, me, players));
+        }
+
+        let key = position_key(board, players, mover);
+        let (window_alpha, window_beta) = (alpha, beta);
+        let entry = table.probe(key);
+        if let Some(entry) = entry.filter(|x| usize::from(x.depth) >= depth) {
+            #[cfg(feature = "profile")]
+            crate::profile::count_table_hit();
+            match entry.bound {
+                Bound::Exact => return Some(entry.score),
+                Bound::Lower => alpha = alpha.max(entry.score),
+                Bound::Upper => beta = beta.min(entry.score),
+            }
+            if alpha >= beta {
+                return Some(entry.score);
+            }
+        }
+
+        let player = players[mover];
+        let next = (mover + 1) % players.len();
+        let mut turns = board.legal_turns(player);
+        if turns.is_empty() {
+            return self.search(
+                board,
+                players,
+                next,
+                depth - 1,
+                alpha,
+                beta,
+                limits,
+                table,
+                contempt,
+            );
+        }
+        turns.sort_by_key(|turn| -progress(board, player, turn));
+
+        // Whatever was best last time is likely to cause a cutoff.
+        let first = entry
+            .map(|x| usize::from(x.best))
+            .filter(|x| *x < turns.len());
+        let order = first
+            .into_iter()
+            .chain((0..turns.len()).filter(|x| Some(*x) != first));
+
+        let maximizing = player == me;
+        let mut best = if maximizing { i32::MIN } else { i32::MAX };
+        let mut best_index = 0;
+        for index in order {
+            let turn = &turns[index];
+            let captured = board.make_turn(turn);
+            let score = self.search(
+                board,
+                players,
+                next,
+                depth - 1,
+                alpha,
+                beta,
+                limits,
+                table,
+                contempt,
+            );
+            board.unmake_turn(turn, &captured);
+            let score = score?;
+            if (maximizing && score > best) || (!maximizing && score < best) {
+                best = score;
+                best_index = index;
+            }
+            if maximizing {
+                alpha = alpha.max(best);
+            } else {
+                beta = beta.min(best);
+            }
+            if alpha >= beta {
+                break;
+            }
+        }
+
+        table.store(Entry {
+            key,
+            score: best,
+            best: best_index.min(usize::from(u16::MAX)) as u16,
+            depth: depth.min(usize::from(u8::MAX)) as u8,
+            bound: if best <= window_alpha {
+                Bound::Upper
+            } else if best >= window_beta {
+                Bound::Lower
+            } else {
+                Bound::Exact
+            },
+            generation: table.generation,
+        });
+        Some(best)
+    }
+
+    /// Best turns at one depth with their scores, searching `turns` in
+    /// order. The first is the best, with ties going to the earlier turn,
+    /// followed by any others that scored within `margin` of it. Without a
+    /// margin, only the best is given. If the budget runs out partway, this
+    /// gives what it found among the turns searched so far along with
+    /// `false`.
+    #[allow(clippy::too_many_arguments)]
+    fn search_root(
+        &self,
+        board: &mut Board,
+        players: &[Player],
+        turns: &[Turn],
+        depth: usize,
+        margin: Option<i32>,
+        limits: &mut Limits,
+        table: &mut TranspositionTable,
+        contempt: &mut Option<Contempt>,
+    ) -> (Vec<(i32, Turn)>, bool) {
+        let mut found: Vec<(i32, Turn)> = vec![];
+        let mut best: Option<i32> = None;
+        for turn in turns {
+            // Turns within the margin need exact scores, not just bounds.
+            let alpha = match (best, margin) {
+                (Some(best), Some(margin)) => best.saturating_sub(margin).saturating_sub(1),
+                (Some(best), None) => best,
+                (None, _) => i32::MIN,
+            };
+            // Turns that get no closer are held against stalling.
+            let stalling = match contempt {
+                Some(contempt) if progress(board, players[0], turn) <= 0 => contempt.value,
+                _ => 0,
+            };
+            let captured = board.make_turn(turn);
+            let score = self.search(
+                board,
+                players,
+                1 % players.len(),
+                depth - 1,
+                alpha.saturating_add(stalling),
+                i32::MAX,
+                limits,
+                table,
+                contempt,
+            );
+            board.unmake_turn(turn, &captured);
+            let score = match score {
+                Some(score) => score.saturating_sub(stalling),
+                None => return (shortlist(found, margin), false),
+            };
+            if best.is_none_or(|x| score > x) {
+                best = Some(score);
+            }
+            found.push((score, turn.clone()));
+        }
+        (shortlist(found, margin), true)
+    }
+
+    /// Like `search_root`, but with the turns shared out between workers,
+    /// which each have their own budget and table. The best turn is the
+    /// same as with a single worker, since ties go to the earlier turn.
+    #[allow(clippy::too_many_arguments)]
+    fn search_workers(
+        &self,
+        board: &mut Board,
+        players: &[Player],
+        turns: &[Turn],
+        depth: usize,
+        margin: Option<i32>,
+        contempt: &Option<Contempt>,
+        workers: &mut [(Limits, TranspositionTable)],
+    ) -> (Vec<(i32, Turn)>, bool) {
+        #[cfg(feature = "parallel")]
+        if workers.len() > 1 {
+            use rayon::prelude::*;
+            let size = turns.len().div_ceil(workers.len()).max(1);
+            let board = &*board;
+            let results: Vec<(Vec<(i32, Turn)>, bool)> = thread_pool(workers.len()).install(|| {
+                workers
+                    .par_iter_mut()
+                    .zip(turns.par_chunks(size))
+                    .map(|((limits, table), turns)| {
+                        self.search_root(
+                            &mut board.clone(),
+                            players,
+                            turns,
+                            depth,
+                            margin,
+                            limits,
+                            table,
+                            &mut contempt.clone(),
+                        )
+                    })
+                    .collect()
+            });
+            let complete = results.iter().all(|(_, complete)| *complete);
+            // Each worker's turns come in order, so the earliest of the
+            // best turns still comes first.
+            let found = results.into_iter().flat_map(|(x, _)| x).collect();
+            return (shortlist(found, margin), complete);
+        }
+        let (limits, table) = &mut workers[0];
+        let contempt = &mut contempt.clone();
+        self.search_root(
+            board, players, turns, depth, margin, limits, table, contempt,
+        )
+    }
+
+    /// Choose a turn like `Bot::choose`, but stop early if `cancel` is
+    /// cancelled, and tell `report` after each depth is finished.
+    pub fn think(
+        &mut self,
+        game: &Game,
+        cancel: &CancelToken,
+        mut report: impl FnMut(&Progress),
+    ) -> Option<Turn> {
+        let players = turn_order(game)?;
+        let margin = self
+            .variety
+            .filter(|x| game.rounds() < x.rounds)
+            .map(|x| x.margin);
+        let contempt = self.contempt_in(game, &players);
+        let mut turns =
+            self.best_turns(&game.board, &players, margin, contempt, cancel, &mut report);
+        if turns.len() > 1 {
+            let index = rng(game).gen_range(0..turns.len());
+            return Some(turns.swap_remove(index));
+        }
+        turns.pop()
+    }
+
+    /// What `with_contempt` does in a game, if anything: only when the
+    /// player to move is doing better than the others.
+    fn contempt_in(&self, game: &Game, players: &[Player]) -> Option<Contempt> {
+        if self.contempt <= 0 || self.evaluate(&game.board, players[0], players) <= 0 {
+            return None;
+        }
+        let seen = game
+            .positions
+            .iter()
+            .filter_map(|(hash, to_move)| Some(mix_repetition(*hash, (*to_move)?)))
+            .collect();
+        Some(Contempt {
+            value: self.contempt,
+            seen,
+        })
+    }
+
+    /// Best turn for `players[0]`, with the rest moving in the given order,
+    /// followed by any others that the deepest finished search scored
+    /// within `margin` of it.
+    fn best_turns(
+        &mut self,
+        board: &Board,
+        players: &[Player],
+        margin: Option<i32>,
+        contempt: Option<Contempt>,
+        cancel: &CancelToken,
+        report: &mut dyn FnMut(&Progress),
+    ) -> Vec<Turn> {
+        let started = Instant::now();
+        let me = players[0];
+        let mut board = board.clone();
+        let mut turns = board.legal_turns(me);
+        turns.sort_by_key(|turn| -progress(&board, me, turn));
+
+        let max_depth = match self.budget {
+            ThinkBudget::Depth(depth) => depth.max(1),
+            _ => MAX_DEPTH,
+        };
+        let mut table = std::mem::replace(&mut self.table, TranspositionTable::new(0));
+        table.next_generation();
+        #[allow(unused_mut)]
+        let mut workers = vec![(Limits::new(self.budget, cancel), table)];
+        #[cfg(feature = "parallel")]
+        {
+            let count = thread_count(self.threads);
+            let bytes = workers[0].1.slots * std::mem::size_of::<Option<Entry>>();
+            for _ in 1..count {
+                workers.push((
+                    Limits::new(self.budget, cancel),
+                    TranspositionTable::new(bytes),
+                ));
+            }
+            for (limits, _) in &mut workers {
+                limits.max_nodes = limits.max_nodes.map(|x| (x / count as u64).max(1));
+            }
+        }
+        let mut best: Vec<Turn> = vec![];
+        for depth in 1..=max_depth {
+            let (found, complete) = self.search_workers(
+                &mut board,
+                players,
+                &turns,
+                depth,
+                margin,
+                &contempt,
+                &mut workers,
+            );
+            // A partial search is only trusted when no depth has finished.
+            if (complete || best.is_empty()) && !found.is_empty() {
+                best = found.into_iter().map(|(_, turn)| turn).collect();
+            }
+            if !complete {
+                break;
+            }
+            report(&Progress {
+                depth,
+                nodes: workers.iter().map(|(limits, _)| limits.nodes).sum(),
+                best: best.first().cloned(),
+                elapsed: started.elapsed(),
+            });
+            // Search the best turn first next time, for more cutoffs.
+            if let Some(index) = best.first().and_then(|x| turns.iter().position(|y| y == x)) {
+                let turn = turns.remove(index);
+                turns.insert(0, turn);
+            }
+        }
+        self.table = workers.swap_remove(0).1;
+        if best.is_empty() {
+            best.extend(turns.first().cloned());
+        }
+        best
+    }
+
+    /// The turns that the search expects to be played from here, starting
+    /// with the best turn for `players[0]`. After the first, they are read
+    /// back from the transposition table, so the line stops early where the
+    /// table lost track of it or someone would have to pass.
+    pub(crate) fn principal_variation(&mut self, board: &Board, players: &[Player]) -> Vec<Turn> {
+        let length = match self.budget {
+            ThinkBudget::Depth(depth) => depth.max(1),
+            _ => MAX_DEPTH,
+        };
+        let mut line: Vec<Turn> = self
+            .best_turns(board, players, None, None, &CancelToken::new(), &mut |_| {})
+            .into_iter()
+            .take(1)
+            .collect();
+        let mut board = board.clone();
+        if let Some(turn) = line.first() {
+            board.make_turn(turn);
+        }
+        while !line.is_empty() && line.len() < length {
+            if players.iter().any(|x| board.has_player_finished(*x)) {
+                break;
+            }
+            let mover = line.len() % players.len();
+            let player = players[mover];
+            let mut turns = board.legal_turns(player);
+            turns.sort_by_key(|turn| -progress(&board, player, turn));
+            let turn = match self
+                .table
+                .probe(position_key(&board, players, mover))
+                .and_then(|x| turns.get(usize::from(x.best)))
+            {
+                Some(turn) => turn.clone(),
+                None => break,
+            };
+            board.make_turn(&turn);
+            line.push(turn);
+        }
+        line
+    }
+}
+
+impl Default for AlphaBeta {
+    fn default() -> Self {
+        Self::new(2)
+    }
+}
+
+impl<E: Evaluator> Bot for AlphaBeta<E> {
+    fn choose(&mut self, game: &Game) -> Option<Turn> {
+        self.think(game, &CancelToken::new(), |_| {})
+    }
+}
+
+/// The earliest of the best-scoring turns, followed by the others within
+/// `margin` of it in their original order, or only the best without one.
+fn shortlist(mut found: Vec<(i32, Turn)>, margin: Option<i32>) -> Vec<(i32, Turn)> {
+    let best = match found.iter().map(|(x, _)| *x).max() {
+        Some(best) => best,
+        None => return vec![],
+    };
+    let first = found.iter().position(|(x, _)| *x == best).unwrap_or(0);
+    let top = found.remove(first);
+    let mut shortlist = vec![top];
+    if let Some(margin) = margin {
+        let cutoff = best.saturating_sub(margin);
+        shortlist.extend(found.into_iter().filter(|(x, _)| *x >= cutoff));
+    }
+    shortlist
+}
+
+/// Turn order starting with whoever is to move.
+fn turn_order(game: &Game) -> Option<Vec<Player>> {
+    let me = game.current_player()?;
+    let start = game.playing().iter().position(|x| *x == me)?;
+    Some(
+        game.playing()[start..]
+            .iter()
+            .chain(&game.playing()[..start])
+            .copied()
+            .collect(),
+    )
+}
+
+/// Monte Carlo tree search. Every player gets their own reward, and each
+/// one picks what is best for themselves, so there is no need to assume
+/// that the opponents work together like in `AlphaBeta`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Mcts {
+    /// Most playouts to run for one turn.
+    pub playouts: usize,
+    /// Stop early once this much time has been spent on one turn.
+    pub time_budget: Option<Duration>,
+    /// Number of turns in each playout before the position is scored.
+    pub playout_depth: usize,
+    /// Threads to grow separate trees on, or 0 for one per core. The
+    /// playouts are shared out between them, and the turn that was tried
+    /// most in all of the trees together is chosen.
+    #[cfg(feature = "parallel")]
+    pub threads: usize,
+}
+
+/// How often `Mcts::think` reports its progress.
+pub const PROGRESS_PLAYOUTS: usize = 100;
+
+/// Exploration weight for UCT, with rewards between 0 and 1.
+const EXPLORATION: f64 = std::f64::consts::SQRT_2;
+
+struct Node {
+    turn: Option<Turn>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    untried: Vec<Turn>,
+    /// Index of the player to move, in the search's turn order.
+    mover: usize,
+    visits: u32,
+    rewards: Vec<f64>,
+}
+
+impl Node {
+    fn new(
+        turn: Option<Turn>,
+        parent: Option<usize>,
+        untried: Vec<Turn>,
+        mover: usize,
+        players: usize,
+    ) -> Self {
+        Self {
+            turn,
+            parent,
+            children: vec![],
+            untried,
+            mover,
+            visits: 0,
+            rewards: vec![0.0; players],
+        }
+    }
+}
+
+impl Mcts {
+    pub fn new(playouts: usize) -> Self {
+        Self {
+            playouts,
+            time_budget: None,
+            playout_depth: 40,
+            #[cfg(feature = "parallel")]
+            threads: 1,
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads;
+        self
+    }
+
+    pub fn with_time_budget(mut self, budget: Duration) -> Self {
+        self.time_budget = Some(budget);
+        self
+    }
+
+    pub fn with_playout_depth(mut self, depth: usize) -> Self {
+        self.playout_depth = depth;
+        self
+    }
+
+    /// Reward for each player, from 1 for being ahead of everyone to 0 for
+    /// being behind everyone. Finishing beats any distance, and the order
+    /// is flipped in the misère variant.
+    fn rewards(board: &Board, players: &[Player]) -> Vec<f64> {
+        let misere = board.config().variant() == Variant::Misere;
+        let scores: Vec<i32> = players
+            .iter()
+            .map(|x| match (board.has_player_finished(*x), misere) {
+                (true, false) => i32::MIN,
+                (true, true) => i32::MAX,
+                (false, false) => distance_to_goal(board, *x),
+                (false, true) => -distance_to_goal(board, *x),
+            })
+            .collect();
+        let others = (players.len().max(2) - 1) as f64;
+        scores
+            .iter()
+            .map(|mine| {
+                let ahead = scores.iter().filter(|x| *x < mine).count();
+                (others - ahead as f64) / others
+            })
+            .collect()
+    }
+
+    /// Play random turns, favoring ones that move forward, then score the
+    /// position and put the board back as it was.
+    fn playout(
+        &self,
+        board: &mut Board,
+        players: &[Player],
+        mut mover: usize,
+        rng: &mut impl Rng,
+    ) -> Vec<f64> {
+        #[cfg(feature = "profile")]
+        crate::profile::count_playout();
+        let mut made = vec![];
+        for _ in 0..self.playout_depth {
+            if players.iter().any(|x| board.has_player_finished(*x)) {
+                break;
+            }
+            let player = players[mover];
+            mover = (mover + 1) % players.len();
+            let turns = board.legal_turns(player);
+            let forward: Vec<&Turn> = turns
+                .iter()
+                .filter(|turn| progress(board, player, turn) > 0)
+                .collect();
+            let turn = match forward.choose(rng) {
+                Some(turn) => (*turn).clone(),
+                None => match turns.choose(rng) {
+                    Some(turn) => turn.clone(),
+                    None => continue,
+                },
+            };
+            let captured = board.make_turn(&turn);
+            made.push((turn, captured));
+        }
+        let rewards = Self::rewards(board, players);
+        for (turn, captured) in made.iter().rev() {
+            board.unmake_turn(turn, captured);
+        }
+        rewards
+    }
+
+    fn select(nodes: &[Node], parent: usize) -> usize {
+        let node = &nodes[parent];
+        let log = f64::from(node.visits).ln();
+        let score = |child: usize| {
+            let child = &nodes[child];
+            let visits = f64::from(child.visits);
+            child.rewards[node.mover] / visits + EXPLORATION * (log / visits).sqrt()
+        };
+        node.children
+            .iter()
+            .copied()
+            .max_by(|a, b| score(*a).total_cmp(&score(*b)))
+            .unwrap_or(parent)
+    }
+}
+
+impl Default for Mcts {
+    fn default() -> Self {
+        Self::new(1000)
+    }
+}
+
+impl Mcts {
+    /// Choose a turn like `Bot::choose`, but stop early if `cancel` is
+    /// cancelled, and tell `report` every `PROGRESS_PLAYOUTS` playouts.
+    /// With more than one thread, progress is only reported once all of
+    /// them are done.
+    pub fn think(
+        &mut self,
+        game: &Game,
+        cancel: &CancelToken,
+        mut report: impl FnMut(&Progress),
+    ) -> Option<Turn> {
+        let players = turn_order(game)?;
+        let mut rng = rng(game);
+        let started = Instant::now();
+        #[cfg(feature = "parallel")]
+        {
+            let count = thread_count(self.threads);
+            if count > 1 {
+                let (tried, depth) =
+                    self.grow_parallel(game, &players, count, started, cancel, &mut rng);
+                let best = most_tried(&tried);
+                report(&Progress {
+                    depth,
+                    nodes: tried.iter().map(|(_, visits)| u64::from(*visits)).sum(),
+                    best: best.clone(),
+                    elapsed: started.elapsed(),
+                });
+                return best;
+            }
+        }
+        let mut board = game.board.clone();
+        let playouts = self.playouts;
+        let (tried, _) = self.grow(
+            &mut board,
+            &players,
+            playouts,
+            started,
+            cancel,
+            &mut rng,
+            &mut report,
+        );
+        most_tried(&tried)
+    }
+
+    /// Grow a tree from `board` with up to `playouts` playouts, and give
+    /// back every turn at the root with how many times it was tried, along
+    /// with how deep the tree grew.
+    #[allow(clippy::too_many_arguments)]
+    fn grow(
+        &self,
+        board: &mut Board,
+        players: &[Player],
+        playouts: usize,
+        started: Instant,
+        cancel: &CancelToken,
+        rng: &mut impl Rng,
+        report: &mut dyn FnMut(&Progress),
+    ) -> (Vec<(Turn, u32)>, usize) {
+        let mut nodes = vec![Node::new(
+            None,
+            None,
+            board.legal_turns(players[0]),
+            0,
+            players.len(),
+        )];
+        if nodes[0].untried.len() <= 1 {
+            return (nodes[0].untried.drain(..).map(|x| (x, 0)).collect(), 0);
+        }
+
+        let root = |nodes: &[Node]| -> Vec<(Turn, u32)> {
+            nodes[0]
+                .children
+                .iter()
+                .filter_map(|child| Some((nodes[*child].turn.clone()?, nodes[*child].visits)))
+                .chain(nodes[0].untried.iter().map(|x| (x.clone(), 0)))
+                .collect()
+        };
+        let mut depth = 0;
+        for played in 0..playouts {
+            if let Some(budget) = self.time_budget {
+                if started.elapsed() >= budget {
+                    break;
+                }
+            }
+            if cancel.is_cancelled() {
+                break;
+            }
+            if played > 0 && played % PROGRESS_PLAYOUTS == 0 {
+                report(&Progress {
+                    depth,
+                    nodes: played as u64,
+                    best: most_tried(&root(&nodes)),
+                    elapsed: started.elapsed(),
+                });
+            }
+
+            // Walk down through fully expanded nodes.
+            let mut current = 0;
+            let mut path = vec![];
+            while nodes[current].untried.is_empty() && !nodes[current].children.is_empty() {
+                current = Self::select(&nodes, current);
+                if let Some(turn) = &nodes[current].turn {
+                    let captured = board.make_turn(turn);
+                    path.push((turn.clone(), captured));
+                }
+            }
+
+            // Add one new child, unless someone has already finished.
+            if !nodes[current].untried.is_empty() {
+                let index = rng.gen_range(0..nodes[current].untried.len());
+                let turn = nodes[current].untried.swap_remove(index);
+                let captured = board.make_turn(&turn);
+                path.push((turn.clone(), captured));
+
+                let mover = (nodes[current].mover + 1) % players.len();
+                let untried = if players.iter().any(|x| board.has_player_finished(*x)) {
+                    vec![]
+                } else {
+                    board.legal_turns(players[mover])
+                };
+                nodes.push(Node::new(
+                    Some(turn),
+                    Some(current),
+                    untried,
+                    mover,
+                    players.len(),
+                ));
+                let child = nodes.len() - 1;
+                nodes[current].children.push(child);
+                current = child;
+            }
+
+            depth = depth.max(path.len());
+            let rewards = self.playout(board, players, nodes[current].mover, rng);
+            for (turn, captured) in path.iter().rev() {
+                board.unmake_turn(turn, captured);
+            }
+
+            let mut node = Some(current);
+            while let Some(index) = node {
+                nodes[index].visits += 1;
+                for (total, reward) in nodes[index].rewards.iter_mut().zip(&rewards) {
+                    *total += reward;
+                }
+                node = nodes[index].parent;
+            }
+        }
+
+        (root(&nodes), depth)
+    }
+}
+
+#[cfg(feature = "parallel")]
+impl Mcts {
+    /// Grow a tree on each of `count` threads, with the playouts shared out
+    /// between them, and add up how often each turn was tried.
+    fn grow_parallel(
+        &self,
+        game: &Game,
+        players: &[Player],
+        count: usize,
+        started: Instant,
+        cancel: &CancelToken,
+        rng: &mut impl Rng,
+    ) -> (Vec<(Turn, u32)>, usize) {
+        use rayon::prelude::*;
+        let seeds: Vec<u64> = (0..count).map(|_| rng.gen()).collect();
+        let trees: Vec<(Vec<(Turn, u32)>, usize)> = thread_pool(count).install(|| {
+            seeds
+                .par_iter()
+                .enumerate()
+                .map(|(index, seed)| {
+                    let playouts =
+                        self.playouts / count + usize::from(index < self.playouts % count);
+                    let mut board = game.board.clone();
+                    let mut rng = StdRng::seed_from_u64(*seed);
+                    self.grow(
+                        &mut board,
+                        players,
+                        playouts,
+                        started,
+                        cancel,
+                        &mut rng,
+                        &mut |_| {},
+                    )
+                })
+                .collect()
+        });
+        let mut tried = trees[0].0.clone();
+        for (tree, _) in &trees[1..] {
+            for (turn, visits) in tree {
+                if let Some(x) = tried.iter_mut().find(|(x, _)| x == turn) {
+                    x.1 += visits;
+                }
+            }
+        }
+        let depth = trees.iter().map(|(_, depth)| *depth).max().unwrap_or(0);
+        (tried, depth)
+    }
+}
+
+impl Bot for Mcts {
+    fn choose(&mut self, game: &Game) -> Option<Turn> {
+        self.think(game, &CancelToken::new(), |_| {})
+    }
+}
+
+/// The turn that was tried the most. Ties go to the last turn, which is the
+/// one left untried for a tree without any playouts.
+fn most_tried(tried: &[(Turn, u32)]) -> Option<Turn> {
+    tried
+        .iter()
+        .max_by_key(|(_, visits)| *visits)
+        .map(|(turn, _)| turn.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, DrawReason, GameError, GameState, Point};
+
+    #[test]
+    fn test_distance_to_goal() {
+        let mut board = Board::empty(Config::default().with_player_lines(1));
+        assert_eq!(distance_to_goal(&board, Player::Head), 0);
+        board.set_piece(Point::new(1, 4), Player::Head).unwrap();
+        assert_eq!(distance_to_goal(&board, Player::Head), 4);
+        board.set_piece(Point::new(3, 6), Player::Head).unwrap();
+        assert_eq!(distance_to_goal(&board, Player::Head), 6);
+    }
+
+    #[test]
+    fn test_evaluators() {
+        let mut board = Board::empty(Config::default().with_player_lines(1));
+        board.set_piece(Point::new(1, 4), Player::Head).unwrap();
+        board.set_piece(Point::new(3, 6), Player::Head).unwrap();
+        assert_eq!(DistanceToGoal.evaluate(&board, Player::Head), -6);
+        assert_eq!(Cohesion.evaluate(&board, Player::Head), -2);
+        assert_eq!(BackmostPiece.evaluate(&board, Player::Head), -4);
+        let blend = |board: &Board, player| {
+            DistanceToGoal.evaluate(board, player) + 2 * Cohesion.evaluate(board, player)
+        };
+        assert_eq!(blend.evaluate(&board, Player::Head), -10);
+        assert_eq!(BackmostPiece.evaluate(&board, Player::Tail), 0);
+    }
+
+    #[test]
+    fn test_random_bot() {
+        let mut game = Game::new(Board::default(), vec![Player::Head, Player::Tail]);
+        for _ in 0..20 {
+            assert!(game.play_bot(&mut RandomBot).is_ok());
+        }
+        assert_eq!(game.history().len(), 20);
+    }
+
+    #[test]
+    fn test_seeded_bots() {
+        let config = Config::default()
+            .with_layout(crate::StartLayout::TwoPlayers)
+            .with_seed(7);
+        let play = |bot: &mut dyn Bot| {
+            let mut game = Game::new(Board::new(config.clone()), vec![Player::Head, Player::Tail]);
+            for _ in 0..10 {
+                game.play_bot(bot).unwrap();
+            }
+            game.history().to_vec()
+        };
+        assert_eq!(play(&mut RandomBot), play(&mut RandomBot));
+        assert_eq!(play(&mut Mcts::new(20)), play(&mut Mcts::new(20)));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_threads() {
+        let config = Config::default()
+            .with_layout(crate::StartLayout::TwoPlayers)
+            .with_seed(5);
+        let mut game = Game::new(Board::new(config), vec![Player::Head, Player::Tail]);
+        for _ in 0..6 {
+            game.play_bot(&mut GreedyBot).unwrap();
+        }
+        for threads in [2, 3, 0] {
+            assert_eq!(
+                AlphaBeta::new(3).with_threads(threads).choose(&game),
+                AlphaBeta::new(3).choose(&game)
+            );
+        }
+        let legal = game.board.legal_turns(Player::Head);
+        let mut bot = Mcts::new(100).with_threads(4);
+        let turn = bot.choose(&game).unwrap();
+        assert!(legal.contains(&turn));
+        assert_eq!(bot.choose(&game), Some(turn));
+    }
+
+    #[test]
+    fn test_greedy_bot() {
+        let mut board = Board::empty(Config::default().with_player_lines(1));
+        board.set_piece(Point::new(3, 2), Player::Head).unwrap();
+        board.set_piece(Point::new(4, 3), Player::Tail).unwrap();
+        let game = Game::new(board, vec![Player::Head, Player::Tail]);
+        assert_eq!(GreedyBot.choose(&game), Some("3/2>5/4".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_alpha_beta_with_evaluator() {
+        let mut game = Game::new(Board::default(), vec![Player::Head, Player::Tail]);
+        let mut bot = AlphaBeta::with_evaluator(1, BackmostPiece);
+        assert!(game.play_bot(&mut bot).is_ok());
+    }
+
+    #[test]
+    fn test_alpha_beta_takes_the_win() {
+        let mut board = Board::empty(Config::default().with_player_lines(1));
+        board.set_piece(Point::new(4, 3), Player::Head).unwrap();
+        board.set_piece(Point::new(3, 4), Player::Tail).unwrap();
+        let game = Game::new(board, vec![Player::Head, Player::Tail]);
+        assert_eq!(
+            AlphaBeta::new(2).choose(&game),
+            Some("4/3-5/4".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_alpha_beta_avoids_finishing_in_misere() {
+        let config = Config::default()
+            .with_player_lines(1)
+            .with_variant(Variant::Misere);
+        let mut board = Board::empty(config);
+        board.set_piece(Point::new(4, 3), Player::Head).unwrap();
+        board.set_piece(Point::new(2, 3), Player::Tail).unwrap();
+        let game = Game::new(board, vec![Player::Head, Player::Tail]);
+        let turn = AlphaBeta::new(1).choose(&game).unwrap();
+        assert_ne!(turn.target(), Point::new(5, 4));
+    }
+
+    #[test]
+    fn test_alpha_beta_with_budget() {
+        let game = Game::new(Board::default(), vec![Player::Head, Player::Tail]);
+        for budget in &[
+            ThinkBudget::Depth(0),
+            ThinkBudget::Nodes(0),
+            ThinkBudget::Nodes(500),
+            ThinkBudget::Millis(50),
+        ] {
+            let mut bot = AlphaBeta::default().with_budget(*budget);
+            let started = Instant::now();
+            let turn = bot.choose(&game).unwrap();
+            assert!(game.board.legal_turns(Player::Head).contains(&turn));
+            assert!(started.elapsed() < Duration::from_secs(5));
+        }
+    }
+
+    #[test]
+    fn test_opening_variety() {
+        let game = |seed| {
+            let config = Config::default()
+                .with_layout(crate::StartLayout::TwoPlayers)
+                .with_seed(seed);
+            Game::new(Board::new(config), vec![Player::Head, Player::Tail])
+        };
+        let plain = AlphaBeta::new(2).choose(&game(0));
+        let mut bot = AlphaBeta::new(2).with_opening_variety(1, 20);
+        let turns: Vec<Turn> = (0..20).filter_map(|x| bot.choose(&game(x))).collect();
+        assert!(turns.iter().any(|x| Some(x) != plain.as_ref()));
+        assert!(turns
+            .iter()
+            .all(|x| game(0).board.legal_turns(Player::Head).contains(x)));
+        // The same seed gives the same pick.
+        assert_eq!(bot.choose(&game(3)), Some(turns[3].clone()));
+
+        // After the opening, it plays like it would without the option.
+        let mut later = game(0);
+        later.play_turn(&"4/10-5/11".parse().unwrap()).unwrap();
+        later.play_turn(&"14/16-13/15".parse().unwrap()).unwrap();
+        assert_eq!(bot.choose(&later), AlphaBeta::new(2).choose(&later));
+
+        // A winning turn is far better than the rest.
+        let mut board = Board::empty(Config::default().with_player_lines(1));
+        board.set_piece(Point::new(4, 3), Player::Head).unwrap();
+        board.set_piece(Point::new(3, 4), Player::Tail).unwrap();
+        let game = Game::new(board, vec![Player::Head, Player::Tail]);
+        assert_eq!(bot.choose(&game), Some("4/3-5/4".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_contempt() {
+        let play = |contempt| {
+            let config = Config::default()
+                .with_player_lines(3)
+                .with_layout(crate::StartLayout::TwoPlayers)
+                .with_repetition_limit(3)
+                .with_progress_limit(40);
+            let mut game = Game::new(Board::new(config), vec![Player::Head, Player::Tail]);
+            let mut bots: Vec<Box<dyn Bot>> = vec![
+                Box::new(AlphaBeta::new(2).with_contempt(contempt)),
+                Box::new(AlphaBeta::new(1)),
+            ];
+            game.play_bots(&mut bots, 400, |_, _| {}).unwrap();
+            game.state()
+        };
+        // Once Tail blocks its target, Head shuffles back and forth.
+        assert_eq!(play(0), GameState::Drawn(DrawReason::Repetition));
+        assert_ne!(play(50), GameState::Drawn(DrawReason::Repetition));
+
+        // Only a bot that is ahead holds draws against itself.
+        let game = Game::new(Board::default(), vec![Player::Head, Player::Tail]);
+        let players = [Player::Head, Player::Tail];
+        assert!(AlphaBeta::new(2).contempt_in(&game, &players).is_none());
+        assert!(AlphaBeta::new(2)
+            .with_contempt(50)
+            .contempt_in(&game, &players)
+            .is_none());
+        let mut game = game;
+        game.play_turn(&"4/10-5/11".parse().unwrap()).unwrap();
+        let bot = AlphaBeta::new(2).with_contempt(50);
+        let contempt = bot.contempt_in(&game, &players).unwrap();
+        assert_eq!(contempt.seen.len(), 2);
+        assert!(bot
+            .contempt_in(&game, &[Player::Tail, Player::Head])
+            .is_none());
+    }
+
+    #[test]
+    fn test_iterative_deepening_matches_fixed_depth() {
+        let mut board = Board::empty(Config::default().with_player_lines(1));
+        board.set_piece(Point::new(4, 3), Player::Head).unwrap();
+        board.set_piece(Point::new(3, 4), Player::Tail).unwrap();
+        let game = Game::new(board, vec![Player::Head, Player::Tail]);
+        let mut bot = AlphaBeta::default().with_budget(ThinkBudget::Nodes(10_000));
+        assert_eq!(bot.choose(&game), Some("4/3-5/4".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_transposition_table() {
+        let entry = |key, depth, generation| Entry {
+            key,
+            score: depth as i32,
+            best: 0,
+            depth,
+            bound: Bound::Exact,
+            generation,
+        };
+        let mut table = TranspositionTable::new(std::mem::size_of::<Option<Entry>>() * 5);
+        assert_eq!(table.capacity(), 4);
+        assert_eq!(table.probe(1), None);
+
+        table.store(entry(1, 3, 0));
+        assert_eq!(table.probe(1), Some(entry(1, 3, 0)));
+        assert_eq!(table.probe(5), None);
+
+        // Keys 1 and 5 share a slot. The deeper entry stays until it is
+        // from an older search.
+        table.store(entry(5, 2, 0));
+        assert_eq!(table.probe(1), Some(entry(1, 3, 0)));
+        table.store(entry(5, 2, 1));
+        assert_eq!(table.probe(5), Some(entry(5, 2, 1)));
+        assert_eq!(table.probe(1), None);
+        assert_eq!(table.used(), 1);
+
+        table.clear();
+        assert_eq!(table.used(), 0);
+        let mut off = TranspositionTable::new(0);
+        off.store(entry(1, 3, 0));
+        assert_eq!(off.probe(1), None);
+    }
+
+    #[test]
+    fn test_alpha_beta_with_table() {
+        let mut board = Board::empty(Config::default().with_player_lines(1));
+        board.set_piece(Point::new(4, 3), Player::Head).unwrap();
+        board.set_piece(Point::new(3, 4), Player::Tail).unwrap();
+        let game = Game::new(board, vec![Player::Head, Player::Tail]);
+        let mut bot = AlphaBeta::new(4);
+        assert_eq!(bot.choose(&game), Some("4/3-5/4".parse().unwrap()));
+        assert!(bot.table.used() > 0);
+        assert_eq!(bot.choose(&game), Some("4/3-5/4".parse().unwrap()));
+
+        let mut bot = AlphaBeta::new(4).with_table_memory(0);
+        assert_eq!(bot.choose(&game), Some("4/3-5/4".parse().unwrap()));
+        assert_eq!(bot.table.used(), 0);
+    }
+
+    #[test]
+    fn test_difficulty() {
+        assert_eq!("Hard".parse(), Ok(Difficulty::Hard));
+        assert!("impossible".parse::<Difficulty>().is_err());
+        assert_eq!(
+            AlphaBeta::with_difficulty(Difficulty::Easy).budget,
+            ThinkBudget::Depth(1)
+        );
+    }
+
+    #[test]
+    fn test_play_bot() {
+        let mut game = Game::new(Board::default(), vec![Player::Head, Player::Tail]);
+        let before = distance_to_goal(&game.board, Player::Head);
+        let turn = game.play_bot(&mut AlphaBeta::default()).unwrap();
+        assert_eq!(game.history()[0].turn, turn);
+        assert!(distance_to_goal(&game.board, Player::Head) < before);
+
+        let mut game = Game::new(
+            Board::empty(Config::default()),
+            vec![Player::Head, Player::Tail],
+        );
+        assert_eq!(
+            game.play_bot(&mut AlphaBeta::default()),
+            Err(GameError::NoLegalMoves)
+        );
+    }
+
+    #[test]
+    fn test_mcts_takes_the_win() {
+        let mut board = Board::empty(Config::default().with_player_lines(1));
+        board.set_piece(Point::new(4, 3), Player::Head).unwrap();
+        board.set_piece(Point::new(3, 4), Player::Tail).unwrap();
+        let game = Game::new(board, vec![Player::Head, Player::Tail]);
+        assert_eq!(
+            Mcts::new(200).choose(&game),
+            Some("4/3-5/4".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn test_mcts_rewards() {
+        let mut board = Board::empty(Config::default().with_player_lines(1));
+        board.set_piece(Point::new(5, 4), Player::Head).unwrap();
+        board.set_piece(Point::new(3, 4), Player::Tail).unwrap();
+        let players = [Player::Head, Player::Tail];
+        assert_eq!(Mcts::rewards(&board, &players), vec![1.0, 0.0]);
+        board.config.variant = Variant::Misere;
+        assert_eq!(Mcts::rewards(&board, &players), vec![0.0, 1.0]);
+    }
+
+    #[test]
+    fn test_mcts_with_time_budget() {
+        let players = vec![Player::Head, Player::LeftHand, Player::RightFoot];
+        let mut game = Game::new(Board::default(), players);
+        let mut bot = Mcts::new(usize::MAX).with_time_budget(Duration::from_millis(50));
+        let started = Instant::now();
+        assert!(game.play_bot(&mut bot).is_ok());
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn test_think() {
+        let game = Game::new(Board::default(), vec![Player::Head, Player::Tail]);
+        let mut reports = vec![];
+        let turn = AlphaBeta::new(3).think(&game, &CancelToken::new(), |x| {
+            reports.push(x.clone());
+        });
+        let depths: Vec<usize> = reports.iter().map(|x| x.depth).collect();
+        assert_eq!(depths, vec![1, 2, 3]);
+        assert!(reports.windows(2).all(|x| x[0].nodes < x[1].nodes));
+        assert_eq!(reports[2].best, turn);
+
+        let mut bot = Mcts::new(PROGRESS_PLAYOUTS * 2 + 1);
+        let mut reports = vec![];
+        assert!(bot
+            .think(&game, &CancelToken::new(), |x| reports.push(x.nodes))
+            .is_some());
+        assert_eq!(reports, vec![100, 200]);
+    }
+
+    #[test]
+    fn test_cancel() {
+        let game = Game::new(Board::default(), vec![Player::Head, Player::Tail]);
+        let cancel = CancelToken::new();
+        assert!(!cancel.is_cancelled());
+        let stopper = {
+            let cancel = cancel.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(50));
+                cancel.cancel();
+            })
+        };
+        let started = Instant::now();
+        let mut bot = AlphaBeta::new(0).with_budget(ThinkBudget::Millis(600_000));
+        assert!(bot.think(&game, &cancel, |_| {}).is_some());
+        assert!(started.elapsed() < Duration::from_secs(30));
+        stopper.join().unwrap();
+
+        let mut reports = 0;
+        let mut bot = Mcts::new(usize::MAX);
+        assert!(bot.think(&game, &cancel, |_| reports += 1).is_some());
+        assert_eq!(reports, 0);
+    }
+}
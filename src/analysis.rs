@@ -0,0 +1,325 @@
+//! Evaluating positions for analysis tools, such as an eval bar and the
+//! line that the computer expects to be played.
+//!
+//! `evaluate` and `best_line` use the same evaluation and search as
+//! `ai::AlphaBeta`. A board
+//! does not know the turn order, so the players still in play are taken to
+//! move in the order of `StartLayout::SixPlayers`, starting from `player`.
+//! `fastest_finish` is for solitaire puzzles instead, where nobody else
+//! moves at all.
+
+use crate::ai::AlphaBeta;
+use crate::{corner_line, Board, Cell, Player, Point, StartLayout, Turn, Variant, WinCondition};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+/// How well a player is doing. Scores compare from worst to best, with
+/// `Lost` below every `Advantage` and `Won` above.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Score {
+    /// The player is out, by finishing in the misère variant.
+    Lost,
+    /// How far ahead of the opponents the player is, or behind if negative.
+    Advantage(i32),
+    /// The player has finished.
+    Won,
+}
+
+/// Score a position for a player without searching ahead.
+pub fn evaluate(board: &Board, player: Player) -> Score {
+    if board.has_player_finished(player) {
+        return match board.config().variant() {
+            Variant::Standard => Score::Won,
+            Variant::Misere => Score::Lost,
+        };
+    }
+    Score::Advantage(AlphaBeta::default().evaluate(board, player, &turn_order(board, player)))
+}
+
+/// The turns that the computer expects to be played over the next `depth`
+/// turns, starting with the best one for `player`. The line is shorter if
+/// the game would end first or someone would have to pass, and empty if
+/// `player` has no legal turn.
+pub fn best_line(board: &Board, player: Player, depth: usize) -> Vec<Turn> {
+    AlphaBeta::new(depth).principal_variation(board, &turn_order(board, player))
+}
+
+/// The fewest turns that the piece at `from` needs to reach `to` if every
+/// other piece stays where it is, counting a step or a whole jump chain as
+/// one turn each. Gives `None` if there is no piece at `from` or it can
+/// never get there.
+pub fn hop_distance(board: &Board, from: Point, to: Point) -> Option<u32> {
+    let player = board.get_piece(from)?.player()?;
+    if from == to {
+        return Some(0);
+    }
+    let mut board = board.clone();
+    board
+        .set_piece(from, Cell::Empty)
+        .expect("the piece is on the board");
+
+    let mut distances = HashMap::new();
+    distances.insert(from, 0);
+    let mut queue = VecDeque::new();
+    queue.push_back(from);
+    while let Some(point) = queue.pop_front() {
+        let distance = distances[&point] + 1;
+        board
+            .set_piece(point, player)
+            .expect("reached spots are on the board");
+        let turns = board.destinations(point);
+        board
+            .set_piece(point, Cell::Empty)
+            .expect("reached spots are on the board");
+        for turn in turns {
+            let target = turn.target();
+            if target == to {
+                return Some(distance);
+            }
+            if let Entry::Vacant(entry) = distances.entry(target) {
+                entry.insert(distance);
+                queue.push_back(target);
+            }
+        }
+    }
+    None
+}
+
+/// The fewest turns that `player` needs to finish if nobody else moves, as
+/// in solitaire puzzles, along with one way to do it. Gives `None` if it
+/// takes more than `max_turns` or cannot be done at all.
+pub fn fastest_finish(board: &Board, player: Player, max_turns: usize) -> Option<Vec<Turn>> {
+    let mut board = board.clone();
+    let mut line = vec![];
+    for turns in 0..=max_turns {
+        if finish_within(&mut board, player, turns, &mut line, &mut HashMap::new()) {
+            return Some(line);
+        }
+    }
+    None
+}
+
+/// Search for a way to finish in exactly `turns` or fewer, leaving it in
+/// `line`. `searched` keeps the most turns that each position has already
+/// failed with, since it cannot do better with the same number or fewer.
+fn finish_within(
+    board: &mut Board,
+    player: Player,
+    turns: usize,
+    line: &mut Vec<Turn>,
+    searched: &mut HashMap<u64, usize>,
+) -> bool {
+    let needed = turns_to_finish(board, player);
+    if needed == 0 {
+        return true;
+    }
+    if needed > turns || searched.get(&board.hash()).is_some_and(|x| *x >= turns) {
+        return false;
+    }
+    for turn in board.legal_turns(player) {
+        let captured = board.make_turn(&turn);
+        line.push(turn.clone());
+        let found = finish_within(board, player, turns - 1, line, searched);
+        board.unmake_turn(&turn, &captured);
+        if found {
+            return true;
+        }
+        line.pop();
+    }
+    searched.insert(board.hash(), turns);
+    false
+}
+
+/// At least how many more turns `player` needs to finish. Each turn only
+/// lands one piece, so every empty spot of the target needs a turn of its
+/// own.
+fn turns_to_finish(board: &Board, player: Player) -> usize {
+    if board.has_player_finished(player) {
+        return 0;
+    }
+    let config = board.config();
+    let needed = match config.win_condition() {
+        WinCondition::FillTarget => {
+            let (target, lines) = (config.target_of(player), config.lines_for(player));
+            board
+                .rows()
+                .enumerate()
+                .map(|(row, cells)| {
+                    cells
+                        .iter()
+                        .enumerate()
+                        .filter(|(column, cell)| {
+                            cell.is_empty()
+                                && match corner_line(
+                                    config.player_lines as usize,
+                                    row,
+                                    *column,
+                                    cells.len(),
+                                ) {
+                                    Some((corner, line)) => corner == target && line < lines,
+                                    None => false,
+                                }
+                        })
+                        .count()
+                })
+                .sum()
+        }
+        WinCondition::FirstNPiecesHome(n) => n.saturating_sub(board.pieces_home(player)),
+        WinCondition::ScoreAtTurnLimit(_) | WinCondition::Elimination => 0,
+    };
+    needed.max(1)
+}
+
+/// How many different ways there are to play the next `depth` turns,
+/// starting with `player`, for checking move generation against known
+/// counts. Players without a legal turn end the line there, so it does
+/// not count toward the total.
+pub fn perft(board: &Board, player: Player, depth: usize) -> u64 {
+    let mut board = board.clone();
+    let players = turn_order(&board, player);
+    count_lines(&mut board, &players, 0, depth)
+}
+
+fn count_lines(board: &mut Board, players: &[Player], mover: usize, depth: usize) -> u64 {
+    if depth == 0 {
+        return 1;
+    }
+    let turns = board.legal_turns(players[mover]);
+    if depth == 1 {
+        return turns.len() as u64;
+    }
+    let next = (mover + 1) % players.len();
+    turns
+        .iter()
+        .map(|turn| {
+            let captured = board.make_turn(turn);
+            let count = count_lines(board, players, next, depth - 1);
+            board.unmake_turn(turn, &captured);
+            count
+        })
+        .sum()
+}
+
+/// Players with pieces in play, in turn order starting from `player`.
+fn turn_order(board: &Board, player: Player) -> Vec<Player> {
+    let players: Vec<Player> = StartLayout::SixPlayers
+        .players()
+        .into_iter()
+        .filter(|x| *x == player || board.pieces_of(*x).next().is_some())
+        .collect();
+    let start = players
+        .iter()
+        .position(|x| *x == player)
+        .expect("player is kept");
+    players[start..]
+        .iter()
+        .chain(&players[..start])
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, Point, WinCondition};
+
+    #[test]
+    fn test_evaluate() {
+        let mut board = Board::default();
+        assert_eq!(evaluate(&board, Player::Head), Score::Advantage(0));
+        board
+            .take_turn(&"4/10-5/11".parse().unwrap(), Player::Head)
+            .unwrap();
+        assert!(evaluate(&board, Player::Head) > Score::Advantage(0));
+        assert!(evaluate(&board, Player::Tail) < Score::Advantage(0));
+
+        let goal = Point::new(17, 13);
+        let config = Config::default().with_win_condition(WinCondition::FirstNPiecesHome(1));
+        let board = Board::from_setup(&[(goal, Player::Head)], config).unwrap();
+        assert_eq!(evaluate(&board, Player::Head), Score::Won);
+        assert!(Score::Lost < Score::Advantage(i32::MIN));
+        assert!(Score::Won > Score::Advantage(i32::MAX));
+    }
+
+    #[test]
+    fn test_best_line() {
+        let mut board = Board::new(Config::default().with_layout(StartLayout::TwoPlayers));
+        let line = best_line(&board, Player::Head, 3);
+        assert_eq!(line.len(), 3);
+        for (turn, player) in line.iter().zip(&[Player::Head, Player::Tail, Player::Head]) {
+            board.take_turn(turn, *player).unwrap();
+        }
+        assert!(best_line(&Board::empty(Config::default()), Player::Head, 3).is_empty());
+    }
+
+    #[test]
+    fn test_hop_distance() {
+        let (from, to) = (Point::new(9, 13), Point::new(9, 17));
+        let board = Board::from_setup(&[(from, Player::Head)], Config::default()).unwrap();
+        assert_eq!(hop_distance(&board, from, to), Some(2));
+        assert_eq!(hop_distance(&board, from, from), Some(0));
+        assert_eq!(hop_distance(&board, to, from), None);
+
+        let setup = [(from, Player::Head), (Point::new(9, 15), Player::Tail)];
+        let board = Board::from_setup(&setup, Config::default()).unwrap();
+        assert_eq!(hop_distance(&board, from, to), Some(1));
+        assert_eq!(hop_distance(&board, from, Point::new(9, 15)), None);
+
+        let board = Board::default();
+        assert_eq!(
+            hop_distance(&board, Point::new(4, 10), Point::new(5, 11)),
+            Some(1)
+        );
+        assert_eq!(
+            hop_distance(&board, Point::new(1, 13), Point::new(5, 11)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_fastest_finish() {
+        let config = Config::default().with_win_condition(WinCondition::FirstNPiecesHome(1));
+        let from = Point::new(9, 13);
+        let board = Board::from_setup(&[(from, Player::Head)], config.clone()).unwrap();
+        let line = fastest_finish(&board, Player::Head, 10).unwrap();
+        assert_eq!(line.len(), 5);
+        let mut finished = board.clone();
+        for turn in &line {
+            finished.take_turn(turn, Player::Head).unwrap();
+        }
+        assert!(finished.has_player_finished(Player::Head));
+        assert_eq!(
+            hop_distance(&board, from, line.last().unwrap().target()),
+            Some(5)
+        );
+        assert_eq!(fastest_finish(&board, Player::Head, 4), None);
+        assert_eq!(fastest_finish(&finished, Player::Head, 0), Some(vec![]));
+
+        // Another piece to jump over saves a turn.
+        let setup = [(from, Player::Head), (Point::new(10, 14), Player::Tail)];
+        let board = Board::from_setup(&setup, config).unwrap();
+        assert_eq!(fastest_finish(&board, Player::Head, 10).unwrap().len(), 4);
+
+        let board = Board::empty(Config::default());
+        assert_eq!(fastest_finish(&board, Player::Head, 3), None);
+    }
+
+    #[test]
+    fn test_perft() {
+        let point = Point::new(9, 13);
+        let board = Board::from_setup(&[(point, Player::Head)], Config::default()).unwrap();
+        assert_eq!(perft(&board, Player::Head, 0), 1);
+        assert_eq!(perft(&board, Player::Head, 1), 6);
+        assert_eq!(perft(&board, Player::Tail, 1), 0);
+
+        let two = Board::new(Config::default().with_layout(StartLayout::TwoPlayers));
+        let three = Board::new(Config::default().with_layout(StartLayout::ThreePlayers));
+        let counts = [1, 14, 196, 4760];
+        for (depth, count) in counts.iter().enumerate() {
+            assert_eq!(perft(&two, Player::Head, depth), *count);
+            assert_eq!(perft(&two, Player::Tail, depth), *count);
+        }
+        assert_eq!(perft(&three, Player::Head, 3), 2744);
+        assert_eq!(perft(&Board::default(), Player::Head, 3), 2786);
+    }
+}
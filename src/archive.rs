@@ -0,0 +1,159 @@
+//! A collection of game records that can be searched by position, to see
+//! how a position has been handled in earlier games.
+//!
+//! Each record is replayed once when it is added, and every position in it
+//! is indexed by `Board::hash` along with who was to move, so that looking
+//! a position up does not replay anything. Positions are counted the same
+//! way as in `Replay`, with resignations and timeouts folded into the
+//! position before the next turn.
+
+use crate::record::{Entry, GameRecord};
+use crate::{Board, GameError, Player};
+use std::collections::HashMap;
+
+/// Where a position came up in an `Archive`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Occurrence {
+    /// Index of the game, in the order that they were added.
+    pub game: usize,
+    /// Turns played before the position came up, as for `Replay::seek`.
+    pub ply: usize,
+}
+
+/// What positions are looked up by: the number of spots, so that boards
+/// of different sizes do not get mixed up, then the hash and who is to move.
+type Key = (usize, u64, Option<Player>);
+
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Archive {
+    games: Vec<GameRecord>,
+    positions: HashMap<Key, Vec<Occurrence>>,
+}
+
+impl Archive {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a game, returning its index. Fails at the first turn that is
+    /// not legal, in which case nothing is added.
+    pub fn add(&mut self, record: GameRecord) -> Result<usize, GameError> {
+        let index = self.games.len();
+        let mut game = record.new_game();
+        let mut keys = vec![];
+        for entry in &record.entries {
+            if let Entry::Turn(_) = entry {
+                keys.push(key(&game.board, game.current_player()));
+            }
+            entry.apply(&mut game)?;
+        }
+        keys.push(key(&game.board, game.current_player()));
+
+        for (ply, key) in keys.into_iter().enumerate() {
+            self.positions
+                .entry(key)
+                .or_default()
+                .push(Occurrence { game: index, ply });
+        }
+        self.games.push(record);
+        Ok(index)
+    }
+
+    pub fn games(&self) -> &[GameRecord] {
+        &self.games
+    }
+
+    pub fn game(&self, index: usize) -> Option<&GameRecord> {
+        self.games.get(index)
+    }
+
+    pub fn len(&self) -> usize {
+        self.games.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.games.is_empty()
+    }
+
+    /// Every time that a position came up with `to_move` to move, in the
+    /// order that the games were added and then by turn. A position that
+    /// came up more than once in a game is listed each time.
+    pub fn find(&self, board: &Board, to_move: Option<Player>) -> &[Occurrence] {
+        self.positions
+            .get(&key(board, to_move))
+            .map_or(&[], |x| x.as_slice())
+    }
+}
+
+fn key(board: &Board, to_move: Option<Player>) -> Key {
+    (board.spot_count(), board.hash(), to_move)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::replay::Replay;
+    use crate::{Config, Game, StartLayout};
+
+    fn record(turns: &[&str]) -> GameRecord {
+        let board = Board::new(Config::default().with_layout(StartLayout::TwoPlayers));
+        let mut game = Game::new(board, vec![Player::Head, Player::Tail]);
+        for turn in turns {
+            game.play_turn(&turn.parse().unwrap()).unwrap();
+        }
+        GameRecord::from_game(&game)
+    }
+
+    #[test]
+    fn test_find() {
+        let mut archive = Archive::new();
+        let first = record(&["4/10-5/11", "14/16-13/15", "4/12-5/13"]);
+        let second = record(&["4/12-5/13", "14/16-13/15", "4/10-5/11", "14/10-13/9"]);
+        assert_eq!(archive.add(first.clone()), Ok(0));
+        assert_eq!(archive.add(second), Ok(1));
+        assert_eq!(archive.len(), 2);
+
+        let start = first.start.clone();
+        assert_eq!(
+            archive.find(&start, Some(Player::Head)),
+            &[
+                Occurrence { game: 0, ply: 0 },
+                Occurrence { game: 1, ply: 0 }
+            ]
+        );
+        assert_eq!(archive.find(&start, Some(Player::Tail)), &[]);
+
+        // Both games reach the same position in a different order.
+        let mut replay = Replay::new(&first).unwrap();
+        replay.seek(3);
+        let found = archive.find(replay.board(), Some(Player::Tail));
+        assert_eq!(
+            found,
+            &[
+                Occurrence { game: 0, ply: 3 },
+                Occurrence { game: 1, ply: 3 }
+            ]
+        );
+        let mut replay = Replay::new(archive.game(found[1].game).unwrap()).unwrap();
+        replay.seek(found[1].ply);
+        assert_eq!(
+            replay.board().hash(),
+            archive.games()[0].to_game().unwrap().board.hash()
+        );
+
+        let board = Board::new(Config::quick().with_layout(StartLayout::TwoPlayers));
+        assert_eq!(archive.find(&board, Some(Player::Head)), &[]);
+    }
+
+    #[test]
+    fn test_add_illegal_record() {
+        let mut archive = Archive::new();
+        let mut record = record(&["4/10-5/11"]);
+        record
+            .entries
+            .push(Entry::Turn("4/10-5/11".parse().unwrap()));
+        assert!(archive.add(record.clone()).is_err());
+        assert!(archive.is_empty());
+        assert_eq!(archive.find(&record.start, Some(Player::Head)), &[]);
+    }
+}
@@ -0,0 +1,212 @@
+//! Adapter for chat bots, mapping plain text commands to game actions.
+//!
+//! The bot decides which chat user controls which piece and passes each
+//! message to `ChatGame::handle`, which returns the text to reply with.
+//! Supported commands are `move <turn>` (e.g., `move 3/11 5/13`),
+//! `board`, `turn`, `resign`, and `help`.
+
+use crate::{Board, Game, GameState, Player, Turn, Variant};
+
+const HELP: &str = "Commands: move <turn> (e.g. move 3/11 5/13), board, turn, resign, help";
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChatGame {
+    game: Game,
+}
+
+impl ChatGame {
+    pub fn new(board: Board, players: Vec<Player>) -> Self {
+        Self {
+            game: Game::new(board, players),
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.game.board
+    }
+
+    /// Whose turn it is, or `None` once the game is over.
+    pub fn current_player(&self) -> Option<Player> {
+        self.game.current_player()
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.game.state() != GameState::InProgress
+    }
+
+    /// Handle one message from the user controlling `player`.
+    pub fn handle(&mut self, player: Player, message: &str) -> String {
+        let message = message.trim();
+        let (command, rest) = match message.find(char::is_whitespace) {
+            Some(index) => (&message[..index], message[index..].trim()),
+            None => (message, ""),
+        };
+        match command.to_lowercase().as_str() {
+            "move" => match self.play(player, rest) {
+                Ok(text) => text,
+                Err(e) => e,
+            },
+            "board" => self.compact_board(),
+            "turn" => self.status(),
+            "resign" => self.resign(player),
+            _ => HELP.to_string(),
+        }
+    }
+
+    fn play(&mut self, player: Player, text: &str) -> Result<String, String> {
+        let turn: Turn = text.parse().map_err(|e| format!("{} {}", e, HELP))?;
+        self.game
+            .play_turn_as(player, &turn)
+            .map_err(|e| e.to_string())?;
+
+        let mut reply = format!("{:?} played {}", player, turn);
+        if self.game.finished().last() == Some(&player) {
+            match self.board().config().variant() {
+                Variant::Standard => reply.push_str(&format!("\n{:?} has finished!", player)),
+                Variant::Misere => {
+                    reply.push_str(&format!("\n{:?} has finished and is out!", player))
+                }
+            }
+        }
+        Ok(format!(
+            "{}\n{}\n{}",
+            reply,
+            self.compact_board(),
+            self.status()
+        ))
+    }
+
+    fn resign(&mut self, player: Player) -> String {
+        if !self.game.resign(player) {
+            return format!("{:?} is not playing", player);
+        }
+        format!("{:?} resigned\n{}", player, self.status())
+    }
+
+    fn status(&self) -> String {
+        match self.current_player() {
+            Some(piece) => format!(
+                "{:?} ({}) to move",
+                piece,
+                self.board().config().symbol(piece)
+            ),
+            None if self.game.finished().is_empty() => "The game is over!".to_string(),
+            None if self.board().config().variant() == Variant::Misere => {
+                let order: Vec<String> = self
+                    .game
+                    .standings()
+                    .iter()
+                    .map(|x| format!("{:?}", x))
+                    .collect();
+                format!("The game is over! Standings: {}", order.join(", "))
+            }
+            None => {
+                let order: Vec<String> = self
+                    .game
+                    .finished()
+                    .iter()
+                    .map(|x| format!("{:?}", x))
+                    .collect();
+                format!("The game is over! Finished: {}", order.join(", "))
+            }
+        }
+    }
+
+    /// The board diagram without its leading margin, for code blocks.
+    fn compact_board(&self) -> String {
+        let lines = self.board().serialize();
+        let margin = lines
+            .iter()
+            .map(|line| line.len() - line.trim_start().len())
+            .min()
+            .unwrap_or(0);
+        lines
+            .iter()
+            .map(|line| &line[margin..])
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cell, Config, GameError, Point};
+
+    fn game() -> ChatGame {
+        ChatGame::new(Board::default(), vec![Player::Head, Player::Tail])
+    }
+
+    #[test]
+    fn test_move() {
+        let mut game = game();
+        let reply = game.handle(Player::Head, "move 4/10 5/11");
+        assert!(reply.starts_with("Head played 4/10-5/11\n"));
+        assert!(reply.ends_with("Tail (2) to move"));
+        assert_eq!(
+            game.board().get_piece(Point::new(5, 11)),
+            Some(Cell::Occupied(Player::Head))
+        );
+        assert_eq!(game.current_player(), Some(Player::Tail));
+    }
+
+    #[test]
+    fn test_move_with_errors() {
+        let mut game = game();
+        assert_eq!(
+            game.handle(Player::Tail, "move 14/16 13/15"),
+            "It is Head's turn, not Tail's.",
+        );
+        assert_eq!(
+            game.handle(Player::Head, "move 1/13 2/12"),
+            "2/12 is already taken by Head.",
+        );
+        assert!(game.handle(Player::Head, "move nowhere").ends_with(HELP));
+        assert_eq!(game.current_player(), Some(Player::Head));
+    }
+
+    #[test]
+    fn test_board() {
+        let mut game = ChatGame::new(
+            Board::new(Config::default().with_player_lines(1)),
+            vec![Player::Head, Player::Tail],
+        );
+        assert_eq!(
+            game.handle(Player::Head, "board"),
+            "   1\n3 . . 5\n . . .\n6 . . 4\n   2"
+        );
+    }
+
+    #[test]
+    fn test_resign() {
+        let mut game = game();
+        assert_eq!(
+            game.handle(Player::Head, "resign"),
+            "Head resigned\nThe game is over!"
+        );
+        assert!(game.is_over());
+        assert_eq!(
+            game.handle(Player::Tail, "move 14/16 13/15"),
+            GameError::GameFinished.to_string(),
+        );
+    }
+
+    #[test]
+    fn test_misere() {
+        let config = Config::default()
+            .with_player_lines(1)
+            .with_variant(Variant::Misere);
+        let mut board = Board::empty(config);
+        board.set_piece(Point::new(4, 3), Player::Head).unwrap();
+        board.set_piece(Point::new(3, 4), Player::Tail).unwrap();
+        let mut game = ChatGame::new(board, vec![Player::Head, Player::Tail]);
+        let reply = game.handle(Player::Head, "move 4/3 5/4");
+        assert!(reply.contains("\nHead has finished and is out!\n"));
+        assert!(reply.ends_with("The game is over! Standings: Tail, Head"));
+    }
+
+    #[test]
+    fn test_help() {
+        assert_eq!(game().handle(Player::Head, "hello"), HELP);
+    }
+}
@@ -0,0 +1,259 @@
+//! Time controls for games where players have to move within a limit.
+//!
+//! A `Clock` does not read the time itself. Frontends measure how long
+//! each player took and pass that to `Game::play_timed_turn`, which charges
+//! it to the clock and takes the player out of the game if their time ran
+//! out. This keeps games reproducible and lets tests use made-up times.
+//!
+//! The clock can also be paused and corrected by hand through `Game`, as
+//! an arbiter would at a tournament. While it is paused, turns cost no
+//! time and nobody runs out.
+
+use crate::{GameError, Player};
+use std::collections::HashMap;
+use std::time::Duration;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TimeControl {
+    /// A fixed amount of time for all of a player's turns together.
+    Absolute(Duration),
+    /// A fixed amount of time for each turn. Unused time is not kept.
+    PerMove(Duration),
+    /// Fischer timing: a starting amount, plus `increment` after each turn.
+    Increment { base: Duration, increment: Duration },
+}
+
+impl TimeControl {
+    /// 3 minutes each, plus 2 seconds per turn.
+    pub const BLITZ: Self = Self::Increment {
+        base: Duration::from_secs(3 * 60),
+        increment: Duration::from_secs(2),
+    };
+    /// 10 minutes each, plus 5 seconds per turn.
+    pub const RAPID: Self = Self::Increment {
+        base: Duration::from_secs(10 * 60),
+        increment: Duration::from_secs(5),
+    };
+    /// 30 minutes each for the whole game.
+    pub const CLASSICAL: Self = Self::Absolute(Duration::from_secs(30 * 60));
+
+    /// Time that each player starts with.
+    fn initial(self) -> Duration {
+        match self {
+            TimeControl::Absolute(total) => total,
+            TimeControl::PerMove(limit) => limit,
+            TimeControl::Increment { base, .. } => base,
+        }
+    }
+}
+
+/// Reads a preset by name, `blitz`, `rapid`, or `classical`, or minutes
+/// and seconds of increment like `5+3`. Without an increment, such as
+/// `15+0`, it is `Absolute`.
+impl std::str::FromStr for TimeControl {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "blitz" => return Ok(Self::BLITZ),
+            "rapid" => return Ok(Self::RAPID),
+            "classical" => return Ok(Self::CLASSICAL),
+            _ => {}
+        }
+        let parsed: Option<(u64, u64)> = s
+            .split_once('+')
+            .and_then(|(minutes, seconds)| Some((minutes.parse().ok()?, seconds.parse().ok()?)));
+        match parsed {
+            Some((0, _)) => Err(format!("No time to play in: {}", s)),
+            Some((minutes, 0)) => Ok(Self::Absolute(Duration::from_secs(minutes * 60))),
+            Some((minutes, seconds)) => Ok(Self::Increment {
+                base: Duration::from_secs(minutes * 60),
+                increment: Duration::from_secs(seconds),
+            }),
+            None => Err(format!("Unknown time control: {}", s)),
+        }
+    }
+}
+
+/// Something done to a clock other than charging it for a turn, which
+/// `Game` remembers so that `undo` can do it again.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Change {
+    Pause,
+    Resume,
+    SetRemaining(Player, Duration),
+}
+
+/// How much time each player has left under a `TimeControl`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Clock {
+    control: TimeControl,
+    remaining: HashMap<Player, Duration>,
+    paused: bool,
+}
+
+impl Clock {
+    pub fn new(control: TimeControl, players: &[Player]) -> Self {
+        Self {
+            control,
+            remaining: players.iter().map(|x| (*x, control.initial())).collect(),
+            paused: false,
+        }
+    }
+
+    pub fn control(&self) -> TimeControl {
+        self.control
+    }
+
+    /// Time that a player has for their next turn, or `None` if they are
+    /// not on the clock.
+    pub fn remaining(&self, player: Player) -> Option<Duration> {
+        self.remaining.get(&player).copied()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Whether a player who has been thinking for `thinking` is out of
+    /// time. Players who are not on the clock never are, and nobody is
+    /// while it is paused.
+    pub fn is_flagged(&self, player: Player, thinking: Duration) -> bool {
+        !self.paused && self.remaining(player).is_some_and(|x| thinking > x)
+    }
+
+    /// Returns whether the change did anything: pausing a paused clock,
+    /// resuming a running one, or setting the time of someone who is not
+    /// on the clock does not.
+    pub(crate) fn apply(&mut self, change: Change) -> bool {
+        match change {
+            Change::Pause | Change::Resume => {
+                let paused = change == Change::Pause;
+                let changed = self.paused != paused;
+                self.paused = paused;
+                changed
+            }
+            Change::SetRemaining(player, time) => match self.remaining.get_mut(&player) {
+                Some(remaining) => {
+                    *remaining = time;
+                    true
+                }
+                None => false,
+            },
+        }
+    }
+
+    /// Use up the rest of a player's time, when they lose on time.
+    pub(crate) fn flag(&mut self, player: Player) {
+        if let Some(remaining) = self.remaining.get_mut(&player) {
+            *remaining = Duration::from_secs(0);
+        }
+    }
+
+    /// Charge a player for a turn that took `spent`. If that was more than
+    /// they had, their time drops to zero and the turn does not count.
+    /// While the clock is paused, turns are free.
+    pub(crate) fn charge(&mut self, player: Player, spent: Duration) -> Result<(), GameError> {
+        if self.paused {
+            return Ok(());
+        }
+        let control = self.control;
+        let remaining = match self.remaining.get_mut(&player) {
+            Some(remaining) => remaining,
+            None => return Ok(()),
+        };
+        if spent > *remaining {
+            *remaining = Duration::from_secs(0);
+            return Err(GameError::TimeExpired);
+        }
+        *remaining = match control {
+            TimeControl::Absolute(_) => *remaining - spent,
+            TimeControl::PerMove(limit) => limit,
+            TimeControl::Increment { increment, .. } => *remaining - spent + increment,
+        };
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn test_absolute() {
+        let mut clock = Clock::new(TimeControl::Absolute(secs(60)), &[Player::Head]);
+        assert_eq!(clock.charge(Player::Head, secs(45)), Ok(()));
+        assert_eq!(clock.remaining(Player::Head), Some(secs(15)));
+        assert!(clock.is_flagged(Player::Head, secs(16)));
+        assert_eq!(
+            clock.charge(Player::Head, secs(16)),
+            Err(GameError::TimeExpired)
+        );
+        assert_eq!(clock.remaining(Player::Head), Some(secs(0)));
+    }
+
+    #[test]
+    fn test_per_move() {
+        let mut clock = Clock::new(TimeControl::PerMove(secs(10)), &[Player::Head]);
+        assert_eq!(clock.charge(Player::Head, secs(9)), Ok(()));
+        assert_eq!(clock.charge(Player::Head, secs(10)), Ok(()));
+        assert_eq!(clock.remaining(Player::Head), Some(secs(10)));
+        assert_eq!(
+            clock.charge(Player::Head, secs(11)),
+            Err(GameError::TimeExpired)
+        );
+    }
+
+    #[test]
+    fn test_pause_and_set_remaining() {
+        let mut clock = Clock::new(TimeControl::Absolute(secs(60)), &[Player::Head]);
+        assert!(!clock.apply(Change::Resume));
+        assert!(clock.apply(Change::Pause));
+        assert!(!clock.apply(Change::Pause));
+        assert!(clock.is_paused());
+        assert!(!clock.is_flagged(Player::Head, secs(100)));
+        assert_eq!(clock.charge(Player::Head, secs(100)), Ok(()));
+        assert_eq!(clock.remaining(Player::Head), Some(secs(60)));
+
+        assert!(clock.apply(Change::Resume));
+        assert!(clock.apply(Change::SetRemaining(Player::Head, secs(90))));
+        assert!(!clock.apply(Change::SetRemaining(Player::Tail, secs(90))));
+        assert_eq!(clock.charge(Player::Head, secs(80)), Ok(()));
+        assert_eq!(clock.remaining(Player::Head), Some(secs(10)));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!("Blitz".parse(), Ok(TimeControl::BLITZ));
+        assert_eq!("rapid".parse(), Ok(TimeControl::RAPID));
+        assert_eq!("30+0".parse(), Ok(TimeControl::CLASSICAL));
+        assert_eq!(
+            "5+3".parse(),
+            Ok(TimeControl::Increment {
+                base: secs(300),
+                increment: secs(3),
+            })
+        );
+        assert!("0+5".parse::<TimeControl>().is_err());
+        assert!("bullet".parse::<TimeControl>().is_err());
+        assert!("5+".parse::<TimeControl>().is_err());
+    }
+
+    #[test]
+    fn test_increment() {
+        let control = TimeControl::Increment {
+            base: secs(30),
+            increment: secs(5),
+        };
+        let mut clock = Clock::new(control, &[Player::Head, Player::Tail]);
+        assert_eq!(clock.charge(Player::Head, secs(20)), Ok(()));
+        assert_eq!(clock.remaining(Player::Head), Some(secs(15)));
+        assert_eq!(clock.remaining(Player::Tail), Some(secs(30)));
+        assert_eq!(clock.remaining(Player::LeftHand), None);
+        assert!(!clock.is_flagged(Player::LeftHand, secs(1000)));
+    }
+}
@@ -0,0 +1,138 @@
+//! Coaching for players who are still learning the rules. When a turn is
+//! rejected, `advise` explains which rule it broke and suggests the legal
+//! turns from the same piece that land closest to where the player was
+//! trying to go.
+
+use crate::{Board, Cell, GameError, Player, Turn};
+
+/// How many alternatives to suggest at most.
+const MAX_SUGGESTIONS: usize = 3;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Advice {
+    /// Why the turn is not allowed, in words.
+    pub rule: String,
+    /// Legal turns from the same piece, nearest to the intended target first.
+    pub suggestions: Vec<Turn>,
+}
+
+/// Put a rejected move in words, with more of the rules than
+/// `GameError`'s own message gives.
+fn explain(error: &GameError, player: Player) -> String {
+    match error {
+        GameError::WrongPlayer {
+            point,
+            found: Cell::Occupied(owner),
+            ..
+        } => format!(
+            "The piece at {} belongs to {:?}, but it is {:?}'s turn.",
+            point, owner, player
+        ),
+        GameError::OccupiedTarget { point, occupant } => format!(
+            "{} is already taken by {:?}, and pieces can only land on empty spots.",
+            point, occupant
+        ),
+        GameError::EmptyMidpoint { from, to, middle } => format!(
+            "There is no piece at {} to jump over on the way from {} to {}.",
+            middle, from, to
+        ),
+        GameError::NoRoute { from, to } => format!(
+            "{} cannot reach {} in one move. A piece can step to one of the six \
+             neighboring spots, or jump over one adjacent piece in a straight line.",
+            from, to
+        ),
+        GameError::Exhausted { .. } => "A turn is either one step to a neighboring spot or a \
+                                        chain of jumps, but not a mix of both."
+            .to_string(),
+        GameError::InChain { error, .. } => explain(error, player),
+        other => other.to_string(),
+    }
+}
+
+/// Explain why a turn is illegal, or `None` if it is fine.
+pub fn advise(board: &Board, turn: &Turn, player: Player) -> Option<Advice> {
+    let error = board.try_turn(turn, player).err()?;
+    let rule = explain(&error, player);
+
+    let mut suggestions = match board.get_piece(turn.source()) {
+        Some(cell) if cell == Cell::Occupied(player) => board.destinations(turn.source()),
+        _ => vec![],
+    };
+    suggestions.sort_by_key(|x| x.target().hex_distance(turn.target()));
+    suggestions.truncate(MAX_SUGGESTIONS);
+
+    Some(Advice { rule, suggestions })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Point;
+
+    fn advise_text(board: &Board, turn: &str, player: Player) -> Option<Advice> {
+        advise(board, &turn.parse().unwrap(), player)
+    }
+
+    #[test]
+    fn test_legal_turn() {
+        assert_eq!(
+            advise_text(&Board::default(), "4/10-5/11", Player::Head),
+            None
+        );
+    }
+
+    #[test]
+    fn test_missing_middle_piece() {
+        let advice = advise_text(&Board::default(), "4/10>6/12", Player::Head).unwrap();
+        assert_eq!(
+            advice.rule,
+            "There is no piece at 5/11 to jump over on the way from 4/10 to 6/12."
+        );
+        assert_eq!(
+            advice.suggestions,
+            vec!["4/10-5/11".parse().unwrap(), "4/10-5/9".parse().unwrap()],
+        );
+    }
+
+    #[test]
+    fn test_wrong_player() {
+        let advice = advise_text(&Board::default(), "4/10-5/11", Player::Tail).unwrap();
+        assert_eq!(
+            advice.rule,
+            "The piece at 4/10 belongs to Head, but it is Tail's turn."
+        );
+        assert_eq!(advice.suggestions, vec![]);
+    }
+
+    #[test]
+    fn test_occupied_target_later_in_chain() {
+        let mut board = Board::default();
+        board
+            .move_piece(Point::new(4, 10), Point::new(5, 11), Player::Head)
+            .unwrap();
+        let advice = advise_text(&board, "3/11>5/13>5/9>5/5", Player::Head).unwrap();
+        assert_eq!(
+            advice.rule,
+            "5/5 is already taken by LeftHand, and pieces can only land on empty spots."
+        );
+        assert_eq!(advice.suggestions[0], "3/11>5/13>5/9".parse().unwrap());
+    }
+
+    #[test]
+    fn test_mixed_turn() {
+        let turn = Turn::Jumps(vec![
+            Point::new(4, 10),
+            Point::new(5, 11),
+            Point::new(6, 12),
+        ]);
+        let advice = advise(&Board::default(), &turn, Player::Head).unwrap();
+        assert!(advice.rule.starts_with("A turn is either one step"));
+        assert_eq!(advice.suggestions[0], "4/10-5/11".parse().unwrap());
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        let advice = advise_text(&Board::default(), "1/13-1/11", Player::Head).unwrap();
+        assert_eq!(advice.rule, "1/11 is not a spot on the board.");
+    }
+}
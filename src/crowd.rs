@@ -0,0 +1,169 @@
+use crate::{Board, GameError, Player, Turn};
+use std::time::{Duration, Instant};
+
+/// Collects votes for one turn of a crowd-controlled seat.
+/// Each voter may have one vote at a time; voting again replaces it.
+/// When the window closes, the turn with the most votes is chosen,
+/// and ties go to the turn that received its first vote earliest.
+#[derive(Clone, Debug)]
+pub struct Ballot {
+    player: Player,
+    window: Duration,
+    opened: Instant,
+    votes: Vec<(String, Turn)>,
+}
+
+impl Ballot {
+    pub fn new(player: Player, window: Duration) -> Self {
+        Self {
+            player,
+            window,
+            opened: Instant::now(),
+            votes: vec![],
+        }
+    }
+
+    pub fn player(&self) -> Player {
+        self.player
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.opened.elapsed() < self.window
+    }
+
+    /// End voting early, such as once everyone in the audience has voted.
+    pub fn close(&mut self) {
+        self.window = self.opened.elapsed();
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.window
+            .checked_sub(self.opened.elapsed())
+            .unwrap_or_default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.votes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.votes.is_empty()
+    }
+
+    /// Record a vote after checking that the turn is legal on the board.
+    pub fn cast(&mut self, board: &Board, voter: &str, turn: Turn) -> Result<(), GameError> {
+        if !self.is_open() {
+            return Err(GameError::TimeExpired);
+        }
+        board.try_turn(&turn, self.player)?;
+        self.votes.retain(|(name, _)| name != voter);
+        self.votes.push((voter.to_string(), turn));
+        Ok(())
+    }
+
+    /// The plurality winner so far, if anyone has voted.
+    pub fn winner(&self) -> Option<Turn> {
+        let mut tally = Vec::<(&Turn, usize)>::new();
+        for (_, turn) in &self.votes {
+            match tally.iter_mut().find(|(candidate, _)| *candidate == turn) {
+                Some((_, count)) => *count += 1,
+                None => tally.push((turn, 1)),
+            }
+        }
+        let mut best: Option<(&Turn, usize)> = None;
+        for (turn, count) in tally {
+            match best {
+                Some((_, best_count)) if best_count >= count => {}
+                _ => best = Some((turn, count)),
+            }
+        }
+        best.map(|(turn, _)| turn.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cell, Game, Point};
+
+    fn step() -> Turn {
+        "4/10-5/11".parse().unwrap()
+    }
+
+    fn jump() -> Turn {
+        "3/11>5/13".parse().unwrap()
+    }
+
+    #[test]
+    fn test_cast_rejects_illegal_turn() {
+        let board = Board::default();
+        let mut ballot = Ballot::new(Player::Head, Duration::from_secs(30));
+        assert_eq!(
+            ballot.cast(&board, "alice", "1/13-2/12".parse().unwrap()),
+            Err(GameError::OccupiedTarget {
+                point: Point::new(2, 12),
+                occupant: Player::Head,
+            }),
+        );
+        assert!(ballot.is_empty());
+    }
+
+    #[test]
+    fn test_cast_after_window_closed() {
+        let board = Board::default();
+        let mut ballot = Ballot::new(Player::Head, Duration::from_secs(0));
+        assert_eq!(
+            ballot.cast(&board, "alice", step()),
+            Err(GameError::TimeExpired),
+        );
+    }
+
+    #[test]
+    fn test_winner_by_plurality_with_replaced_votes() {
+        let board = Board::default();
+        let mut ballot = Ballot::new(Player::Head, Duration::from_secs(30));
+        ballot.cast(&board, "alice", step()).unwrap();
+        ballot.cast(&board, "bob", jump()).unwrap();
+        ballot.cast(&board, "carol", jump()).unwrap();
+        ballot.cast(&board, "carol", step()).unwrap();
+        ballot.cast(&board, "dave", step()).unwrap();
+        assert_eq!(ballot.len(), 4);
+        assert_eq!(ballot.winner(), Some(step()));
+    }
+
+    #[test]
+    fn test_winner_tie_goes_to_earliest() {
+        let board = Board::default();
+        let mut ballot = Ballot::new(Player::Head, Duration::from_secs(30));
+        assert_eq!(ballot.winner(), None);
+        ballot.cast(&board, "alice", jump()).unwrap();
+        ballot.cast(&board, "bob", step()).unwrap();
+        assert_eq!(ballot.winner(), Some(jump()));
+    }
+
+    #[test]
+    fn test_play_ballot() {
+        let mut game = Game::new(Board::default(), vec![Player::Head, Player::Tail]);
+        let mut ballot = Ballot::new(Player::Head, Duration::from_secs(30));
+        ballot.cast(&game.board, "alice", step()).unwrap();
+        assert_eq!(game.play_ballot(&ballot), Err(GameError::VotingOpen));
+        ballot.close();
+        assert!(!ballot.is_open());
+        assert_eq!(ballot.remaining(), Duration::from_secs(0));
+        assert_eq!(game.play_ballot(&ballot), Ok(step()));
+        assert_eq!(
+            game.board.get_piece(Point::new(5, 11)),
+            Some(Cell::Occupied(Player::Head))
+        );
+        assert_eq!(
+            game.play_ballot(&ballot),
+            Err(GameError::NotYourTurn {
+                player: Player::Head,
+                to_move: Player::Tail,
+            })
+        );
+
+        let ballot = Ballot::new(Player::Tail, Duration::from_secs(0));
+        assert_eq!(game.play_ballot(&ballot), Err(GameError::NoVotes));
+    }
+}
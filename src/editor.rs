@@ -0,0 +1,102 @@
+//! Editing positions by hand, for analysis tools, puzzles, and test
+//! scaffolding.
+//!
+//! A `BoardEditor` moves pieces around without regard for the rules, the
+//! same way `Board::set_piece` and `Board::clear` do, and only checks the
+//! position once editing is done, so that a setup can pass through states
+//! that could never be played on the way to one that can.
+
+use crate::{Board, Cell, GameError, Player, Point};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BoardEditor {
+    board: Board,
+}
+
+impl BoardEditor {
+    /// Start editing from an existing position, such as `Board::empty`.
+    pub fn new(board: Board) -> Self {
+        Self { board }
+    }
+
+    /// The position as it stands, which may not be valid yet.
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Put a player's piece on a spot, replacing whatever was there.
+    pub fn place(&mut self, point: Point, player: Player) -> Result<(), GameError> {
+        self.board.set_piece(point, player)
+    }
+
+    /// Empty a spot. Returns the player whose piece was there, if any.
+    pub fn remove(&mut self, point: Point) -> Result<Option<Player>, GameError> {
+        let previous = self
+            .board
+            .get_piece(point)
+            .ok_or(GameError::OutOfBounds(point))?;
+        self.board.set_piece(point, Cell::Empty)?;
+        Ok(previous.player())
+    }
+
+    /// Take every piece off the board.
+    pub fn clear(&mut self) {
+        self.board.clear();
+    }
+
+    /// Check whether the position could be played; see `Board::validate_setup`.
+    pub fn validate(&self) -> Result<(), GameError> {
+        self.board.validate_setup()
+    }
+
+    /// Stop editing and give back the position, as long as it is valid.
+    pub fn finish(self) -> Result<Board, GameError> {
+        self.validate()?;
+        Ok(self.board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_editing() {
+        let mut editor = BoardEditor::new(Board::default());
+        editor.clear();
+        assert_eq!(editor.board(), &Board::empty(Config::default()));
+        assert_eq!(editor.validate(), Err(GameError::InvalidSetup));
+
+        let point = Point::new(9, 13);
+        assert_eq!(editor.place(point, Player::Head), Ok(()));
+        assert_eq!(editor.place(point, Player::Tail), Ok(()));
+        assert_eq!(editor.remove(point), Ok(Some(Player::Tail)));
+        assert_eq!(editor.remove(point), Ok(None));
+        assert_eq!(
+            editor.place(Point::new(9, 14), Player::Head),
+            Err(GameError::OutOfBounds(Point::new(9, 14)))
+        );
+        assert_eq!(
+            editor.remove(Point::new(9, 14)),
+            Err(GameError::OutOfBounds(Point::new(9, 14)))
+        );
+
+        editor.place(point, Player::Head).unwrap();
+        let board = editor.finish().unwrap();
+        assert_eq!(
+            board,
+            Board::from_setup(&[(point, Player::Head)], Config::default()).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_clear_keeps_hash_consistent() {
+        let mut board = Board::default();
+        board.clear();
+        assert_eq!(board.hash(), Board::empty(Config::default()).hash());
+        board.set_piece(Point::new(9, 13), Player::Head).unwrap();
+        board.set_piece(Point::new(9, 13), Cell::Empty).unwrap();
+        assert_eq!(board.hash(), 0);
+    }
+}
@@ -0,0 +1,219 @@
+//! A line-based protocol for driving the computer players from other
+//! programs, in the spirit of UCI for chess. The frontend writes one
+//! command per line, and the engine answers each with zero or more lines:
+//!
+//! * `sep` introduces the frontend. The engine replies with `id name ...`
+//!   and `id author ...`, then `sepok`.
+//! * `isready` is answered with `readyok` once earlier commands are done.
+//! * `newgame` forgets what the search learned about earlier positions.
+//! * `position startpos [players <2|3|4|6>] [moves <turn> ...]` sets up
+//!   the starting position, and `position fen <fen> [moves <turn> ...]` a
+//!   position from `Board::to_fen`, then plays any given turns in order.
+//! * `fen` is answered with `fen <fen>` for the current position.
+//! * `legal` is answered with `legal <turn> ...` for the player to move.
+//! * `go [depth <n>] [nodes <n>] [movetime <milliseconds>]` searches for
+//!   the player to move and is answered with `bestmove <turn>`, or
+//!   `bestmove none` if there is no turn to play.
+//! * `quit` stops the engine.
+//!
+//! Turns are written in the usual notation, such as `4/10-5/11`. A command
+//! that cannot be carried out is answered with `error <reason>` and leaves
+//! the position as it was.
+
+use crate::ai::{AlphaBeta, Bot, Difficulty, ThinkBudget};
+use crate::{Board, Config, Game, GameError, StartLayout, Turn};
+use std::io::{self, BufRead, Write};
+
+pub struct Engine {
+    config: Config,
+    game: Game,
+    bot: AlphaBeta,
+}
+
+impl Engine {
+    /// An engine whose `startpos` is the starting position for `config`.
+    pub fn new(config: Config) -> Self {
+        let seats = config.layout().players();
+        Self {
+            game: Game::new(Board::new(config.clone()), seats),
+            config,
+            bot: AlphaBeta::with_difficulty(Difficulty::Medium),
+        }
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Carry out one command and give back the lines of the answer.
+    pub fn handle(&mut self, line: &str) -> Vec<String> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let result = match words.split_first() {
+            None => Ok(vec![]),
+            Some((&"sep", _)) => Ok(vec![
+                format!("id name stelsalto {}", env!("CARGO_PKG_VERSION")),
+                format!("id author {}", env!("CARGO_PKG_AUTHORS")),
+                String::from("sepok"),
+            ]),
+            Some((&"isready", _)) => Ok(vec![String::from("readyok")]),
+            Some((&"newgame", _)) => {
+                self.bot.table.clear();
+                Ok(vec![])
+            }
+            Some((&"position", args)) => self.position(args).map(|()| vec![]),
+            Some((&"fen", _)) => Ok(vec![format!("fen {}", self.game.to_fen())]),
+            Some((&"legal", _)) => {
+                let turns = match self.game.current_player() {
+                    Some(player) => self.game.board.legal_turns(player),
+                    None => vec![],
+                };
+                let mut line = String::from("legal");
+                for turn in turns {
+                    line.push_str(&format!(" {}", turn));
+                }
+                Ok(vec![line])
+            }
+            Some((&"go", args)) => self.go(args).map(|x| vec![x]),
+            Some((command, _)) => Err(format!("Unknown command: {}", command)),
+        };
+        result.unwrap_or_else(|e| vec![format!("error {}", e)])
+    }
+
+    /// Answer commands from `input` until it ends or says `quit`.
+    pub fn run(&mut self, input: impl BufRead, mut output: impl Write) -> io::Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim() == "quit" {
+                break;
+            }
+            for reply in self.handle(&line) {
+                writeln!(output, "{}", reply)?;
+            }
+            output.flush()?;
+        }
+        Ok(())
+    }
+
+    fn position(&mut self, args: &[&str]) -> Result<(), String> {
+        let (setup, moves) = match args.iter().position(|x| *x == "moves") {
+            Some(index) => (&args[..index], &args[index + 1..]),
+            None => (args, &[][..]),
+        };
+        let mut game = match setup {
+            ["startpos"] => self.start(self.config.layout()),
+            ["startpos", "players", count] => {
+                let layout = count
+                    .parse()
+                    .ok()
+                    .and_then(StartLayout::for_players)
+                    .ok_or_else(|| format!("Unsupported number of players: {}", count))?;
+                self.start(layout)
+            }
+            ["fen", fen @ ..] => {
+                let (board, to_move) =
+                    Board::from_fen(&fen.join(" ")).map_err(|e| e.to_string())?;
+                let mut seats = board.config().layout().players();
+                if let Some(to_move) = to_move {
+                    let index = seats
+                        .iter()
+                        .position(|x| *x == to_move)
+                        .ok_or_else(|| GameError::InvalidSetup.to_string())?;
+                    seats.rotate_left(index);
+                }
+                Game::new(board, seats)
+            }
+            _ => return Err(String::from("Expected startpos or fen")),
+        };
+        for turn in moves {
+            let turn: Turn = turn.parse().map_err(|e| format!("{}: {}", turn, e))?;
+            game.play_turn(&turn)
+                .map_err(|e| format!("{}: {}", turn, e))?;
+        }
+        self.game = game;
+        Ok(())
+    }
+
+    fn start(&self, layout: StartLayout) -> Game {
+        let config = self.config.clone().with_layout(layout);
+        Game::new(Board::new(config), layout.players())
+    }
+
+    fn go(&mut self, args: &[&str]) -> Result<String, String> {
+        let mut budget = Difficulty::Medium.budget();
+        for pair in args.chunks(2) {
+            let value = pair.get(1).and_then(|x| x.parse().ok());
+            budget = match (pair[0], value) {
+                ("depth", Some(depth)) => ThinkBudget::Depth(depth as usize),
+                ("nodes", Some(nodes)) => ThinkBudget::Nodes(nodes),
+                ("movetime", Some(millis)) => ThinkBudget::Millis(millis),
+                _ => return Err(format!("Invalid search limit: {}", pair.join(" "))),
+            };
+        }
+        self.bot.budget = budget;
+        Ok(match self.bot.choose(&self.game) {
+            Some(turn) => format!("bestmove {}", turn),
+            None => String::from("bestmove none"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    #[test]
+    fn test_session() {
+        let input =
+            "sep\nisready\nposition startpos moves 4/10-5/11\nlegal\ngo depth 1\nquit\nfen\n";
+        let mut output = vec![];
+        let mut engine = Engine::new(Config::default());
+        engine.run(input.as_bytes(), &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 6);
+        assert!(lines[0].starts_with("id name stelsalto "));
+        assert_eq!(&lines[2..4], &["sepok", "readyok"]);
+        assert!(lines[4].starts_with("legal ") && lines[4].contains(" 14/10-13/9"));
+        assert!(lines[5].starts_with("bestmove "));
+        assert_eq!(engine.game().current_player(), Some(Player::Tail));
+    }
+
+    #[test]
+    fn test_position() {
+        let mut engine = Engine::new(Config::default());
+        assert!(engine.handle("position startpos players 3").is_empty());
+        assert_eq!(
+            engine.game().seats(),
+            &[Player::Head, Player::RightFoot, Player::LeftFoot]
+        );
+
+        engine.handle("position startpos moves 4/10-5/11");
+        let fen = engine.handle("fen")[0].replacen("fen ", "", 1);
+        assert!(engine.handle("position startpos").is_empty());
+        assert!(engine.handle(&format!("position fen {}", fen)).is_empty());
+        assert_eq!(engine.game().current_player(), Some(Player::Tail));
+        assert_eq!(engine.handle("fen"), vec![format!("fen {}", fen)]);
+
+        assert_eq!(
+            engine.handle("position startpos moves 4/10-5/11 4/12-5/13"),
+            vec![format!(
+                "error 4/12-5/13: {}",
+                GameError::WrongPlayer {
+                    point: "4/12".parse().unwrap(),
+                    expected: Player::Tail,
+                    found: crate::Cell::Occupied(Player::Head),
+                }
+            )]
+        );
+        assert_eq!(engine.game().current_player(), Some(Player::Tail));
+        assert_eq!(
+            engine.handle("go depth"),
+            vec![String::from("error Invalid search limit: depth")]
+        );
+        assert_eq!(
+            engine.handle("jump"),
+            vec![String::from("error Unknown command: jump")]
+        );
+    }
+}
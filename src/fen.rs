@@ -0,0 +1,272 @@
+//! Single-line position strings, similar to FEN in chess.
+//!
+//! A position is written as five fields separated by spaces, such as
+//! `1/11/111/1111/3333e5555/... 1 fill standard 6` for the standard board:
+//!
+//! * Rows from top to bottom, separated by `/`. Pieces are written with the
+//!   default symbols `1` to `6`, and a run of empty spots with a letter,
+//!   where `a` is one spot, `b` is two, and so on up to `z`.
+//! * The player to move, as their symbol, or `-` if nobody is.
+//! * The win condition: `fill`, `home:<pieces>`, `score:<rounds>`, or
+//!   `eliminate`.
+//! * The variant: `standard` or `misere`, followed by `+capture` if pieces
+//!   that are jumped over get captured, `+revisit` if jump chains may land
+//!   on a spot twice, and `+clear` if the pieces of players who leave are
+//!   taken off the board.
+//! * The number of players in the start layout.
+//!
+//! The board size follows from the number of rows. Other settings, such as
+//! handicaps or custom starting corners, are not included.
+
+use crate::{
+    row_len, Board, Cell, Config, ForfeitRule, ParseError, Player, StartLayout, Variant,
+    WinCondition, MAX_PLAYER_LINES,
+};
+
+pub(crate) fn symbol(player: Player) -> char {
+    match player {
+        Player::Head => '1',
+        Player::Tail => '2',
+        Player::LeftHand => '3',
+        Player::RightFoot => '4',
+        Player::RightHand => '5',
+        Player::LeftFoot => '6',
+    }
+}
+
+pub(crate) fn player(symbol: char) -> Option<Player> {
+    match symbol {
+        '1' => Some(Player::Head),
+        '2' => Some(Player::Tail),
+        '3' => Some(Player::LeftHand),
+        '4' => Some(Player::RightFoot),
+        '5' => Some(Player::RightHand),
+        '6' => Some(Player::LeftFoot),
+        _ => None,
+    }
+}
+
+fn parse_win_condition(text: &str) -> Option<WinCondition> {
+    let mut parts = text.splitn(2, ':');
+    match (parts.next()?, parts.next()) {
+        ("fill", None) => Some(WinCondition::FillTarget),
+        ("home", Some(n)) => n.parse().ok().map(WinCondition::FirstNPiecesHome),
+        ("score", Some(n)) => n.parse().ok().map(WinCondition::ScoreAtTurnLimit),
+        ("eliminate", None) => Some(WinCondition::Elimination),
+        _ => None,
+    }
+}
+
+impl Board {
+    /// Write the position as a single line, with whoever is to move.
+    pub fn to_fen(&self, to_move: Option<Player>) -> String {
+        let rows: Vec<String> = self
+            .rows()
+            .map(|row| {
+                let mut text = String::new();
+                let mut empty = 0;
+                for cell in row
+                    .iter()
+                    .chain(std::iter::once(&Cell::Occupied(Player::Head)))
+                {
+                    // The extra piece at the end flushes the last run.
+                    let player = match cell {
+                        Cell::Empty => {
+                            empty += 1;
+                            continue;
+                        }
+                        Cell::Occupied(player) => *player,
+                    };
+                    while empty > 0 {
+                        let run = empty.min(26);
+                        text.push((b'a' + run as u8 - 1) as char);
+                        empty -= run;
+                    }
+                    text.push(symbol(player));
+                }
+                text.pop();
+                text
+            })
+            .collect();
+        let win_condition = match self.config.win_condition() {
+            WinCondition::FillTarget => String::from("fill"),
+            WinCondition::FirstNPiecesHome(n) => format!("home:{}", n),
+            WinCondition::ScoreAtTurnLimit(n) => format!("score:{}", n),
+            WinCondition::Elimination => String::from("eliminate"),
+        };
+        let mut variant = String::from(match self.config.variant() {
+            Variant::Standard => "standard",
+            Variant::Misere => "misere",
+        });
+        if self.config.captures() {
+            variant.push_str("+capture");
+        }
+        if self.config.revisits() {
+            variant.push_str("+revisit");
+        }
+        if self.config.forfeit_rule() == ForfeitRule::Clear {
+            variant.push_str("+clear");
+        }
+        format!(
+            "{} {} {} {} {}",
+            rows.join("/"),
+            to_move.map_or('-', symbol),
+            win_condition,
+            variant,
+            self.config.layout().players().len()
+        )
+    }
+
+    /// Inverse of `to_fen`, giving the position and whoever is to move.
+    pub fn from_fen(text: &str) -> Result<(Self, Option<Player>), ParseError> {
+        let fields: Vec<&str> = text.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(ParseError::InvalidField);
+        }
+
+        let lines: Vec<&str> = fields[0].split('/').collect();
+        let pl = lines.len() / 4;
+        if lines.len() % 4 != 1 || pl < 1 || pl > MAX_PLAYER_LINES as usize {
+            return Err(ParseError::WrongRowCount);
+        }
+        let mut rows = vec![];
+        for (index, line) in lines.iter().enumerate() {
+            let mut row = vec![];
+            for c in line.chars() {
+                match c {
+                    'a'..='z' => {
+                        let run = (c as u8 - b'a' + 1) as usize;
+                        row.resize(row.len() + run, Cell::Empty);
+                    }
+                    _ => row.push(Cell::Occupied(player(c).ok_or(ParseError::UnknownSymbol)?)),
+                }
+            }
+            if row.len() != row_len(pl, index) {
+                return Err(ParseError::WrongRowLength);
+            }
+            rows.push(row);
+        }
+
+        let to_move = match fields[1] {
+            "-" => None,
+            x => {
+                let mut chars = x.chars();
+                match (chars.next().and_then(player), chars.next()) {
+                    (Some(player), None) => Some(player),
+                    _ => return Err(ParseError::UnknownSymbol),
+                }
+            }
+        };
+        let win_condition = parse_win_condition(fields[2]).ok_or(ParseError::InvalidField)?;
+        let (variant, forfeit_rule) = match fields[3].strip_suffix("+clear") {
+            Some(variant) => (variant, ForfeitRule::Clear),
+            None => (fields[3], ForfeitRule::Freeze),
+        };
+        let (variant, revisits) = match variant.strip_suffix("+revisit") {
+            Some(variant) => (variant, true),
+            None => (variant, false),
+        };
+        let (variant, captures) = match variant.strip_suffix("+capture") {
+            Some(variant) => (variant, true),
+            None => (variant, false),
+        };
+        let variant = match variant {
+            "standard" => Variant::Standard,
+            "misere" => Variant::Misere,
+            _ => return Err(ParseError::InvalidField),
+        };
+        let layout = fields[4]
+            .parse()
+            .ok()
+            .and_then(StartLayout::for_players)
+            .ok_or(ParseError::InvalidField)?;
+
+        let config = Config::default()
+            .with_player_lines(pl as i32)
+            .with_win_condition(win_condition)
+            .with_variant(variant)
+            .with_captures(captures)
+            .with_revisits(revisits)
+            .with_forfeit_rule(forfeit_rule)
+            .with_layout(layout);
+        Ok((Board::from_rows(rows, config), to_move))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Point;
+
+    #[test]
+    fn test_to_fen() {
+        let board = Board::new(Config::default().with_player_lines(1));
+        assert_eq!(
+            board.to_fen(Some(Player::Head)),
+            "1/3b5/c/6b4/2 1 fill standard 6"
+        );
+        assert_eq!(
+            Board::default().to_fen(None).split(' ').next().unwrap(),
+            "1/11/111/1111/3333e5555/333f555/33g55/3h5/i/6h4/66g44/666f444/6666e4444/2222/222/22/2",
+        );
+    }
+
+    #[test]
+    fn test_fen_round_trip() {
+        let config = Config::default()
+            .with_win_condition(WinCondition::FirstNPiecesHome(5))
+            .with_variant(Variant::Misere)
+            .with_layout(StartLayout::ThreePlayers);
+        let mut board = Board::new(config);
+        board
+            .move_piece(Point::new(4, 10), Point::new(5, 11), Player::Head)
+            .unwrap();
+        let fen = board.to_fen(Some(Player::RightFoot));
+        assert_eq!(Board::from_fen(&fen), Ok((board, Some(Player::RightFoot))));
+
+        let mut large = Board::empty(Config::default().with_player_lines(9));
+        large.set_piece(large.center(), Player::Tail).unwrap();
+        let fen = large.to_fen(None);
+        assert_eq!(Board::from_fen(&fen), Ok((large, None)));
+
+        let capture = Board::new(
+            Config::default()
+                .with_player_lines(1)
+                .with_captures(true)
+                .with_revisits(true)
+                .with_forfeit_rule(ForfeitRule::Clear)
+                .with_win_condition(WinCondition::Elimination),
+        );
+        let fen = capture.to_fen(None);
+        assert!(fen.ends_with(" - eliminate standard+capture+revisit+clear 6"));
+        assert_eq!(Board::from_fen(&fen), Ok((capture, None)));
+    }
+
+    #[test]
+    fn test_from_fen_with_errors() {
+        assert_eq!(
+            Board::from_fen("1/3b5/c/6b4/2 1"),
+            Err(ParseError::InvalidField)
+        );
+        assert_eq!(
+            Board::from_fen("1/3b5/c/6b4 1 fill standard 6"),
+            Err(ParseError::WrongRowCount),
+        );
+        assert_eq!(
+            Board::from_fen("a 1 fill standard 6"),
+            Err(ParseError::WrongRowCount),
+        );
+        assert_eq!(
+            Board::from_fen("1/3c5/c/6b4/2 1 fill standard 6"),
+            Err(ParseError::WrongRowLength),
+        );
+        assert_eq!(
+            Board::from_fen("1/3b5/c/6b7/2 1 fill standard 6"),
+            Err(ParseError::UnknownSymbol),
+        );
+        assert_eq!(
+            Board::from_fen("1/3b5/c/6b4/2 1 fill standard 5"),
+            Err(ParseError::InvalidField),
+        );
+    }
+}
@@ -0,0 +1,146 @@
+//! Axial hex coordinates, as an alternative to the padded rows and columns
+//! of `Point`.
+//!
+//! `Hex { q: 0, r: 0 }` is the center of the board. Moving east adds 1 to
+//! `q`, and moving southeast adds 1 to `r`, so the six neighbors of a spot
+//! are found by adding one of `Hex::DIRECTIONS`. The third cube coordinate
+//! is `s = -q - r`, and the distance between two spots is half the sum of
+//! the differences in `q`, `r`, and `s`.
+
+use crate::{Board, Point};
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Hex {
+    pub q: i32,
+    pub r: i32,
+}
+
+impl Hex {
+    /// Offsets to the six neighbors, starting east and going
+    /// counterclockwise, in the same order as `Point`'s directions.
+    pub const DIRECTIONS: [Hex; 6] = [
+        Hex { q: 1, r: 0 },
+        Hex { q: 1, r: -1 },
+        Hex { q: 0, r: -1 },
+        Hex { q: -1, r: 0 },
+        Hex { q: -1, r: 1 },
+        Hex { q: 0, r: 1 },
+    ];
+
+    pub fn new(q: i32, r: i32) -> Self {
+        Self { q, r }
+    }
+
+    /// The third cube coordinate, so that `q + r + s == 0`.
+    pub fn s(self) -> i32 {
+        -self.q - self.r
+    }
+
+    /// Number of steps to another spot, ignoring any pieces in the way.
+    pub fn distance(self, other: Hex) -> i32 {
+        ((self.q - other.q).abs() + (self.r - other.r).abs() + (self.s() - other.s()).abs()) / 2
+    }
+
+    /// The spots next to this one, whether or not they are on a board.
+    pub fn neighbors(self) -> impl Iterator<Item = Hex> {
+        Hex::DIRECTIONS
+            .iter()
+            .map(move |x| Hex::new(self.q + x.q, self.r + x.r))
+    }
+}
+
+/// Anything that names a spot on a board, so that the movement methods of
+/// `Board` accept either coordinate system.
+pub trait Location: Copy {
+    fn to_point(self, board: &Board) -> Point;
+}
+
+impl Location for Point {
+    fn to_point(self, _board: &Board) -> Point {
+        self
+    }
+}
+
+impl Location for Hex {
+    fn to_point(self, board: &Board) -> Point {
+        board.from_hex(self)
+    }
+}
+
+impl Board {
+    /// Axial coordinates of a point. Points off the board convert too,
+    /// as long as they are in the same grid.
+    pub fn to_hex(&self, point: Point) -> Hex {
+        let center = self.center();
+        let r = point.row - center.row;
+        Hex::new((point.column - center.column - r) / 2, r)
+    }
+
+    /// The point at axial coordinates, which may be off the board.
+    pub fn from_hex(&self, hex: Hex) -> Point {
+        let center = self.center();
+        Point::new(center.row + hex.r, center.column + 2 * hex.q + hex.r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cell, Config, Player};
+
+    #[test]
+    fn test_conversions() {
+        let board = Board::default();
+        assert_eq!(board.to_hex(board.center()), Hex::new(0, 0));
+        assert_eq!(board.to_hex(Point::new(1, 13)), Hex::new(4, -8));
+        assert_eq!(board.from_hex(Hex::new(4, -8)), Point::new(1, 13));
+
+        let board = Board::new(Config::default().with_player_lines(1));
+        for point in board
+            .pieces_of(Player::Head)
+            .chain(board.pieces_of(Player::LeftHand))
+        {
+            assert_eq!(board.from_hex(board.to_hex(point)), point);
+        }
+    }
+
+    #[test]
+    fn test_distance_and_neighbors() {
+        let board = Board::default();
+        let top = board.to_hex(Point::new(1, 13));
+        let bottom = board.to_hex(Point::new(17, 13));
+        assert_eq!(top.distance(bottom), 16);
+        assert_eq!(
+            top.distance(bottom),
+            Point::new(1, 13).hex_distance(Point::new(17, 13))
+        );
+
+        let center = board.center();
+        let neighbors: Vec<Point> = Hex::new(0, 0)
+            .neighbors()
+            .map(|x| board.from_hex(x))
+            .collect();
+        for neighbor in &neighbors {
+            assert_eq!(center.hex_distance(*neighbor), 1);
+        }
+        assert_eq!(neighbors[0], Point::new(center.row, center.column + 2));
+    }
+
+    #[test]
+    fn test_movement_with_hexes() {
+        let mut board = Board::default();
+        let source = board.to_hex(Point::new(4, 10));
+        let target = Hex::new(source.q, source.r + 1);
+        assert_eq!(board.get_piece(source), Some(Cell::Occupied(Player::Head)));
+        assert_eq!(board.move_piece(source, target, Player::Head), Ok(()));
+        assert_eq!(
+            board.get_piece(Point::new(5, 11)),
+            Some(Cell::Occupied(Player::Head))
+        );
+        assert_eq!(board.set_piece(Hex::new(0, 0), Player::Tail), Ok(()));
+        assert_eq!(
+            board.get_piece(board.center()),
+            Some(Cell::Occupied(Player::Tail))
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Import of transcripts written in turn notation, one turn per line.
+//!
+//! Turns are read and applied one at a time, so a transcript can be
+//! streamed in while it is still being written. Players move in the given
+//! order, skipping those who have finished. Blank lines and lines starting
+//! with `#` are ignored. Import stops at the first line that cannot be
+//! played, and the error says which line it was and why.
+
+use crate::{Board, Game, GameError, ParseError, Player, Turn, TurnRecord};
+use std::fmt;
+use std::io::{self, BufRead};
+
+#[derive(Debug)]
+pub enum Reason {
+    Read(io::Error),
+    Parse(ParseError),
+    Illegal(GameError),
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Reason::Read(e) => write!(f, "{}", e),
+            Reason::Parse(e) => write!(f, "{}", e),
+            Reason::Illegal(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct ImportError {
+    /// Line number in the transcript, starting from 1.
+    pub line: usize,
+    pub reason: Reason,
+}
+
+impl fmt::Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Importer {
+    game: Game,
+    lines: usize,
+}
+
+impl Importer {
+    pub fn new(board: Board, players: Vec<Player>) -> Self {
+        Self {
+            game: Game::new(board, players),
+            lines: 0,
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.game.board
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Every turn applied so far.
+    pub fn turns(&self) -> &[TurnRecord] {
+        self.game.history()
+    }
+
+    /// Apply the next line of the transcript. Returns the turn that was
+    /// played, or `None` if the line was blank or a comment.
+    pub fn feed(&mut self, line: &str) -> Result<Option<(Player, Turn)>, ImportError> {
+        self.lines += 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+        let number = self.lines;
+        let error = |reason| ImportError {
+            line: number,
+            reason,
+        };
+        let player = self
+            .game
+            .current_player()
+            .ok_or_else(|| error(Reason::Illegal(GameError::GameFinished)))?;
+        let turn: Turn = line.parse().map_err(|e| error(Reason::Parse(e)))?;
+        self.game
+            .play_turn(&turn)
+            .map_err(|e| error(Reason::Illegal(e)))?;
+        Ok(Some((player, turn)))
+    }
+
+    /// Read and apply lines until the input ends. Returns how many turns
+    /// were played.
+    pub fn run<R: BufRead>(&mut self, input: R) -> Result<usize, ImportError> {
+        let mut count = 0;
+        for line in input.lines() {
+            let line = line.map_err(|e| ImportError {
+                line: self.lines + 1,
+                reason: Reason::Read(e),
+            })?;
+            if self.feed(&line)?.is_some() {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn importer() -> Importer {
+        Importer::new(Board::default(), vec![Player::Head, Player::Tail])
+    }
+
+    #[test]
+    fn test_run() {
+        let mut importer = importer();
+        let text = "# opening\n4/10-5/11\n\n14/16 13/15\n3/11>5/13>5/9\n";
+        assert_eq!(importer.run(text.as_bytes()).unwrap(), 3);
+        assert_eq!(importer.turns()[2].player, Player::Head);
+        assert_eq!(
+            importer.board().get_piece(crate::Point::new(5, 9)),
+            Some(crate::Cell::Occupied(Player::Head))
+        );
+    }
+
+    #[test]
+    fn test_run_with_illegal_turn() {
+        let mut importer = importer();
+        let error = importer
+            .run("4/10-5/11\n4/12-5/13\n".as_bytes())
+            .unwrap_err();
+        assert_eq!(error.line, 2);
+        assert_eq!(
+            error.to_string(),
+            "line 2: The piece at 4/12 belongs to Head, not Tail."
+        );
+        assert_eq!(importer.turns().len(), 1);
+    }
+
+    #[test]
+    fn test_run_with_bad_notation() {
+        let error = importer().run("\nsomewhere\n".as_bytes()).unwrap_err();
+        assert_eq!(error.line, 2);
+        match error.reason {
+            Reason::Parse(ParseError::InvalidPoint) => {}
+            reason => panic!("unexpected reason: {}", reason),
+        }
+    }
+}
@@ -1,675 +1,4735 @@
+pub mod ai;
+pub mod analysis;
+pub mod archive;
+pub mod chat;
+pub mod clock;
+pub mod coach;
+pub mod crowd;
+pub mod editor;
+pub mod engine;
+mod fen;
+pub mod hex;
+pub mod import;
+pub mod ndjson;
+#[cfg(feature = "net")]
+pub mod net;
+#[cfg(feature = "nn")]
+pub mod nn;
+mod notation;
+pub mod positions;
+#[cfg(feature = "profile")]
+pub mod profile;
+pub mod puzzle;
+#[cfg(feature = "image")]
+pub mod raster;
+pub mod record;
+pub mod replay;
+mod save;
+#[cfg(feature = "net")]
+pub mod simul;
+pub mod tikz;
+pub mod tournament;
+pub mod training;
+#[cfg(feature = "tui")]
+pub mod tui;
+pub mod turn_builder;
+pub mod turn_log;
+pub mod variation;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
 use maplit::hashmap;
-use std::cmp::max;
 use std::collections::HashMap;
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// Largest number of lines per corner that `Config::new` accepts. Bigger
+/// boards still work, but their diagrams no longer fit most terminals.
+pub const MAX_PLAYER_LINES: i32 = 10;
 
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Config {
     player_lines: i32,
-    symbols: HashMap<Piece, String>,
+    symbols: HashMap<Cell, String>,
+    /// Starting lines for players that differ from `player_lines`.
+    corner_lines: HashMap<Player, i32>,
+    /// Players who start somewhere other than their own corner.
+    starts: HashMap<Player, Player>,
+    /// Players whose target is not opposite of where they start.
+    targets: HashMap<Player, Player>,
+    win_condition: WinCondition,
+    goal_rule: GoalRule,
+    variant: Variant,
+    layout: StartLayout,
+    color_mode: ColorMode,
+    /// Whether `Board::render` adds row and column numbers.
+    labels: bool,
+    /// How many times a position may come up before the game is drawn.
+    repetition_limit: Option<usize>,
+    /// How many turns may pass without progress before the game is drawn.
+    progress_limit: Option<usize>,
+    /// Whether jumping over another player's piece removes it.
+    captures: bool,
+    /// Whether a jump chain may land on a spot that it already visited.
+    revisits: bool,
+    forfeit_rule: ForfeitRule,
+    /// Seed for the random choices of computer players.
+    seed: Option<u64>,
 }
 
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            player_lines: 4,
-            symbols: hashmap!(
-                Piece::Head => String::from("1"),
-                Piece::Tail => String::from("2"),
-                Piece::LeftHand => String::from("3"),
-                Piece::RightHand => String::from("5"),
-                Piece::LeftFoot => String::from("6"),
-                Piece::RightFoot => String::from("4"),
-                Piece::Empty => String::from("."),
-            ),
-        }
-    }
+/// What a player has to achieve for the game to consider them finished.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum WinCondition {
+    /// Fill every spot of the target corner that the player started with.
+    FillTarget,
+    /// Get this many pieces into the target corner.
+    FirstNPiecesHome(usize),
+    /// Play this many rounds, then rank players by pieces in their target.
+    ScoreAtTurnLimit(usize),
+    /// Capture every piece of the other players. Only makes sense with
+    /// `Config::with_captures`.
+    Elimination,
 }
 
-/// The overall, padded row and column indices for piece locations.
-/// For example, the topmost piece on a standard board is
-/// `Point { row: 1, column: 13 }`, despite the row only having one piece,
-/// because there are 12 columns to the left in other rows.
+/// Which pieces fill the spots of a player's target corner, as far as
+/// `Board::has_player_won` is concerned.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-pub struct Point {
-    row: i32,
-    column: i32,
+pub enum GoalRule {
+    /// Only the player's own pieces.
+    OwnPieces,
+    /// Also pieces of whoever started in that corner and never moved them
+    /// out, so that parking pieces at home cannot keep an opponent from
+    /// winning. At least one of the spots must hold the player's own piece.
+    HomeBlockers,
+    /// Any piece, as long as at least one of them is the player's own.
+    /// This is the rule in most published rulebooks, since a single
+    /// opponent left behind in the corner would otherwise make it
+    /// impossible to win.
+    AnyOccupant,
 }
 
-impl Point {
-    pub fn new(row: i32, column: i32) -> Self {
-        Self { row, column }
+/// What happens to the pieces of a player who resigns or runs out of time.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ForfeitRule {
+    /// The pieces stay where they are and can still be jumped over.
+    Freeze,
+    /// The pieces are taken off the board.
+    Clear,
+}
+
+/// Which corners are in play, following the standard setups for each
+/// number of players. Corners of players who are not in play stay empty.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum StartLayout {
+    /// `Head` and `Tail`.
+    TwoPlayers,
+    /// Every other corner, so that each player's target starts out empty.
+    ThreePlayers,
+    /// Two pairs of opposite corners.
+    FourPlayers,
+    SixPlayers,
+}
+
+impl StartLayout {
+    /// The layout for a number of players, if there is a standard one.
+    pub fn for_players(count: usize) -> Option<Self> {
+        match count {
+            2 => Some(StartLayout::TwoPlayers),
+            3 => Some(StartLayout::ThreePlayers),
+            4 => Some(StartLayout::FourPlayers),
+            6 => Some(StartLayout::SixPlayers),
+            _ => None,
+        }
+    }
+
+    /// Players in play, in turn order.
+    pub fn players(self) -> Vec<Player> {
+        use Player::*;
+        match self {
+            StartLayout::TwoPlayers => vec![Head, Tail],
+            StartLayout::ThreePlayers => vec![Head, RightFoot, LeftFoot],
+            StartLayout::FourPlayers => vec![Head, Tail, LeftHand, RightFoot],
+            StartLayout::SixPlayers => vec![Head, Tail, LeftHand, RightFoot, RightHand, LeftFoot],
+        }
     }
 }
 
-/// The internal vector-based row and column indices for piece locations.
-/// For example, the topmost piece on a standard board is
-/// `IndexPair { row: 0, column: 0 }`, e.g., `rows[0][0]`.
+/// Built-in sets of symbols for drawing the pieces.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
-struct IndexPair {
-    row: usize,
-    column: usize,
+pub enum SymbolTheme {
+    /// Digits for the players and `.` for empty spots.
+    Ascii,
+    /// Filled and hollow shapes, with `·` for empty spots.
+    Unicode,
+    /// Colored circles. Most terminals draw these twice as wide as other
+    /// characters, so the board looks stretched.
+    Emoji,
 }
 
-impl IndexPair {
-    fn new(row: usize, column: usize) -> Self {
-        Self { row, column }
+impl SymbolTheme {
+    pub fn symbols(self) -> HashMap<Cell, String> {
+        let [head, tail, left_hand, right_hand, left_foot, right_foot, empty] = match self {
+            SymbolTheme::Ascii => ["1", "2", "3", "5", "6", "4", "."],
+            SymbolTheme::Unicode => ["●", "○", "◆", "◇", "■", "□", "·"],
+            SymbolTheme::Emoji => ["🔴", "🔵", "🟢", "🟠", "🟣", "🟡", "⚪"],
+        };
+        hashmap!(
+            Cell::Occupied(Player::Head) => String::from(head),
+            Cell::Occupied(Player::Tail) => String::from(tail),
+            Cell::Occupied(Player::LeftHand) => String::from(left_hand),
+            Cell::Occupied(Player::RightHand) => String::from(right_hand),
+            Cell::Occupied(Player::LeftFoot) => String::from(left_foot),
+            Cell::Occupied(Player::RightFoot) => String::from(right_foot),
+            Cell::Empty => String::from(empty),
+        )
     }
 }
 
-#[derive(Clone, Debug, derive_error::Error, Eq, PartialEq)]
-pub enum GameError {
-    /// Tried to move piece from wrong player.
-    WrongPlayer,
-    /// Point does not exist on board.
-    OutOfBounds,
-    /// Cannot make it from source point to target point.
-    NoRoute,
-    /// Target point is occupied by another piece.
-    OccupiedTarget,
-    /// Attempt to mix single spot movement and jump chains in one turn.
-    Exhausted,
+/// How `Board::render` draws the pieces.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ColorMode {
+    /// Only the symbols, as in `Board::serialize`.
+    Plain,
+    /// Each player's symbols in their own ANSI color, for terminals.
+    Ansi,
 }
 
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-pub enum Piece {
-    Head,
-    Tail,
-    LeftHand,
-    RightHand,
-    LeftFoot,
-    RightFoot,
-    Empty,
+/// Whether finishing is good or bad for a player.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Variant {
+    /// Players are ranked in the order that they finish.
+    Standard,
+    /// Misère: finishing knocks a player out, so the last one left wins.
+    Misere,
 }
 
-#[derive(Clone, Debug, Eq, PartialEq)]
-pub struct Board {
-    rows: Vec<Vec<Piece>>,
-    config: Config,
+impl Variant {
+    /// Final standings from best to worst, given the order in which
+    /// players finished and the ones who never did.
+    pub fn standings(self, finished: &[Player], remaining: &[Player]) -> Vec<Player> {
+        match self {
+            Variant::Standard => finished.iter().chain(remaining).copied().collect(),
+            Variant::Misere => remaining
+                .iter()
+                .chain(finished.iter().rev())
+                .copied()
+                .collect(),
+        }
+    }
 }
 
-impl Board {
-    pub fn new(config: Config) -> Self {
-        let player_lines = config.player_lines;
-        Self {
-            rows: {
-                let mut rows = Vec::<Vec<Piece>>::new();
+impl Config {
+    /// Settings for a board with this many lines of pieces in each corner,
+    /// from 1 to `MAX_PLAYER_LINES`, and the defaults for everything else.
+    pub fn new(player_lines: i32) -> Result<Self, ConfigError> {
+        if player_lines < 1 {
+            Err(ConfigError::TooFewLines)
+        } else if player_lines > MAX_PLAYER_LINES {
+            Err(ConfigError::TooManyLines)
+        } else {
+            Ok(Self::default().with_player_lines(player_lines))
+        }
+    }
 
-                for n in 1..=player_lines {
-                    let mut row = Vec::<Piece>::new();
-                    for _ in 0..n {
-                        row.push(Piece::Head);
-                    }
-                    rows.push(row);
-                }
+    /// Change the size of the board. Unlike `Config::new`, this does not
+    /// check that the size makes sense.
+    pub fn with_player_lines(mut self, player_lines: i32) -> Self {
+        self.player_lines = player_lines;
+        self
+    }
 
-                for n in 1..=player_lines {
-                    let mut row = Vec::<Piece>::new();
-                    for _ in 0..player_lines + 1 - n {
-                        row.push(Piece::LeftHand);
-                    }
-                    for _ in 0..player_lines + n {
-                        row.push(Piece::Empty);
-                    }
-                    for _ in 0..player_lines + 1 - n {
-                        row.push(Piece::RightHand);
-                    }
-                    rows.push(row);
-                }
+    /// Start a player with fewer lines of pieces than the board allows,
+    /// for example as a handicap. The player only has to fill that many
+    /// lines of their target corner to win.
+    pub fn with_corner_lines(mut self, player: Player, lines: i32) -> Self {
+        self.corner_lines.insert(player, lines);
+        self
+    }
 
-                rows.push(vec![Piece::Empty; player_lines as usize * 2 + 1]);
+    /// Start a player in a different corner than the one they are named
+    /// after. To swap two players, assign both of them. If two players
+    /// would start in the same corner, the one assigned here wins.
+    pub fn with_start(mut self, player: Player, corner: Player) -> Self {
+        self.starts.insert(player, corner);
+        self
+    }
 
-                for n in (1..=player_lines).rev() {
-                    let mut row = Vec::<Piece>::new();
-                    for _ in 0..player_lines + 1 - n {
-                        row.push(Piece::LeftFoot);
-                    }
-                    for _ in 0..player_lines + n {
-                        row.push(Piece::Empty);
-                    }
-                    for _ in 0..player_lines + 1 - n {
-                        row.push(Piece::RightFoot);
-                    }
-                    rows.push(row);
-                }
+    /// Give a player a target other than the corner opposite their start.
+    pub fn with_target(mut self, player: Player, corner: Player) -> Self {
+        self.targets.insert(player, corner);
+        self
+    }
 
-                for n in (1..=player_lines).rev() {
-                    let mut row = Vec::<Piece>::new();
-                    for _ in 0..n {
-                        row.push(Piece::Tail);
-                    }
-                    rows.push(row);
-                }
+    /// The corner where a player starts.
+    pub fn start_of(&self, player: Player) -> Player {
+        *self.starts.get(&player).unwrap_or(&player)
+    }
 
-                rows
-            },
-            config,
+    /// The corner that a player has to fill.
+    pub fn target_of(&self, player: Player) -> Player {
+        match self.targets.get(&player) {
+            Some(target) => *target,
+            None => self.start_of(player).opposite(),
         }
     }
 
-    pub fn serialize(&self) -> Vec<String> {
-        self.rows
+    /// The player who starts in a corner, or `None` if nobody does.
+    pub fn occupant(&self, corner: Player) -> Option<Player> {
+        let assigned = PLAYERS
             .iter()
-            .map(|row| {
-                format!(
-                    "{}{}",
-                    " ".repeat(self.config.player_lines as usize * 3 + 1 - row.len()),
-                    row.iter()
-                        .map(|piece| format!(" {}", self.config.symbols[piece]))
-                        .collect::<String>(),
-                )
-            })
-            .collect()
+            .find(|player| self.starts.get(player) == Some(&corner));
+        let player = match assigned {
+            Some(player) => *player,
+            None if !self.starts.contains_key(&corner) => corner,
+            None => return None,
+        };
+        Some(player).filter(|x| self.in_play(*x))
     }
 
-    pub fn draw(&self) {
-        for row in self.serialize() {
-            println!("{}", row)
-        }
+    /// Preset for short casual games, where each player only has the
+    /// six pieces in the back three lines of their corner.
+    pub fn quick() -> Self {
+        PLAYERS.iter().fold(Self::default(), |config, corner| {
+            config.with_corner_lines(*corner, 3)
+        })
     }
 
-    pub fn take_turn(&mut self, points: Vec<Point>, player: Piece) -> Result<(), GameError> {
-        if points.len() < 2 {
-            return Err(GameError::NoRoute);
-        }
-        if points.len() > 2 {
-            let mut distances = Vec::<i32>::new();
-            for (index, point) in points[1..].iter().enumerate() {
-                let previous_point = points[index];
-                let distance = match point.row - previous_point.row {
-                    0 => (point.column - previous_point.column).abs() / 2,
-                    x => x.abs(),
-                };
-                distances.push(distance);
-            }
-            if distances.len() > 1 && distances.iter().any(|x| *x != 2) {
-                return Err(GameError::Exhausted);
-            }
-        }
-        for (index, point) in points[1..].iter().enumerate() {
-            self.move_piece(points[index], *point, player)?;
+    /// How a cell, or a player's piece, is drawn on the board.
+    pub fn symbol(&self, cell: impl Into<Cell>) -> &str {
+        &self.symbols[&cell.into()]
+    }
+
+    /// Draw every piece with a built-in set of symbols.
+    pub fn with_symbol_theme(mut self, theme: SymbolTheme) -> Self {
+        self.symbols = theme.symbols();
+        self
+    }
+
+    /// Draw one piece with a custom symbol. The symbol should not contain
+    /// whitespace, or diagrams of the board cannot be read back.
+    pub fn with_symbol(mut self, cell: impl Into<Cell>, symbol: &str) -> Self {
+        self.symbols.insert(cell.into(), symbol.to_string());
+        self
+    }
+
+    pub fn with_win_condition(mut self, win_condition: WinCondition) -> Self {
+        self.win_condition = win_condition;
+        self
+    }
+
+    pub fn win_condition(&self) -> WinCondition {
+        self.win_condition
+    }
+
+    pub fn with_goal_rule(mut self, goal_rule: GoalRule) -> Self {
+        self.goal_rule = goal_rule;
+        self
+    }
+
+    pub fn goal_rule(&self) -> GoalRule {
+        self.goal_rule
+    }
+
+    pub fn with_variant(mut self, variant: Variant) -> Self {
+        self.variant = variant;
+        self
+    }
+
+    pub fn variant(&self) -> Variant {
+        self.variant
+    }
+
+    pub fn with_layout(mut self, layout: StartLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    pub fn layout(&self) -> StartLayout {
+        self.layout
+    }
+
+    pub fn with_color_mode(mut self, color_mode: ColorMode) -> Self {
+        self.color_mode = color_mode;
+        self
+    }
+
+    pub fn color_mode(&self) -> ColorMode {
+        self.color_mode
+    }
+
+    /// Draw the game once the same position, with the same player to move,
+    /// has come up this many times.
+    pub fn with_repetition_limit(mut self, times: usize) -> Self {
+        self.repetition_limit = Some(times);
+        self
+    }
+
+    pub fn repetition_limit(&self) -> Option<usize> {
+        self.repetition_limit
+    }
+
+    /// Draw the game once this many turns in a row have gone by without
+    /// any player getting closer to their target than they have been,
+    /// as measured by `ai::distance_to_goal`, or finishing.
+    pub fn with_progress_limit(mut self, turns: usize) -> Self {
+        self.progress_limit = Some(turns);
+        self
+    }
+
+    pub fn progress_limit(&self) -> Option<usize> {
+        self.progress_limit
+    }
+
+    /// Play the capture variant, where a piece that jumps over another
+    /// player's piece takes it off the board. Jumping over one's own
+    /// pieces is still allowed and removes nothing.
+    pub fn with_captures(mut self, captures: bool) -> Self {
+        self.captures = captures;
+        self
+    }
+
+    pub fn captures(&self) -> bool {
+        self.captures
+    }
+
+    /// Let jump chains land on spots that they already visited, including
+    /// jumping straight back. Such chains can go around in circles, so by
+    /// default a chain may visit each spot only once.
+    pub fn with_revisits(mut self, revisits: bool) -> Self {
+        self.revisits = revisits;
+        self
+    }
+
+    pub fn revisits(&self) -> bool {
+        self.revisits
+    }
+
+    /// Decide what happens to the pieces of players who leave the game
+    /// through `Game::resign` or `Game::forfeit_on_timeout`.
+    pub fn with_forfeit_rule(mut self, rule: ForfeitRule) -> Self {
+        self.forfeit_rule = rule;
+        self
+    }
+
+    pub fn forfeit_rule(&self) -> ForfeitRule {
+        self.forfeit_rule
+    }
+
+    /// Make the computer players that choose at random, such as
+    /// `ai::RandomBot` and `ai::Mcts`, choose the same way every time that
+    /// they see the same game.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Number the rows and columns around rendered boards, so that it is
+    /// easier to tell which point to type.
+    pub fn with_labels(mut self, labels: bool) -> Self {
+        self.labels = labels;
+        self
+    }
+
+    pub fn labels(&self) -> bool {
+        self.labels
+    }
+
+    /// Whether a player takes part according to the layout.
+    pub fn in_play(&self, player: Player) -> bool {
+        self.layout.players().contains(&player)
+    }
+
+    /// Starting lines for a player, limited to what fits on the board.
+    pub fn lines_for(&self, player: Player) -> usize {
+        let lines = *self.corner_lines.get(&player).unwrap_or(&self.player_lines);
+        lines.max(0).min(self.player_lines) as usize
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            player_lines: 4,
+            symbols: SymbolTheme::Ascii.symbols(),
+            corner_lines: HashMap::new(),
+            starts: HashMap::new(),
+            targets: HashMap::new(),
+            win_condition: WinCondition::FillTarget,
+            goal_rule: GoalRule::OwnPieces,
+            variant: Variant::Standard,
+            layout: StartLayout::SixPlayers,
+            color_mode: ColorMode::Plain,
+            labels: false,
+            repetition_limit: None,
+            progress_limit: None,
+            captures: false,
+            revisits: false,
+            forfeit_rule: ForfeitRule::Freeze,
+            seed: None,
         }
-        Ok(())
     }
+}
 
-    pub fn try_turn(&self, points: Vec<Point>, player: Piece) -> Result<(), GameError> {
-        let mut test_board = self.clone();
-        test_board.take_turn(points, player)
+/// The overall, padded row and column indices for piece locations.
+/// For example, the topmost piece on a standard board is
+/// `Point { row: 1, column: 13 }`, despite the row only having one piece,
+/// because there are 12 columns to the left in other rows.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Point {
+    row: i32,
+    column: i32,
+}
+
+impl Point {
+    pub fn new(row: i32, column: i32) -> Self {
+        Self { row, column }
     }
 
-    fn get_index_pair(&self, point: Point) -> Option<IndexPair> {
-        let max_pieces_per_row = self.config.player_lines as usize * 3 + 1;
-        let row = self.rows.get(point.row as usize - 1)?;
-        let mut valid_columns = Vec::<usize>::new();
-        if row.len() == 1 {
-            valid_columns.push(max_pieces_per_row);
+    /// The direction from one point to another and how many spots away
+    /// it is, or `None` unless they are in line along one of `DIRECTIONS`.
+    fn line_to(self, other: Point) -> Option<((i32, i32), i32)> {
+        let (rows, columns) = (other.row - self.row, other.column - self.column);
+        let count = if rows == 0 && columns % 2 == 0 {
+            columns.abs() / 2
+        } else if rows.abs() == columns.abs() {
+            rows.abs()
         } else {
-            let offset = match row.len() % 2 {
-                0 => 1 + 2 * (row.len() / 2 - 1),
-                _ => 2 * ((row.len() - 1) / 2),
-            };
-            for x in (max_pieces_per_row - offset..=max_pieces_per_row + offset).step_by(2) {
-                valid_columns.push(x);
-            }
+            return None;
+        };
+        if count == 0 {
+            return None;
         }
-        Some(IndexPair::new(
-            point.row as usize - 1,
-            valid_columns
-                .iter()
-                .position(|x| *x == point.column as usize)?,
-        ))
+        Some(((rows / count, columns / count), count))
     }
 
-    pub fn get_piece(&self, point: Point) -> Option<Piece> {
-        let pair = self.get_index_pair(point)?;
-        Some(*(self.rows.get(pair.row)?.get(pair.column)?))
+    /// Number of steps to another point, ignoring any pieces in the way.
+    pub fn hex_distance(self, other: Point) -> i32 {
+        let rows = (self.row - other.row).abs();
+        let columns = (self.column - other.column).abs();
+        if columns <= rows {
+            rows
+        } else {
+            rows + (columns - rows) / 2
+        }
     }
 
-    pub fn move_piece(
-        &mut self,
-        source: Point,
-        target: Point,
-        player: Piece,
-    ) -> Result<(), GameError> {
-        let distance = match source.row - target.row {
-            0 => (source.column - target.column).abs() / 2,
-            x => x.abs(),
+    /// The neighboring point in a direction, which may be off the board.
+    pub fn step(self, direction: Direction) -> Point {
+        let (rows, columns) = direction.offset();
+        Point::new(self.row + rows, self.column + columns)
+    }
+
+    /// Where a jump in a direction lands, two steps away.
+    pub fn jump(self, direction: Direction) -> Point {
+        self.step(direction).step(direction)
+    }
+
+    /// Number of spots to another point in a straight line, if there is one.
+    fn spots_to(self, other: Point) -> Option<i32> {
+        self.line_to(other).map(|(_, count)| count)
+    }
+}
+
+/// One player's turn: either a single step to an adjacent spot,
+/// or a chain of jumps over other pieces.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub enum Turn {
+    Step {
+        from: Point,
+        to: Point,
+    },
+    /// Every point visited by the jumping piece, starting where it began.
+    Jumps(Vec<Point>),
+}
+
+impl Turn {
+    /// Build a turn from the points visited by the moving piece,
+    /// rejecting sequences that cannot be a step or a jump chain.
+    pub fn new(points: Vec<Point>) -> Result<Self, GameError> {
+        let turn = match points.as_slice() {
+            [from, to] if from.spots_to(*to) == Some(1) => Turn::Step {
+                from: *from,
+                to: *to,
+            },
+            _ => Turn::Jumps(points),
         };
-        if source == target || distance > 2 {
-            return Err(GameError::NoRoute);
+        turn.check_shape()?;
+        Ok(turn)
+    }
+
+    fn check_shape(&self) -> Result<(), GameError> {
+        match self {
+            Turn::Step { from, to } => {
+                if from.spots_to(*to) != Some(1) {
+                    return Err(GameError::NoRoute {
+                        from: *from,
+                        to: *to,
+                    });
+                }
+            }
+            Turn::Jumps(points) => {
+                if points.len() < 2 {
+                    return Err(GameError::IncompleteTurn);
+                }
+                // Every hop has to be a jump of two spots in a straight line.
+                // A step is only allowed on its own, so a chain that steps
+                // anywhere has exhausted its movement.
+                let distances: Vec<Option<i32>> = points
+                    .windows(2)
+                    .map(|pair| pair[0].spots_to(pair[1]))
+                    .collect();
+                if let Some(index) = distances
+                    .iter()
+                    .position(|x| *x != Some(1) && *x != Some(2))
+                {
+                    return Err(GameError::NoRoute {
+                        from: points[index],
+                        to: points[index + 1],
+                    });
+                }
+                if let Some(index) = distances.iter().position(|x| *x == Some(1)) {
+                    return Err(if distances.len() > 1 {
+                        GameError::Exhausted { segment: index }
+                    } else {
+                        GameError::NoRoute {
+                            from: points[0],
+                            to: points[1],
+                        }
+                    });
+                }
+            }
         }
+        Ok(())
+    }
 
-        let source_piece = self.get_piece(source).ok_or(GameError::OutOfBounds)?;
-        if source_piece != player {
-            return Err(GameError::WrongPlayer);
+    /// Reject chains that land on a spot that they already visited,
+    /// unless the config allows it.
+    fn check_revisits(&self, config: &Config) -> Result<(), GameError> {
+        if config.revisits {
+            return Ok(());
         }
-        let target_piece = self.get_piece(target).ok_or(GameError::OutOfBounds)?;
-        if target_piece != Piece::Empty {
-            return Err(GameError::OccupiedTarget);
+        let points = self.points();
+        match (1..points.len()).find(|x| points[..*x].contains(&points[*x])) {
+            Some(index) => Err(GameError::Revisited {
+                segment: index - 1,
+                point: points[index],
+            }),
+            None => Ok(()),
         }
+    }
 
-        if distance == 2 {
-            let middle_piece = self
-                .get_piece(Point::new(
-                    max(source.row, target.row) - 1,
-                    max(source.column, target.column) - 1,
-                ))
-                .ok_or(GameError::OutOfBounds)?;
-            if middle_piece == Piece::Empty {
-                return Err(GameError::NoRoute);
-            }
+    /// Every point visited by the moving piece, starting where it began.
+    pub fn points(&self) -> Vec<Point> {
+        match self {
+            Turn::Step { from, to } => vec![*from, *to],
+            Turn::Jumps(points) => points.clone(),
         }
+    }
 
-        let source_indices = self.get_index_pair(source).ok_or(GameError::OutOfBounds)?;
-        let target_indices = self.get_index_pair(target).ok_or(GameError::OutOfBounds)?;
-        self.rows[source_indices.row][source_indices.column] = Piece::Empty;
-        self.rows[target_indices.row][target_indices.column] = player;
-        Ok(())
+    pub fn source(&self) -> Point {
+        match self {
+            Turn::Step { from, .. } => *from,
+            Turn::Jumps(points) => points[0],
+        }
     }
 
-    pub fn try_move_piece(
-        &self,
-        source: Point,
-        target: Point,
-        player: Piece,
-    ) -> Result<(), GameError> {
-        let mut test_board = self.clone();
-        test_board.move_piece(source, target, player)
+    pub fn target(&self) -> Point {
+        match self {
+            Turn::Step { to, .. } => *to,
+            Turn::Jumps(points) => points[points.len() - 1],
+        }
     }
 
-    pub fn has_player_won(&self, piece: Piece) -> bool {
-        let pl = self.config.player_lines as usize;
-        let (reversed, increasing, start) = match piece {
-            Piece::Head => (false, false, pl * 3 + 1),
-            Piece::LeftHand => (true, true, pl * 2 + 1),
-            Piece::RightHand => (false, true, pl * 2 + 1),
-            Piece::LeftFoot => (true, false, pl),
-            Piece::RightFoot => (false, false, pl),
-            Piece::Tail => (false, true, 0),
-            Piece::Empty => return false,
-        };
-        for (n, row) in self.rows[start..start + pl].iter().enumerate() {
-            let offset = match increasing {
-                true => n + 1,
-                false => self.config.player_lines as usize - n,
-            };
-            let row_part: Vec<&Piece> = {
-                match reversed {
-                    true => row.iter().rev().take(offset).collect(),
-                    false => row.iter().take(offset).collect(),
-                }
-            };
-            if row_part.iter().any(|x| *x != &piece) {
-                return false;
-            }
+    /// The spots jumped over, in order. A step jumps over nothing.
+    pub fn jumped(&self) -> Vec<Point> {
+        match self {
+            Turn::Step { .. } => vec![],
+            Turn::Jumps(points) => points
+                .windows(2)
+                .map(|pair| {
+                    Point::new(
+                        (pair[0].row + pair[1].row) / 2,
+                        (pair[0].column + pair[1].column) / 2,
+                    )
+                })
+                .collect(),
         }
-        true
     }
 }
 
-impl Default for Board {
-    fn default() -> Self {
-        Self::new(Config::default())
+/// What `Board::explain_turn` found out about one hop of a turn.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Verdict {
+    /// A legal step to a neighboring spot.
+    Step,
+    /// A legal jump over someone's piece, which is taken off the board if
+    /// `captures` is set.
+    Jump {
+        over: Point,
+        owner: Player,
+        captures: bool,
+    },
+    /// The hop breaks the rules for this reason.
+    Illegal(GameError),
+    /// An earlier hop was illegal, so the piece never gets here.
+    Unchecked,
+}
+
+/// One hop of a turn and whether it is legal.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Segment {
+    pub from: Point,
+    pub to: Point,
+    pub verdict: Verdict,
+}
+
+impl std::fmt::Display for Segment {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} to {}: ", self.from, self.to)?;
+        match &self.verdict {
+            Verdict::Step => write!(f, "A step to a neighboring spot."),
+            Verdict::Jump {
+                over,
+                owner,
+                captures: false,
+            } => write!(f, "A jump over {:?}'s piece at {}.", owner, over),
+            Verdict::Jump {
+                over,
+                owner,
+                captures: true,
+            } => write!(f, "A jump that captures {:?}'s piece at {}.", owner, over),
+            Verdict::Illegal(error) => write!(f, "{}", error),
+            Verdict::Unchecked => write!(f, "Not checked, since an earlier hop is illegal."),
+        }
     }
 }
 
+/// Every hop of a turn, from `Board::explain_turn`.
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub struct Game {
-    pub board: Board,
-    pieces: Vec<Piece>,
+pub struct TurnAnalysis {
+    pub segments: Vec<Segment>,
+}
+
+impl TurnAnalysis {
+    /// The first problem with the turn, in the same form as from
+    /// `Board::take_turn`. Problems with the shape of a chain are noticed
+    /// in order of the hops here, so for a turn with several problems, this
+    /// may name a different one.
+    pub fn error(&self) -> Option<GameError> {
+        let (index, error) = match self.segments.as_slice() {
+            [] => return Some(GameError::IncompleteTurn),
+            segments => segments
+                .iter()
+                .enumerate()
+                .find_map(|(index, x)| match &x.verdict {
+                    Verdict::Illegal(error) => Some((index, error.clone())),
+                    _ => None,
+                })?,
+        };
+        Some(match (self.segments.len(), error) {
+            (1, error)
+            | (_, error @ GameError::Exhausted { .. })
+            | (_, error @ GameError::Revisited { .. }) => error,
+            (_, error) => GameError::InChain {
+                segment: index,
+                error: Box::new(error),
+            },
+        })
+    }
+
+    pub fn is_legal(&self) -> bool {
+        self.error().is_none()
+    }
 }
 
-impl Game {
-    pub fn new(board: Board, pieces: Vec<Piece>) -> Self {
-        Self {
-            board,
-            pieces,
+/// The internal row and column indices for piece locations, counting only
+/// the spots in each row. For example, the topmost piece on a standard board
+/// is `IndexPair { row: 0, column: 0 }`, which is bit 0 of the masks.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+struct IndexPair {
+    row: usize,
+    column: usize,
+}
+
+impl IndexPair {
+    fn new(row: usize, column: usize) -> Self {
+        Self { row, column }
+    }
+}
+
+/// Why a turn or an edit was rejected. The variants carry the spots
+/// involved, so that frontends can point at them, and `Display` describes
+/// the problem in a sentence.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GameError {
+    /// Tried to move a piece that the player does not own, or from an
+    /// empty spot.
+    WrongPlayer {
+        point: Point,
+        expected: Player,
+        found: Cell,
+    },
+    /// Point does not exist on board.
+    OutOfBounds(Point),
+    /// Cannot make it from source point to target point in one move.
+    NoRoute { from: Point, to: Point },
+    /// Tried to jump over a spot with no piece on it.
+    EmptyMidpoint {
+        from: Point,
+        to: Point,
+        middle: Point,
+    },
+    /// Target point is occupied by another piece.
+    OccupiedTarget { point: Point, occupant: Player },
+    /// Attempt to mix single spot movement and jump chains in one turn.
+    /// `segment` is the index of the first step among the chain's hops.
+    Exhausted { segment: usize },
+    /// Jump chain landed on a spot that it already visited, which
+    /// `Config::with_revisits` allows. `segment` is the index of the hop.
+    Revisited { segment: usize, point: Point },
+    /// One hop of a chain with several was illegal. `segment` is its
+    /// index among the hops, starting from 0.
+    InChain {
+        segment: usize,
+        error: Box<GameError>,
+    },
+    /// Turn does not visit at least two points.
+    IncompleteTurn,
+    /// Board setup breaks the rules of the game.
+    InvalidSetup,
+    /// Tried to play out of turn.
+    NotYourTurn { player: Player, to_move: Player },
+    /// Player has no legal turn available.
+    NoLegalMoves,
+    /// Tried to play the turn of a crowd-controlled seat before voting
+    /// closed.
+    VotingOpen,
+    /// Voting closed without anyone voting for a turn.
+    NoVotes,
+    /// Game is already over.
+    GameFinished,
+    /// Ran out of time.
+    TimeExpired,
+}
+
+impl std::fmt::Display for GameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GameError::WrongPlayer {
+                point,
+                expected,
+                found,
+            } => match found.player() {
+                Some(owner) => write!(
+                    f,
+                    "The piece at {} belongs to {:?}, not {:?}.",
+                    point, owner, expected
+                ),
+                None => write!(f, "There is no piece at {} to move.", point),
+            },
+            GameError::OutOfBounds(point) => write!(f, "{} is not a spot on the board.", point),
+            GameError::NoRoute { from, to } => {
+                write!(
+                    f,
+                    "Cannot move from {} to {} in one step or jump.",
+                    from, to
+                )
+            }
+            GameError::EmptyMidpoint { from, to, middle } => write!(
+                f,
+                "Cannot jump from {} to {}: midpoint {} is empty.",
+                from, to, middle
+            ),
+            GameError::OccupiedTarget { point, occupant } => {
+                write!(f, "{} is already taken by {:?}.", point, occupant)
+            }
+            GameError::Exhausted { segment } => write!(
+                f,
+                "Hop {} of the chain is a step, and steps cannot be mixed with jumps.",
+                segment + 1
+            ),
+            GameError::Revisited { segment, point } => write!(
+                f,
+                "Hop {} of the chain lands on {} again, and chains cannot visit a spot twice.",
+                segment + 1,
+                point
+            ),
+            GameError::InChain { segment, error } => {
+                write!(f, "Hop {} of the chain is illegal. {}", segment + 1, error)
+            }
+            GameError::IncompleteTurn => {
+                write!(f, "A turn needs a starting spot and a destination.")
+            }
+            GameError::InvalidSetup => write!(f, "Board setup breaks the rules of the game."),
+            GameError::NotYourTurn { player, to_move } => {
+                write!(f, "It is {:?}'s turn, not {:?}'s.", to_move, player)
+            }
+            GameError::NoLegalMoves => write!(f, "Player has no legal turn available."),
+            GameError::VotingOpen => write!(f, "Voting for the turn is still open."),
+            GameError::NoVotes => write!(f, "Nobody voted for a turn."),
+            GameError::GameFinished => write!(f, "Game is already over."),
+            GameError::TimeExpired => write!(f, "Ran out of time."),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+#[derive(Clone, Debug, derive_error::Error, Eq, PartialEq)]
+pub enum ParseError {
+    /// Number of rows does not match the board size.
+    WrongRowCount,
+    /// Number of pieces in a row does not match the board shape.
+    WrongRowLength,
+    /// Symbol does not belong to any piece.
+    UnknownSymbol,
+    /// Point is not written as `row/column`.
+    InvalidPoint,
+    /// Points do not form a step or a jump chain.
+    InvalidTurn,
+    /// Field of a position string is missing or malformed.
+    InvalidField,
+    /// Header of a game record is not written as `[Key "value"]`.
+    InvalidHeader,
+    /// Game record lacks its `Setup` or `Players` header.
+    MissingHeader,
+    /// Comment or variation in a game record is not closed, or a variation
+    /// does not follow a turn.
+    InvalidAnnotation,
+    /// Weights of a network are malformed or its layers do not fit together.
+    InvalidWeights,
+    /// Puzzle collection is not a JSON array of puzzles with a position and
+    /// a solution.
+    InvalidPuzzle,
+}
+
+#[derive(Clone, Debug, derive_error::Error, Eq, PartialEq)]
+pub enum ConfigError {
+    /// Board needs at least one line of pieces in each corner.
+    TooFewLines,
+    /// Board has more lines in each corner than `MAX_PLAYER_LINES`.
+    TooManyLines,
+}
+
+/// One of the six players, named after the corner where they start.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Player {
+    Head,
+    Tail,
+    LeftHand,
+    RightHand,
+    LeftFoot,
+    RightFoot,
+}
+
+/// What is on a spot of the board.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Cell {
+    Empty,
+    Occupied(Player),
+}
+
+impl Cell {
+    /// The player whose piece is here, if any.
+    pub fn player(self) -> Option<Player> {
+        match self {
+            Cell::Empty => None,
+            Cell::Occupied(player) => Some(player),
+        }
+    }
+
+    pub fn is_empty(self) -> bool {
+        self == Cell::Empty
+    }
+}
+
+impl From<Player> for Cell {
+    fn from(player: Player) -> Self {
+        Cell::Occupied(player)
+    }
+}
+
+/// SGR parameter for a cell's color in `ColorMode::Ansi`.
+fn ansi_color(cell: Cell) -> &'static str {
+    match cell.player() {
+        Some(Player::Head) => "31",
+        Some(Player::Tail) => "34",
+        Some(Player::LeftHand) => "32",
+        Some(Player::RightHand) => "36",
+        Some(Player::LeftFoot) => "35",
+        Some(Player::RightFoot) => "33",
+        None => "2",
+    }
+}
+
+/// Every player, in the order of their masks in `Board`.
+const PLAYERS: [Player; 6] = [
+    Player::Head,
+    Player::Tail,
+    Player::LeftHand,
+    Player::RightHand,
+    Player::LeftFoot,
+    Player::RightFoot,
+];
+
+/// Row and column offsets to the six neighbors of a spot.
+const DIRECTIONS: [(i32, i32); 6] = [(0, 2), (-1, 1), (-1, -1), (0, -2), (1, -1), (1, 1)];
+
+/// One of the six directions along the lines of the board.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Direction {
+    East,
+    NorthEast,
+    NorthWest,
+    West,
+    SouthWest,
+    SouthEast,
+}
+
+impl Direction {
+    /// Every direction, starting east and going counterclockwise.
+    pub const ALL: [Direction; 6] = [
+        Direction::East,
+        Direction::NorthEast,
+        Direction::NorthWest,
+        Direction::West,
+        Direction::SouthWest,
+        Direction::SouthEast,
+    ];
+
+    /// Row and column offsets of one step in this direction.
+    pub fn offset(self) -> (i32, i32) {
+        DIRECTIONS[self as usize]
+    }
+
+    pub fn opposite(self) -> Direction {
+        Direction::ALL[(self as usize + 3) % 6]
+    }
+}
+
+impl Player {
+    /// The corner across the board, which is this player's target.
+    pub fn opposite(self) -> Player {
+        match self {
+            Player::Head => Player::Tail,
+            Player::Tail => Player::Head,
+            Player::LeftHand => Player::RightFoot,
+            Player::RightFoot => Player::LeftHand,
+            Player::RightHand => Player::LeftFoot,
+            Player::LeftFoot => Player::RightHand,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Board {
+    config: Config,
+    /// Index of the first spot in each row, followed by the number of spots.
+    /// Spot `starts[row] + column` is bit `starts[row] + column` of a mask.
+    starts: Vec<usize>,
+    /// One bitmask per player, in the order of `PLAYERS`, each taking up
+    /// the same number of words. A spot is empty when no mask has its bit.
+    masks: Vec<u64>,
+    /// Zobrist hash of the masks, kept up to date by `place`.
+    hash: u64,
+}
+
+impl Board {
+    pub fn new(config: Config) -> Self {
+        let pl = config.player_lines as usize;
+        let rows = (0..pl * 4 + 1)
+            .map(|row| {
+                let row_len = row_len(pl, row);
+                (0..row_len)
+                    .map(|column| match corner_line(pl, row, column, row_len) {
+                        Some((corner, line)) => match config.occupant(corner) {
+                            Some(occupant) if line < config.lines_for(occupant) => {
+                                Cell::Occupied(occupant)
+                            }
+                            _ => Cell::Empty,
+                        },
+                        None => Cell::Empty,
+                    })
+                    .collect()
+            })
+            .collect();
+        Self::from_rows(rows, config)
+    }
+
+    fn from_rows(rows: Vec<Vec<Cell>>, config: Config) -> Self {
+        let mut starts = vec![0];
+        for row in &rows {
+            starts.push(starts[starts.len() - 1] + row.len());
+        }
+        let words = starts[rows.len()].div_ceil(64);
+        let mut board = Self {
+            config,
+            starts,
+            masks: vec![0; words * PLAYERS.len()],
+            hash: 0,
+        };
+        for (index, cell) in rows.into_iter().flatten().enumerate() {
+            board.place(index, cell);
+        }
+        board
+    }
+
+    /// Number of spots on the board.
+    fn spot_count(&self) -> usize {
+        self.starts[self.starts.len() - 1]
+    }
+
+    /// Number of words in each player's mask.
+    fn words(&self) -> usize {
+        self.masks.len() / PLAYERS.len()
+    }
+
+    /// A player's bitmask.
+    fn mask(&self, player: Player) -> &[u64] {
+        let (index, words) = (player as usize, self.words());
+        &self.masks[index * words..(index + 1) * words]
+    }
+
+    /// The contents of the rows in order, as the board used to store them.
+    pub(crate) fn rows(&self) -> impl Iterator<Item = Vec<Cell>> + '_ {
+        self.starts
+            .windows(2)
+            .map(move |x| (x[0]..x[1]).map(|index| self.piece_at(index)).collect())
+    }
+
+    /// A compact copy of the pieces, which is much cheaper to take and keep
+    /// around than a clone of the whole board. It does not include the config,
+    /// so it can only be restored onto a board of the same size.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            spots: self.spot_count(),
+            masks: self.masks.clone().into_boxed_slice(),
+        }
+    }
+
+    /// Put the pieces back the way they were when the snapshot was taken.
+    pub fn restore(&mut self, snapshot: &Snapshot) -> Result<(), GameError> {
+        if snapshot.spots != self.spot_count() {
+            return Err(GameError::InvalidSetup);
+        }
+        self.masks.copy_from_slice(&snapshot.masks);
+        self.rehash();
+        Ok(())
+    }
+
+    /// A number that identifies the position, for transposition tables and
+    /// repetition checks. Equal positions on boards of the same size always
+    /// have the same hash, and it is updated with every move rather than
+    /// recomputed. Whose turn it is is not included.
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Compute `hash` from scratch after the masks were replaced.
+    fn rehash(&mut self) {
+        let words = self.words();
+        self.hash = 0;
+        for (index, word) in self.masks.iter().enumerate() {
+            let (player, word_index) = (index / words, index % words);
+            let mut bits = *word;
+            while bits != 0 {
+                let bit = bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+                self.hash ^= zobrist_key(player, word_index * 64 + bit);
+            }
+        }
+    }
+
+    /// A board of the same shape with every spot empty.
+    pub fn empty(config: Config) -> Self {
+        let mut board = Self::new(config);
+        board.clear();
+        board
+    }
+
+    /// Take every piece off the board, keeping its size and config.
+    pub fn clear(&mut self) {
+        for word in self.masks.iter_mut() {
+            *word = 0;
+        }
+        self.hash = 0;
+    }
+
+    /// A board with only the given pieces on it, for puzzles and tests.
+    /// Fails if a point is off the board or given more than once. The
+    /// position is not checked against the rules; see `validate_setup`.
+    pub fn from_setup(placements: &[(Point, Player)], config: Config) -> Result<Self, GameError> {
+        let mut board = Self::empty(config);
+        for (point, player) in placements {
+            match board.get_piece(*point) {
+                Some(Cell::Empty) => board.set_piece(*point, *player)?,
+                Some(Cell::Occupied(_)) => return Err(GameError::InvalidSetup),
+                None => return Err(GameError::OutOfBounds(*point)),
+            }
+        }
+        Ok(board)
+    }
+
+    pub fn serialize(&self) -> Vec<String> {
+        self.rows()
+            .map(|row| {
+                format!(
+                    "{}{}",
+                    " ".repeat(self.config.player_lines as usize * 3 + 1 - row.len()),
+                    row.iter()
+                        .map(|cell| format!(" {}", self.config.symbols[cell]))
+                        .collect::<String>(),
+                )
+            })
+            .collect()
+    }
+
+    /// Inverse of `serialize`. Leading and trailing whitespace is ignored,
+    /// so diagrams that lost their indentation when pasted still load.
+    pub fn from_ascii(lines: &[&str], config: &Config) -> Result<Self, ParseError> {
+        let mut rows: Vec<Vec<Cell>> = Self::empty(config.clone()).rows().collect();
+        let lines: Vec<&str> = lines
+            .iter()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+        if lines.len() != rows.len() {
+            return Err(ParseError::WrongRowCount);
+        }
+        for (row, line) in rows.iter_mut().zip(lines) {
+            let symbols: Vec<&str> = line.split_whitespace().collect();
+            if symbols.len() != row.len() {
+                return Err(ParseError::WrongRowLength);
+            }
+            for (cell, symbol) in row.iter_mut().zip(symbols) {
+                *cell = *config
+                    .symbols
+                    .iter()
+                    .find(|(_, x)| x.as_str() == symbol)
+                    .ok_or(ParseError::UnknownSymbol)?
+                    .0;
+            }
+        }
+        Ok(Self::from_rows(rows, config.clone()))
+    }
+
+    /// Same as `from_ascii`, for the owned lines that `serialize` returns.
+    pub fn deserialize(lines: &[String], config: &Config) -> Result<Self, ParseError> {
+        let lines: Vec<&str> = lines.iter().map(|x| x.as_str()).collect();
+        Self::from_ascii(&lines, config)
+    }
+
+    /// The same diagram as `serialize`, but drawn according to the color
+    /// mode and labels of the config, with the piece at `highlight`
+    /// standing out. Escape codes are only used with `ColorMode::Ansi`.
+    pub fn render(&self, highlight: Option<Point>) -> Vec<String> {
+        let rows = match self.config.color_mode {
+            ColorMode::Plain => self.serialize(),
+            ColorMode::Ansi => (1..=self.row_count())
+                .map(|row| {
+                    let (first, last) = self.column_range(row).unwrap_or((1, 0));
+                    let mut line = " ".repeat(first as usize - 1);
+                    for column in (first..=last).step_by(2) {
+                        let point = Point::new(row, column);
+                        let cell = self.get_piece(point).unwrap_or(Cell::Empty);
+                        let mut code = ansi_color(cell).to_string();
+                        if highlight == Some(point) {
+                            code.push_str(";1;7");
+                        }
+                        line.push_str(&format!(
+                            " \x1B[{}m{}\x1B[0m",
+                            code,
+                            self.config.symbol(cell)
+                        ));
+                    }
+                    line
+                })
+                .collect(),
+        };
+        if self.config.labels {
+            self.label(rows)
+        } else {
+            rows
+        }
+    }
+
+    /// Put row numbers to the left of a diagram and column numbers above
+    /// and below it, written downward when they have two digits.
+    fn label(&self, rows: Vec<String>) -> Vec<String> {
+        let width = self.row_count().to_string().len();
+        let margin = " ".repeat(width + 1);
+        // Each spot's symbol is at the character offset of its column.
+        let columns = 1..=self.config.player_lines * 6 + 1;
+        let digit = |x: i32| char::from_digit(x as u32 % 10, 10).unwrap_or(' ');
+        let tens: String = columns
+            .clone()
+            .map(|x| if x >= 10 { digit(x / 10) } else { ' ' })
+            .collect();
+        let ones: String = columns.map(digit).collect();
+        let mut guide = vec![];
+        if !tens.trim().is_empty() {
+            guide.push(format!("{} {}", margin, tens));
+        }
+        guide.push(format!("{} {}", margin, ones));
+
+        let mut lines = guide.clone();
+        lines.extend(
+            rows.iter()
+                .enumerate()
+                .map(|(index, row)| format!("{:>width$} {}", index + 1, row, width = width)),
+        );
+        lines.extend(guide);
+        lines
+    }
+
+    /// Move a player's piece along a turn. This only checks the movement
+    /// rules, not whose turn it is, so play through `Game` for that.
+    /// With `Config::with_captures`, other players' pieces are removed as
+    /// soon as they are jumped over. If any hop is illegal, the board is
+    /// left as it was.
+    pub fn take_turn(&mut self, turn: &Turn, player: Player) -> Result<(), GameError> {
+        *self = self.after_turn(turn, player)?;
+        Ok(())
+    }
+
+    /// The board after a turn, which is played on a copy so that a hop
+    /// failing partway through a chain does not leave `self` half moved.
+    fn after_turn(&self, turn: &Turn, player: Player) -> Result<Board, GameError> {
+        turn.check_shape()?;
+        turn.check_revisits(&self.config)?;
+        let mut board = self.clone();
+        let points = turn.points();
+        let jumped = turn.jumped();
+        let hops = points.len() - 1;
+        for (index, point) in points[1..].iter().enumerate() {
+            board
+                .move_piece(points[index], *point, player)
+                .map_err(|error| match hops {
+                    1 => error,
+                    _ => GameError::InChain {
+                        segment: index,
+                        error: Box::new(error),
+                    },
+                })?;
+            if let Some(middle) = jumped.get(index) {
+                if board.is_capture(*middle, player) {
+                    board.set_piece(*middle, Cell::Empty)?;
+                }
+            }
+        }
+        Ok(board)
+    }
+
+    /// Check the hops between `points` one at a time for a player's piece,
+    /// for teaching and for pointing out exactly what is wrong with a turn.
+    /// Like `take_turn`, this does not check whose turn it is.
+    pub fn explain_turn(&self, points: &[Point], player: Player) -> TurnAnalysis {
+        let mut board = self.clone();
+        let hops = points.len().saturating_sub(1);
+        let mut failed = false;
+        let mut segments = vec![];
+        for (index, pair) in points.windows(2).enumerate() {
+            let (from, to) = (pair[0], pair[1]);
+            let verdict = if failed {
+                Verdict::Unchecked
+            } else {
+                match from.spots_to(to) {
+                    Some(1) if hops > 1 => {
+                        Verdict::Illegal(GameError::Exhausted { segment: index })
+                    }
+                    _ if !board.config.revisits && points[..=index].contains(&to) => {
+                        Verdict::Illegal(GameError::Revisited {
+                            segment: index,
+                            point: to,
+                        })
+                    }
+                    _ => match board.apply_hop(from, to, player) {
+                        Ok(verdict) => verdict,
+                        Err(error) => Verdict::Illegal(error),
+                    },
+                }
+            };
+            failed = failed || matches!(verdict, Verdict::Illegal(_));
+            segments.push(Segment { from, to, verdict });
+        }
+        TurnAnalysis { segments }
+    }
+
+    /// Move a player's piece by one step or jump, capturing what it jumps
+    /// over if the config says so. Whether a step may be mixed with jumps
+    /// is left to the caller. An illegal hop leaves the board as it was.
+    pub(crate) fn apply_hop(
+        &mut self,
+        from: Point,
+        to: Point,
+        player: Player,
+    ) -> Result<Verdict, GameError> {
+        if !matches!(from.spots_to(to), Some(1) | Some(2)) {
+            return Err(GameError::NoRoute { from, to });
+        }
+        let middle = Point::new((from.row + to.row) / 2, (from.column + to.column) / 2);
+        let owner = (from.spots_to(to) == Some(2))
+            .then(|| self.get_piece(middle).and_then(Cell::player))
+            .flatten();
+        self.move_piece(from, to, player)?;
+        Ok(match owner {
+            None => Verdict::Step,
+            Some(owner) => {
+                let captures = self.is_capture(middle, player);
+                if captures {
+                    self.set_piece(middle, Cell::Empty)
+                        .expect("the jumped piece is on the board");
+                }
+                Verdict::Jump {
+                    over: middle,
+                    owner,
+                    captures,
+                }
+            }
+        })
+    }
+
+    fn is_capture(&self, point: Point, player: Player) -> bool {
+        match self.get_piece(point) {
+            Some(Cell::Occupied(other)) => self.config.captures && other != player,
+            _ => false,
+        }
+    }
+
+    /// The pieces that a turn would capture, with their owners, in the
+    /// order that they are jumped. This is empty unless the config has
+    /// captures.
+    pub fn captured_by(&self, turn: &Turn, player: Player) -> Vec<(Point, Player)> {
+        turn.jumped()
+            .into_iter()
+            .filter(|x| self.is_capture(*x, player))
+            .filter_map(|x| Some((x, self.get_piece(x)?.player()?)))
+            .collect()
+    }
+
+    pub fn try_turn(&self, turn: &Turn, player: Player) -> Result<(), GameError> {
+        self.after_turn(turn, player).map(|_| ())
+    }
+
+    /// Number of rows on the board. Rows are numbered starting from 1.
+    pub fn row_count(&self) -> i32 {
+        self.starts.len() as i32 - 1
+    }
+
+    /// The spot in the middle of the board.
+    pub fn center(&self) -> Point {
+        Point::new(
+            self.config.player_lines * 2 + 1,
+            self.config.player_lines * 3 + 1,
+        )
+    }
+
+    /// The first and last column with a spot in a row. Every other column
+    /// in between has a spot as well, since neighbors are two columns apart.
+    pub fn column_range(&self, row: i32) -> Option<(i32, i32)> {
+        if row < 1 {
+            return None;
+        }
+        let max_pieces_per_row = self.config.player_lines * 3 + 1;
+        let row = row as usize;
+        if row >= self.starts.len() {
+            return None;
+        }
+        let offset = (self.starts[row] - self.starts[row - 1]) as i32 - 1;
+        Some((max_pieces_per_row - offset, max_pieces_per_row + offset))
+    }
+
+    /// The top left and bottom right corners of the padded grid
+    /// that every point on the board fits in.
+    pub fn extents(&self) -> (Point, Point) {
+        (
+            Point::new(1, 1),
+            Point::new(self.row_count(), self.config.player_lines * 6 + 1),
+        )
+    }
+
+    /// Radius of a spot when the board is drawn to fit in a unit square.
+    /// Neighboring spots touch, so this is half of the distance between them.
+    pub fn cell_radius(&self) -> f64 {
+        let pl = f64::from(self.config.player_lines);
+        1.0 / (2.0 * (2.0 * 3f64.sqrt() * pl + 1.0))
+    }
+
+    /// Center of a spot when the board is drawn to fit in a unit square,
+    /// as `(x, y)` with `y` increasing downward like the rows do.
+    /// The board is taller than it is wide, so it is centered horizontally.
+    pub fn cell_center(&self, point: Point) -> Option<(f64, f64)> {
+        self.get_index_pair(point)?;
+        let radius = self.cell_radius();
+        let width = f64::from(self.config.player_lines * 3 + 1) * 2.0 * radius;
+        let x = (1.0 - width) / 2.0 + radius + f64::from(point.column - 1) * radius;
+        let y = radius + f64::from(point.row - 1) * 3f64.sqrt() * radius;
+        Some((x, y))
+    }
+
+    fn get_index_pair(&self, point: Point) -> Option<IndexPair> {
+        let (first, last) = self.column_range(point.row)?;
+        if point.column < first || point.column > last || (point.column - first) % 2 != 0 {
+            return None;
+        }
+        Some(IndexPair::new(
+            point.row as usize - 1,
+            ((point.column - first) / 2) as usize,
+        ))
+    }
+
+    fn get_point(&self, pair: IndexPair) -> Point {
+        let (first, _) = self.column_range(pair.row as i32 + 1).unwrap();
+        Point::new(pair.row as i32 + 1, first + 2 * pair.column as i32)
+    }
+
+    /// Position of a spot's bit in the masks.
+    fn bit_index(&self, pair: IndexPair) -> usize {
+        self.starts[pair.row] + pair.column
+    }
+
+    /// The point for a position in the masks.
+    fn bit_point(&self, index: usize) -> Point {
+        let row = self.starts.partition_point(|x| *x <= index) - 1;
+        self.get_point(IndexPair::new(row, index - self.starts[row]))
+    }
+
+    fn piece_at(&self, index: usize) -> Cell {
+        let (word, bit) = (index / 64, 1 << (index % 64));
+        let words = self.words();
+        PLAYERS
+            .iter()
+            .enumerate()
+            .find(|(player, _)| self.masks[player * words + word] & bit != 0)
+            .map_or(Cell::Empty, |(_, player)| Cell::Occupied(*player))
+    }
+
+    pub fn get_piece(&self, location: impl hex::Location) -> Option<Cell> {
+        let pair = self.get_index_pair(location.to_point(self))?;
+        Some(self.piece_at(self.bit_index(pair)))
+    }
+
+    /// Place a player's piece directly, ignoring the movement rules.
+    /// Use `Cell::Empty` to remove whatever is there.
+    pub fn set_piece(
+        &mut self,
+        location: impl hex::Location,
+        cell: impl Into<Cell>,
+    ) -> Result<(), GameError> {
+        let point = location.to_point(self);
+        let pair = self
+            .get_index_pair(point)
+            .ok_or(GameError::OutOfBounds(point))?;
+        self.place(self.bit_index(pair), cell.into());
+        Ok(())
+    }
+
+    /// Set a spot's bit in the mask of the player on it, if any, and clear
+    /// it in the others, updating the hash for every bit that changes.
+    fn place(&mut self, index: usize, cell: Cell) {
+        let (word, bit) = (index / 64, 1 << (index % 64));
+        let words = self.words();
+        for (player, x) in PLAYERS.iter().enumerate() {
+            let mask = &mut self.masks[player * words + word];
+            if (*mask & bit != 0) != (cell == Cell::Occupied(*x)) {
+                *mask ^= bit;
+                self.hash ^= zobrist_key(player, index);
+            }
+        }
+    }
+
+    /// The locations of a player's pieces, in row and column order.
+    pub fn pieces_of(&self, player: Player) -> impl Iterator<Item = Point> + '_ {
+        self.mask(player)
+            .iter()
+            .enumerate()
+            .flat_map(move |(word, bits)| {
+                let mut bits = *bits;
+                std::iter::from_fn(move || {
+                    if bits == 0 {
+                        return None;
+                    }
+                    let bit = bits.trailing_zeros() as usize;
+                    bits &= bits - 1;
+                    Some(self.bit_point(word * 64 + bit))
+                })
+            })
+    }
+
+    /// Every spot on the board and what is on it, in row and column order.
+    pub fn cells(&self) -> impl Iterator<Item = (Point, Cell)> + '_ {
+        (0..self.spot_count()).map(move |index| (self.bit_point(index), self.piece_at(index)))
+    }
+
+    /// Deal with the pieces of a player who left the game, according to
+    /// `Config::forfeit_rule`.
+    fn apply_forfeit(&mut self, player: Player) {
+        if self.config.forfeit_rule == ForfeitRule::Clear {
+            let pieces: Vec<Point> = self.pieces_of(player).collect();
+            for point in pieces {
+                self.set_piece(point, Cell::Empty)
+                    .expect("pieces are on the board");
+            }
+        }
+    }
+
+    /// Check that a hand-made setup could be played: there must be at least
+    /// one player, and nobody can have more pieces than fit in a corner.
+    pub fn validate_setup(&self) -> Result<(), GameError> {
+        let pl = self.config.player_lines as usize;
+        let corner_size = pl * (pl + 1) / 2;
+        let mut counts = HashMap::<Player, usize>::new();
+        for player in PLAYERS.iter() {
+            let count = self
+                .mask(*player)
+                .iter()
+                .map(|x| x.count_ones())
+                .sum::<u32>();
+            if count > 0 {
+                counts.insert(*player, count as usize);
+            }
+        }
+        if counts.is_empty() || counts.values().any(|x| *x > corner_size) {
+            return Err(GameError::InvalidSetup);
+        }
+        Ok(())
+    }
+
+    pub fn move_piece(
+        &mut self,
+        source: impl hex::Location,
+        target: impl hex::Location,
+        player: Player,
+    ) -> Result<(), GameError> {
+        let (source, target) = (source.to_point(self), target.to_point(self));
+        let source_piece = self
+            .get_piece(source)
+            .ok_or(GameError::OutOfBounds(source))?;
+        let target_piece = self
+            .get_piece(target)
+            .ok_or(GameError::OutOfBounds(target))?;
+        let (direction, distance) = match source.line_to(target) {
+            Some((direction, distance)) if distance <= 2 => (direction, distance),
+            _ => {
+                return Err(GameError::NoRoute {
+                    from: source,
+                    to: target,
+                })
+            }
+        };
+
+        if source_piece != Cell::Occupied(player) {
+            return Err(GameError::WrongPlayer {
+                point: source,
+                expected: player,
+                found: source_piece,
+            });
+        }
+        if let Cell::Occupied(occupant) = target_piece {
+            return Err(GameError::OccupiedTarget {
+                point: target,
+                occupant,
+            });
+        }
+
+        if distance == 2 {
+            // A jump covers two steps in one direction,
+            // so the jumped spot is one step along.
+            let middle = Point::new(source.row + direction.0, source.column + direction.1);
+            let middle_piece = self
+                .get_piece(middle)
+                .ok_or(GameError::OutOfBounds(middle))?;
+            if middle_piece == Cell::Empty {
+                return Err(GameError::EmptyMidpoint {
+                    from: source,
+                    to: target,
+                    middle,
+                });
+            }
+        }
+
+        let source_indices = self.get_index_pair(source).expect("checked above");
+        let target_indices = self.get_index_pair(target).expect("checked above");
+        self.place(self.bit_index(source_indices), Cell::Empty);
+        self.place(self.bit_index(target_indices), Cell::Occupied(player));
+        Ok(())
+    }
+
+    pub fn try_move_piece(
+        &self,
+        source: impl hex::Location,
+        target: impl hex::Location,
+        player: Player,
+    ) -> Result<(), GameError> {
+        let mut test_board = self.clone();
+        test_board.move_piece(source, target, player)
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Number of a player's pieces in their target, counting only
+    /// as many lines of the opposite corner as they started with.
+    pub fn pieces_home(&self, player: Player) -> usize {
+        let pl = self.config.player_lines as usize;
+        let lines = self.config.lines_for(player);
+        self.pieces_of(player)
+            .filter(|point| {
+                let pair = self.get_index_pair(*point).unwrap();
+                let row_len = self.starts[pair.row + 1] - self.starts[pair.row];
+                match corner_line(pl, pair.row, pair.column, row_len) {
+                    Some((corner, line)) => corner == self.config.target_of(player) && line < lines,
+                    None => false,
+                }
+            })
+            .count()
+    }
+
+    /// Whether a player is done according to the configured win condition.
+    /// With `WinCondition::ScoreAtTurnLimit`, nobody finishes early.
+    pub fn has_player_finished(&self, player: Player) -> bool {
+        match self.config.win_condition {
+            WinCondition::FillTarget => self.has_player_won(player),
+            WinCondition::FirstNPiecesHome(n) => self.pieces_home(player) >= n,
+            WinCondition::ScoreAtTurnLimit(_) => false,
+            WinCondition::Elimination => PLAYERS
+                .iter()
+                .filter(|x| **x != player)
+                .all(|x| self.pieces_of(*x).next().is_none()),
+        }
+    }
+
+    /// The spots next to a point that are on the board.
+    pub fn neighbors(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        Direction::ALL
+            .iter()
+            .map(move |x| point.step(*x))
+            .filter(move |x| self.get_piece(*x).is_some())
+    }
+
+    /// Every turn available to the piece at a point: steps to each empty
+    /// neighbor, plus the shortest jump chain to each spot it can reach
+    /// by jumping. Each spot comes up once, so frontends can highlight the
+    /// targets of these turns when a player selects a piece, and play the
+    /// one whose target is picked next.
+    pub fn destinations(&self, from: Point) -> Vec<Turn> {
+        match self.get_piece(from) {
+            None | Some(Cell::Empty) => return vec![],
+            _ => {}
+        }
+        let mut turns = Vec::<Turn>::new();
+        for to in self.neighbors(from) {
+            if self.get_piece(to) == Some(Cell::Empty) {
+                turns.push(Turn::Step { from, to });
+            }
+        }
+
+        // The moving piece has left its starting spot, so that spot
+        // cannot be jumped over later in the chain.
+        let mut visited = vec![from];
+        let mut chains = vec![vec![from]];
+        let mut next = 0;
+        while next < chains.len() {
+            let chain = chains[next].clone();
+            next += 1;
+            let last = chain[chain.len() - 1];
+            for direction in Direction::ALL.iter() {
+                let middle = last.step(*direction);
+                let landing = last.jump(*direction);
+                let jumpable = match self.get_piece(middle) {
+                    Some(cell) => !cell.is_empty() && middle != from,
+                    None => false,
+                };
+                if jumpable
+                    && self.get_piece(landing) == Some(Cell::Empty)
+                    && !visited.contains(&landing)
+                {
+                    visited.push(landing);
+                    let mut longer = chain.clone();
+                    longer.push(landing);
+                    turns.push(Turn::Jumps(longer.clone()));
+                    chains.push(longer);
+                }
+            }
+        }
+        turns
+    }
+
+    /// The spot at the tip of a corner, farthest from the center.
+    pub fn tip(&self, corner: Player) -> Point {
+        let pl = self.config.player_lines;
+        let (row, column) = match corner {
+            Player::Head => (1, pl * 3 + 1),
+            Player::Tail => (pl * 4 + 1, pl * 3 + 1),
+            Player::LeftHand => (pl + 1, 1),
+            Player::RightHand => (pl + 1, pl * 6 + 1),
+            Player::LeftFoot => (pl * 3 + 1, 1),
+            Player::RightFoot => (pl * 3 + 1, pl * 6 + 1),
+        };
+        Point::new(row, column)
+    }
+
+    /// Move the piece at the start of a turn to its end without checking
+    /// any rules, for searches that have already generated legal turns.
+    /// Gives back the pieces it captured, which `unmake_turn` needs to
+    /// put everything back.
+    pub fn make_turn(&mut self, turn: &Turn) -> Vec<(Point, Player)> {
+        let captured = match self.get_piece(turn.source()).and_then(Cell::player) {
+            Some(player) => self.captured_by(turn, player),
+            None => vec![],
+        };
+        for (point, _) in &captured {
+            self.set_piece(*point, Cell::Empty)
+                .expect("captured pieces are on the board");
+        }
+        self.shift(turn.source(), turn.target());
+        captured
+    }
+
+    pub fn unmake_turn(&mut self, turn: &Turn, captured: &[(Point, Player)]) {
+        self.shift(turn.target(), turn.source());
+        for (point, player) in captured {
+            self.set_piece(*point, *player)
+                .expect("captured pieces are on the board");
+        }
+    }
+
+    fn shift(&mut self, from: Point, to: Point) {
+        let from_pair = self.get_index_pair(from).expect("turn is on the board");
+        let to_pair = self.get_index_pair(to).expect("turn is on the board");
+        let (from, to) = (self.bit_index(from_pair), self.bit_index(to_pair));
+        let cell = self.piece_at(from);
+        self.place(from, Cell::Empty);
+        self.place(to, cell);
+    }
+
+    /// Every turn that a player could take right now.
+    pub fn legal_turns(&self, player: Player) -> Vec<Turn> {
+        self.pieces_of(player)
+            .flat_map(|point| self.destinations(point))
+            .collect()
+    }
+
+    /// Whether a player has filled their target corner. Only as many lines
+    /// as the player started with are counted, beginning from the tip.
+    /// Which pieces count toward filling it depends on the goal rule.
+    /// Players who are not in play according to the layout never win.
+    pub fn has_player_won(&self, player: Player) -> bool {
+        if !self.config.in_play(player) {
+            return false;
+        }
+        let pl = self.config.player_lines as usize;
+        let target = self.config.target_of(player);
+        let lines = self.config.lines_for(player);
+        let mut own = false;
+        for (row_index, row) in self.rows().enumerate() {
+            for (column_index, cell) in row.iter().enumerate() {
+                match corner_line(pl, row_index, column_index, row.len()) {
+                    Some((corner, line)) if corner == target && line < lines => {
+                        own |= *cell == Cell::Occupied(player);
+                        let filled = match (self.config.goal_rule, cell.player()) {
+                            (_, Some(x)) if x == player => true,
+                            (GoalRule::OwnPieces, _) | (_, None) => false,
+                            (GoalRule::HomeBlockers, Some(x)) => self.config.start_of(x) == target,
+                            (GoalRule::AnyOccupant, Some(_)) => true,
+                        };
+                        if !filled {
+                            return false;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        own || lines == 0
+    }
+}
+
+/// The random number that `Board::hash` mixes in for a player's piece on a
+/// spot. It is derived from the two indices with SplitMix64 instead of being
+/// looked up in a table, so every board size gets keys without any setup,
+/// and hashes stay the same from one run to the next.
+fn zobrist_key(player: usize, index: usize) -> u64 {
+    let mut x = ((player as u64) << 32 | index as u64).wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+/// Number of spots in a row of the internal representation.
+fn row_len(player_lines: usize, row: usize) -> usize {
+    let pl = player_lines;
+    if row < pl {
+        row + 1
+    } else if row < pl * 2 {
+        pl * 4 + 1 - row
+    } else if row <= pl * 3 {
+        row + 1
+    } else {
+        pl * 4 + 1 - row
+    }
+}
+
+/// Which corner a spot belongs to, if any, and which line of that corner
+/// it is on, counting from 0 at the corner's tip.
+fn corner_line(
+    player_lines: usize,
+    row: usize,
+    column: usize,
+    row_len: usize,
+) -> Option<(Player, usize)> {
+    let pl = player_lines;
+    let from_right = row_len - 1 - column;
+    if row < pl {
+        Some((Player::Head, row))
+    } else if row < pl * 2 {
+        let j = row - pl;
+        if column < pl - j {
+            Some((Player::LeftHand, j + column))
+        } else if from_right < pl - j {
+            Some((Player::RightHand, j + from_right))
+        } else {
+            None
+        }
+    } else if row == pl * 2 {
+        None
+    } else if row <= pl * 3 {
+        let j = pl * 3 - row;
+        if column < pl - j {
+            Some((Player::LeftFoot, j + column))
+        } else if from_right < pl - j {
+            Some((Player::RightFoot, j + from_right))
+        } else {
+            None
+        }
+    } else {
+        Some((Player::Tail, pl * 4 - row))
+    }
+}
+
+/// The player masks of a board, as taken by `Board::snapshot`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Snapshot {
+    spots: usize,
+    masks: Box<[u64]>,
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new(Config::default())
+    }
+}
+
+/// How automatically played turns are paced. Without a delay, a
+/// computer's move can appear in the same frame as the previous human
+/// move, which makes it easy to miss what happened.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Pacing {
+    /// Pause before each automatic turn is applied, after
+    /// `GameEvent::TurnStarted` is sent.
+    pub delay: Duration,
+}
+
+/// Whether a game still accepts turns.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GameState {
+    InProgress,
+    Finished,
+    /// Stopped early by one of the draw rules in the config.
+    Drawn(DrawReason),
+}
+
+/// Which draw rule ended a game.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DrawReason {
+    /// See `Config::with_repetition_limit`.
+    Repetition,
+    /// See `Config::with_progress_limit`.
+    NoProgress,
+}
+
+impl std::fmt::Display for DrawReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DrawReason::Repetition => write!(f, "the same position came up too many times"),
+            DrawReason::NoProgress => write!(f, "nobody made progress for too long"),
+        }
+    }
+}
+
+/// Whether a person or the computer decides the turns of a seat.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Controller {
+    Human,
+    Bot,
+}
+
+/// Who sits in a seat of a `Game`, for frontends to show and save along
+/// with the game.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SeatInfo {
+    pub name: String,
+    pub rating: Option<u32>,
+    pub controller: Controller,
+}
+
+impl SeatInfo {
+    /// An unrated human, named after the player's corner.
+    pub fn new(player: Player) -> Self {
+        Self {
+            name: format!("{:?}", player),
+            rating: None,
+            controller: Controller::Human,
+        }
+    }
+
+    pub fn with_name(mut self, name: &str) -> Self {
+        self.name = name.to_string();
+        self
+    }
+
+    pub fn with_rating(mut self, rating: u32) -> Self {
+        self.rating = Some(rating);
+        self
+    }
+
+    pub fn with_controller(mut self, controller: Controller) -> Self {
+        self.controller = controller;
+        self
+    }
+}
+
+/// A turn that was accepted by a `Game`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TurnRecord {
+    /// Position in the game, starting from 1.
+    pub number: usize,
+    pub player: Player,
+    pub turn: Turn,
+    /// Pieces that the turn captured, with their owners.
+    pub captured: Vec<(Point, Player)>,
+    /// How long the player took, for turns from `Game::play_timed_turn`.
+    pub spent: Option<Duration>,
+    /// Total time taken by every timed turn so far, including this one.
+    pub timestamp: Option<Duration>,
+    /// Time that the player had left on the clock after the turn, in a
+    /// game with a clock.
+    pub clock_remaining: Option<Duration>,
+    /// What an engine made of the position after the turn, for the player
+    /// who took it, as set by `Game::set_turn_evaluation`.
+    pub evaluation: Option<i32>,
+    pub comment: Option<String>,
+}
+
+/// How a player's game went, as reported by `Game::result`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    /// Reached their target. In the misère variant, this knocks them out.
+    Finished,
+    /// Lost all of their pieces to captures.
+    Eliminated,
+    /// Left the game through `Game::resign`.
+    Resigned,
+    /// Left the game through `Game::forfeit_on_timeout`.
+    TimedOut,
+    /// Still in the game when the result was taken.
+    Playing,
+}
+
+/// One player's line in a `GameResult`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Placing {
+    pub player: Player,
+    pub outcome: Outcome,
+    /// Number of turns that the player took.
+    pub turns: usize,
+    /// Number of turns that had been played in the whole game when the
+    /// player finished, was eliminated, or resigned.
+    pub left_after: Option<usize>,
+}
+
+/// The results of a game so far, for frontends to show however they like.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameResult {
+    pub state: GameState,
+    /// Everyone who started the game, ranked as in `Game::standings`,
+    /// followed by players who resigned or ran out of time, the earliest
+    /// one at the end.
+    pub placings: Vec<Placing>,
+    /// Number of turns played by everyone.
+    pub turns: usize,
+    pub rounds: usize,
+}
+
+impl GameResult {
+    /// The best-placed player, once the game has finished without a draw.
+    pub fn winner(&self) -> Option<Player> {
+        match self.state {
+            GameState::Finished => self.placings.first().map(|x| x.player),
+            GameState::InProgress | GameState::Drawn(_) => None,
+        }
+    }
+
+    /// The line for a player, or `None` if they were not in the game.
+    pub fn placing(&self, player: Player) -> Option<&Placing> {
+        self.placings.iter().find(|x| x.player == player)
+    }
+}
+
+/// Something that happened while `Game::play` or `Game::play_bots` was
+/// running, so that frontends can show the game however suits them.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GameEvent {
+    /// A player is about to move, before the pause from the pacing.
+    TurnStarted(Player),
+    TurnTaken(TurnRecord),
+    /// A player reached their target with the turn just taken.
+    PlayerFinished(Player),
+    /// A player's time ran out before they moved, so they are out.
+    TimedOut(Player),
+    /// The game ended, either by finishing or in a draw.
+    GameOver(GameResult),
+}
+
+/// Turn order and results for a game on a board. Frontends ask whose turn
+/// it is, submit that player's turn, and check whether the game is over.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Game {
+    pub board: Board,
+    /// Everyone who started the game, in turn order.
+    seats: Vec<Player>,
+    /// Who sits in each of `seats`, in the same order.
+    seat_info: Vec<SeatInfo>,
+    /// Players still in the game, in turn order.
+    playing: Vec<Player>,
+    /// Index in `playing` of whoever is to move.
+    current: usize,
+    /// Players who have finished, in the order that they did.
+    finished: Vec<Player>,
+    /// Players who lost all of their pieces to captures, in the order
+    /// that they did.
+    eliminated: Vec<Player>,
+    rounds: usize,
+    /// The board before the first turn, for replaying the history.
+    start: Board,
+    history: Vec<TurnRecord>,
+    /// Players who resigned or ran out of time, with how many turns had
+    /// been played at the time and which of the two it was.
+    resignations: Vec<(usize, Player, Outcome)>,
+    /// Hash of every position so far and who was to move in it.
+    positions: Vec<(u64, Option<Player>)>,
+    /// The closest that each player has been to their target.
+    best_distances: HashMap<Player, i32>,
+    /// Turns since someone got closer to their target than ever before.
+    quiet_turns: usize,
+    drawn: Option<DrawReason>,
+    pacing: Pacing,
+    clock: Option<clock::Clock>,
+    /// Pauses and corrections to the clock, with how many turns had been
+    /// played at the time.
+    clock_changes: Vec<(usize, clock::Change)>,
+}
+
+impl Game {
+    /// Start a game where `players` take turns in the given order.
+    pub fn new(board: Board, players: Vec<Player>) -> Self {
+        Self {
+            start: board.clone(),
+            positions: vec![(board.hash(), players.first().copied())],
+            best_distances: players
+                .iter()
+                .map(|x| (*x, ai::distance_to_goal(&board, *x)))
+                .collect(),
+            quiet_turns: 0,
+            drawn: None,
+            board,
+            seat_info: players.iter().map(|x| SeatInfo::new(*x)).collect(),
+            seats: players.clone(),
+            playing: players,
+            current: 0,
+            finished: vec![],
+            eliminated: vec![],
+            rounds: 0,
+            history: vec![],
+            resignations: vec![],
+            pacing: Pacing::default(),
+            clock: None,
+            clock_changes: vec![],
+        }
+    }
+
+    pub fn set_pacing(&mut self, pacing: Pacing) {
+        self.pacing = pacing;
+    }
+
+    /// Put everyone who started the game on a clock, for turns played
+    /// through `play_timed_turn`.
+    pub fn set_time_control(&mut self, control: clock::TimeControl) {
+        self.clock = Some(clock::Clock::new(control, &self.seats));
+        self.clock_changes.clear();
+    }
+
+    pub fn clock(&self) -> Option<&clock::Clock> {
+        self.clock.as_ref()
+    }
+
+    /// Stop the clock until `resume_clock`, so that timed turns cost
+    /// nothing and nobody runs out of time. Returns whether there was a
+    /// running clock to pause.
+    pub fn pause_clock(&mut self) -> bool {
+        self.change_clock(clock::Change::Pause)
+    }
+
+    /// Start the clock again after `pause_clock`. Returns whether it was
+    /// paused.
+    pub fn resume_clock(&mut self) -> bool {
+        self.change_clock(clock::Change::Resume)
+    }
+
+    /// Correct how much time a player has left, for the rest of the game
+    /// or, with `TimeControl::PerMove`, for their next turn. Returns
+    /// whether they are on the clock.
+    pub fn set_remaining_time(&mut self, player: Player, remaining: Duration) -> bool {
+        self.change_clock(clock::Change::SetRemaining(player, remaining))
+    }
+
+    fn change_clock(&mut self, change: clock::Change) -> bool {
+        let changed = self.clock.as_mut().is_some_and(|x| x.apply(change));
+        if changed {
+            self.clock_changes.push((self.history.len(), change));
+        }
+        changed
+    }
+
+    /// Everyone who started the game, in turn order.
+    pub fn seats(&self) -> &[Player] {
+        &self.seats
+    }
+
+    /// Who sits in a player's seat, or `None` if they are not in the game.
+    pub fn seat_info(&self, player: Player) -> Option<&SeatInfo> {
+        let index = self.seats.iter().position(|x| *x == player)?;
+        self.seat_info.get(index)
+    }
+
+    /// Change who sits in a player's seat. Returns whether they have a
+    /// seat in the game.
+    pub fn set_seat_info(&mut self, player: Player, info: SeatInfo) -> bool {
+        match self.seats.iter().position(|x| *x == player) {
+            Some(index) => {
+                self.seat_info[index] = info;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The game is over once fewer than two players are left, or once
+    /// the round limit of `WinCondition::ScoreAtTurnLimit` is reached.
+    /// It can also end in a draw, if the config has draw rules.
+    pub fn state(&self) -> GameState {
+        let out_of_rounds = match self.board.config().win_condition() {
+            WinCondition::ScoreAtTurnLimit(limit) => self.rounds >= limit,
+            _ => false,
+        };
+        if self.playing.len() < 2 || out_of_rounds {
+            GameState::Finished
+        } else if let Some(reason) = self.drawn {
+            GameState::Drawn(reason)
+        } else {
+            GameState::InProgress
+        }
+    }
+
+    /// Whose turn it is, or `None` once the game is over.
+    pub fn current_player(&self) -> Option<Player> {
+        match self.state() {
+            GameState::InProgress => Some(self.playing[self.current]),
+            GameState::Finished | GameState::Drawn(_) => None,
+        }
+    }
+
+    /// Players still in the game, in turn order.
+    pub fn playing(&self) -> &[Player] {
+        &self.playing
+    }
+
+    /// Players who have finished, in the order that they did.
+    pub fn finished(&self) -> &[Player] {
+        &self.finished
+    }
+
+    /// Players who lost all of their pieces to captures, in the order
+    /// that they did.
+    pub fn eliminated(&self) -> &[Player] {
+        &self.eliminated
+    }
+
+    /// Number of rounds that every remaining player has moved in.
+    pub fn rounds(&self) -> usize {
+        self.rounds
+    }
+
+    /// The current position as a single line, as in `Board::to_fen`.
+    pub fn to_fen(&self) -> String {
+        self.board.to_fen(self.current_player())
+    }
+
+    /// Every accepted turn, in the order that they were played.
+    pub fn history(&self) -> &[TurnRecord] {
+        &self.history
+    }
+
+    /// The board as it was after a number of turns, where 0 is the
+    /// starting position, or `None` if that many turns were not played.
+    pub fn position_after(&self, turns: usize) -> Option<Board> {
+        let records = self.history.get(..turns)?;
+        let mut board = self.start.clone();
+        for index in 0..=turns {
+            for (_, player, _) in self.resignations.iter().filter(|x| x.0 == index) {
+                board.apply_forfeit(*player);
+            }
+            if let Some(record) = records.get(index) {
+                board
+                    .take_turn(&record.turn, record.player)
+                    .expect("recorded turns were legal when played");
+            }
+        }
+        Some(board)
+    }
+
+    /// Play a turn for the current player, given as the points it visits.
+    pub fn submit_turn(&mut self, points: Vec<Point>) -> Result<Turn, GameError> {
+        let turn = Turn::new(points)?;
+        self.play_turn(&turn)?;
+        Ok(turn)
+    }
+
+    /// Play a turn on behalf of a player, who has to be the one to move.
+    /// This is for frontends where several people can send turns at any
+    /// time. Players who have finished or left are never to move.
+    pub fn play_turn_as(&mut self, player: Player, turn: &Turn) -> Result<(), GameError> {
+        let to_move = self.current_player().ok_or(GameError::GameFinished)?;
+        if to_move != player {
+            return Err(GameError::NotYourTurn { player, to_move });
+        }
+        self.play_turn(turn)
+    }
+
+    /// Play a turn for the current player, then pass the turn on to the
+    /// next player still in the game. If the turn is illegal, the game is
+    /// left as it was.
+    pub fn play_turn(&mut self, turn: &Turn) -> Result<(), GameError> {
+        self.play_turn_with_time(turn, None)
+    }
+
+    /// Play a turn for the current player that took them `spent` to choose,
+    /// charging it to their clock if there is one. If they took longer than
+    /// they had left, the turn is not played, they are taken out of the
+    /// game as with `forfeit_on_timeout`, and this returns
+    /// `GameError::TimeExpired`. An illegal turn costs no time.
+    pub fn play_timed_turn(&mut self, turn: &Turn, spent: Duration) -> Result<(), GameError> {
+        self.play_turn_with_time(turn, Some(spent))
+    }
+
+    fn play_turn_with_time(
+        &mut self,
+        turn: &Turn,
+        spent: Option<Duration>,
+    ) -> Result<(), GameError> {
+        let player = self.current_player().ok_or(GameError::GameFinished)?;
+        if let (Some(clock), Some(spent)) = (&self.clock, spent) {
+            if clock.is_flagged(player, spent) {
+                self.forfeit_on_timeout(player);
+                return Err(GameError::TimeExpired);
+            }
+        }
+        let board = self.board.after_turn(turn, player)?;
+        if let (Some(clock), Some(spent)) = (&mut self.clock, spent) {
+            clock.charge(player, spent)?;
+        }
+        let captured = self.board.captured_by(turn, player);
+        self.board = board;
+        let timestamp = spent.map(|spent| {
+            let before = self.history.iter().rev().find_map(|x| x.timestamp);
+            before.unwrap_or_default() + spent
+        });
+        self.history.push(TurnRecord {
+            number: self.history.len() + 1,
+            player,
+            turn: turn.clone(),
+            captured,
+            spent,
+            timestamp,
+            clock_remaining: self.clock.as_ref().and_then(|x| x.remaining(player)),
+            evaluation: None,
+            comment: None,
+        });
+        let finished = self.board.has_player_finished(player);
+        if finished {
+            self.playing.remove(self.current);
+            self.finished.push(player);
+        } else {
+            self.current += 1;
+        }
+        let mut index = 0;
+        while index < self.playing.len() {
+            let other = self.playing[index];
+            if other == player || self.board.pieces_of(other).next().is_some() {
+                index += 1;
+                continue;
+            }
+            self.playing.remove(index);
+            self.eliminated.push(other);
+            if index < self.current {
+                self.current -= 1;
+            }
+        }
+        if self.current >= self.playing.len() {
+            self.current = 0;
+            self.rounds += 1;
+        }
+        self.check_draw_rules(player, finished);
+        Ok(())
+    }
+
+    /// Record the position after a turn by `player`, and end the game in
+    /// a draw if that broke one of the rules.
+    fn check_draw_rules(&mut self, player: Player, finished: bool) {
+        let distance = ai::distance_to_goal(&self.board, player);
+        let best = self.best_distances.entry(player).or_insert(distance);
+        if finished || distance < *best {
+            *best = distance.min(*best);
+            self.quiet_turns = 0;
+        } else {
+            self.quiet_turns += 1;
+        }
+
+        let position = (self.board.hash(), self.playing.get(self.current).copied());
+        self.positions.push(position);
+        let config = self.board.config();
+        if let Some(limit) = config.repetition_limit() {
+            if self.positions.iter().filter(|x| **x == position).count() >= limit {
+                self.drawn = Some(DrawReason::Repetition);
+            }
+        }
+        if let Some(limit) = config.progress_limit() {
+            if self.quiet_turns >= limit {
+                self.drawn = Some(DrawReason::NoProgress);
+            }
+        }
+    }
+
+    /// Take a player out of the game because they gave up. What happens
+    /// to their pieces depends on `Config::forfeit_rule`. Returns whether
+    /// they were still playing.
+    pub fn resign(&mut self, player: Player) -> bool {
+        self.forfeit(player, Outcome::Resigned)
+    }
+
+    /// Take a player out of the game because their time ran out, the same
+    /// way as `resign`. Returns whether they were still playing.
+    pub fn forfeit_on_timeout(&mut self, player: Player) -> bool {
+        self.forfeit(player, Outcome::TimedOut)
+    }
+
+    fn forfeit(&mut self, player: Player, outcome: Outcome) -> bool {
+        let index = match self.playing.iter().position(|x| *x == player) {
+            Some(index) => index,
+            None => return false,
+        };
+        self.playing.remove(index);
+        self.resignations
+            .push((self.history.len(), player, outcome));
+        if index < self.current {
+            self.current -= 1;
+        }
+        if self.current >= self.playing.len() {
+            self.current = 0;
+        }
+        self.board.apply_forfeit(player);
+        if let (Some(clock), Outcome::TimedOut) = (&mut self.clock, outcome) {
+            clock.flag(player);
+        }
+        true
+    }
+
+    /// Take back the last turn, along with any resignations and changes to
+    /// the clock since, by replaying the game from the start. Returns the
+    /// turn that was taken back, or `None` if no turns have been played.
+    pub fn undo(&mut self) -> Option<TurnRecord> {
+        let last = self.history.last()?.clone();
+        let mut game = Game::new(self.start.clone(), self.seats.clone());
+        game.pacing = self.pacing;
+        game.seat_info = self.seat_info.clone();
+        if let Some(clock) = &self.clock {
+            game.set_time_control(clock.control());
+        }
+        for (index, record) in self.history.iter().enumerate() {
+            for (_, player, outcome) in self.resignations.iter().filter(|x| x.0 == index) {
+                game.forfeit(*player, *outcome);
+            }
+            for (_, change) in self.clock_changes.iter().filter(|x| x.0 == index) {
+                game.change_clock(*change);
+            }
+            if index + 1 < self.history.len() {
+                game.play_turn_with_time(&record.turn, record.spent)
+                    .expect("recorded turns were legal when played");
+            }
+        }
+        for (record, old) in game.history.iter_mut().zip(&self.history) {
+            record.evaluation = old.evaluation;
+            record.comment = old.comment.clone();
+        }
+        *self = game;
+        Some(last)
+    }
+
+    /// Note what an engine made of the last turn in its `TurnRecord`.
+    /// Returns whether any turn has been played.
+    pub fn set_turn_evaluation(&mut self, evaluation: Option<i32>) -> bool {
+        match self.history.last_mut() {
+            Some(record) => {
+                record.evaluation = evaluation;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Attach a comment to the last turn in its `TurnRecord`. Returns
+    /// whether any turn has been played.
+    pub fn set_turn_comment(&mut self, comment: Option<String>) -> bool {
+        match self.history.last_mut() {
+            Some(record) => {
+                record.comment = comment;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Players from best to worst. With `WinCondition::ScoreAtTurnLimit`,
+    /// they are ranked by pieces in their target; otherwise by the variant.
+    /// Players who were eliminated come last, the earliest one at the end.
+    pub fn standings(&self) -> Vec<Player> {
+        let mut players =
+            if let WinCondition::ScoreAtTurnLimit(_) = self.board.config().win_condition() {
+                let mut players = self.playing.clone();
+                players.sort_by_key(|piece| std::cmp::Reverse(self.board.pieces_home(*piece)));
+                players
+            } else {
+                self.board
+                    .config()
+                    .variant()
+                    .standings(&self.finished, &self.playing)
+            };
+        players.extend(self.eliminated.iter().rev());
+        players
+    }
+
+    /// Standings, turn counts, and how everyone left the game, whether or
+    /// not it is over yet.
+    pub fn result(&self) -> GameResult {
+        let placing = |player: Player| {
+            let turns = self.history.iter().filter(|x| x.player == player).count();
+            let (outcome, left_after) = if self.finished.contains(&player) {
+                let last = self.history.iter().rev().find(|x| x.player == player);
+                (Outcome::Finished, last.map(|x| x.number))
+            } else if self.eliminated.contains(&player) {
+                let last = self
+                    .history
+                    .iter()
+                    .rev()
+                    .find(|x| x.captured.iter().any(|(_, owner)| *owner == player));
+                (Outcome::Eliminated, last.map(|x| x.number))
+            } else if let Some((at, _, outcome)) =
+                self.resignations.iter().find(|(_, x, _)| *x == player)
+            {
+                (*outcome, Some(*at))
+            } else {
+                (Outcome::Playing, None)
+            };
+            Placing {
+                player,
+                outcome,
+                turns,
+                left_after,
+            }
+        };
+        let resigned = self.resignations.iter().rev().map(|(_, x, _)| *x);
+        GameResult {
+            state: self.state(),
+            placings: self
+                .standings()
+                .into_iter()
+                .chain(resigned)
+                .map(placing)
+                .collect(),
+            turns: self.history.len(),
+            rounds: self.rounds,
+        }
+    }
+
+    /// Play the winning turn of a ballot for its crowd-controlled seat,
+    /// once voting has closed.
+    pub fn play_ballot(&mut self, ballot: &crowd::Ballot) -> Result<Turn, GameError> {
+        let (player, to_move) = (ballot.player(), self.current_player());
+        let to_move = to_move.ok_or(GameError::GameFinished)?;
+        if to_move != player {
+            return Err(GameError::NotYourTurn { player, to_move });
+        }
+        if ballot.is_open() {
+            return Err(GameError::VotingOpen);
+        }
+        let turn = ballot.winner().ok_or(GameError::NoVotes)?;
+        self.play_turn(&turn)?;
+        Ok(turn)
+    }
+
+    /// Let a bot choose and play the turn of whoever is to move.
+    pub fn play_bot(&mut self, bot: &mut dyn ai::Bot) -> Result<Turn, GameError> {
+        if self.state() != GameState::InProgress {
+            return Err(GameError::GameFinished);
+        }
+        let turn = bot.choose(self).ok_or(GameError::NoLegalMoves)?;
+        self.play_turn(&turn)?;
+        Ok(turn)
+    }
+
+    /// The board as `Board::render` draws it, highlighting the piece that
+    /// moved last.
+    pub fn render(&self) -> Vec<String> {
+        let last = self.history.last().map(|x| x.turn.target());
+        self.board.render(last)
+    }
+
+    /// Recommend a turn for a player as if it were their turn, using the
+    /// same search as the computer players. Gives `None` if the game is
+    /// over, they are not playing, or they have no legal turn.
+    pub fn suggest_turn(&self, player: Player) -> Option<Turn> {
+        if self.state() != GameState::InProgress {
+            return None;
+        }
+        let mut game = self.clone();
+        game.current = self.playing.iter().position(|x| *x == player)?;
+        ai::Bot::choose(&mut ai::AlphaBeta::default(), &game)
+    }
+
+    /// Play scripted turns for whoever is to move, with pauses according
+    /// to the pacing, until the script runs out or the game is over.
+    /// `observer` hears about every turn as it happens.
+    pub fn play(
+        &mut self,
+        turns: &[Turn],
+        observer: impl FnMut(&Game, &GameEvent),
+    ) -> Result<(), GameError> {
+        let mut turns = turns.iter();
+        self.present(|_| turns.next().cloned(), observer)
+    }
+
+    /// Let bots play against each other, one for each seat in the order
+    /// that the players were given, with pauses according to the pacing.
+    /// With a time control, the time that each bot takes to choose is
+    /// charged to its clock.
+    /// Stops once the game is over, a bot has no turn, or `max_turns`
+    /// turns have been played. `observer` hears about every turn as it
+    /// happens.
+    pub fn play_bots(
+        &mut self,
+        bots: &mut [Box<dyn ai::Bot>],
+        max_turns: usize,
+        observer: impl FnMut(&Game, &GameEvent),
+    ) -> Result<(), GameError> {
+        let mut remaining = max_turns;
+        self.present(
+            |game| {
+                remaining = remaining.checked_sub(1)?;
+                let piece = game.current_player()?;
+                let seat = game.seats.iter().position(|x| *x == piece)?;
+                bots.get_mut(seat)?.choose(game)
+            },
+            observer,
+        )
+    }
+
+    fn present(
+        &mut self,
+        mut next: impl FnMut(&Game) -> Option<Turn>,
+        mut observer: impl FnMut(&Game, &GameEvent),
+    ) -> Result<(), GameError> {
+        while let Some(piece) = self.current_player() {
+            let started = Instant::now();
+            let turn = match next(self) {
+                Some(turn) => turn,
+                None => break,
+            };
+            let spent = started.elapsed();
+
+            observer(self, &GameEvent::TurnStarted(piece));
+            if self.pacing.delay > Duration::from_secs(0) {
+                sleep(self.pacing.delay);
+            }
+            if self.clock.is_none() {
+                self.play_turn(&turn)?;
+            } else if let Err(e) = self.play_timed_turn(&turn, spent) {
+                if e != GameError::TimeExpired {
+                    return Err(e);
+                }
+                observer(self, &GameEvent::TimedOut(piece));
+                continue;
+            }
+            if let Some(record) = self.history.last() {
+                observer(self, &GameEvent::TurnTaken(record.clone()));
+            }
+            if self.finished.last() == Some(&piece) {
+                observer(self, &GameEvent::PlayerFinished(piece));
+            }
+        }
+
+        if self.state() != GameState::InProgress {
+            observer(self, &GameEvent::GameOver(self.result()));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_small_board() {
+        use Player::*;
+        let empty = Cell::Empty;
+        let (head, tail, lefthand, righthand, leftfoot, rightfoot) = (
+            Cell::Occupied(Head),
+            Cell::Occupied(Tail),
+            Cell::Occupied(LeftHand),
+            Cell::Occupied(RightHand),
+            Cell::Occupied(LeftFoot),
+            Cell::Occupied(RightFoot),
+        );
+        #[rustfmt::skip]
+        let rows = vec![
+            vec![              head                 ],
+            vec![ lefthand, empty, empty, righthand ],
+            vec![       empty, empty, empty         ],
+            vec![ leftfoot, empty, empty, rightfoot ],
+            vec![              tail                 ]
+        ];
+        assert_eq!(
+            Board::new(Config {
+                player_lines: 1,
+                ..Config::default()
+            }),
+            Board::from_rows(
+                rows,
+                Config {
+                    player_lines: 1,
+                    ..Config::default()
+                }
+            ),
+        );
+    }
+
+    #[test]
+    fn test_new_standard_board() {
+        use Player::*;
+        let e = Cell::Empty;
+        let lhand = Cell::Occupied(LeftHand);
+        let rhand = Cell::Occupied(RightHand);
+        let lfoot = Cell::Occupied(LeftFoot);
+        let rfoot = Cell::Occupied(RightFoot);
+        let (head, tail) = (Cell::Occupied(Head), Cell::Occupied(Tail));
+        #[rustfmt::skip]
+        let rows = vec![
+            vec![                                 head                                  ],
+            vec![                              head, head                               ],
+            vec![                           head, head, head                            ],
+            vec![                        head, head, head, head                         ],
+            vec![ lhand, lhand, lhand, lhand, e, e, e, e, e, rhand, rhand, rhand, rhand ],
+            vec![      lhand, lhand, lhand, e, e, e, e, e, e, rhand, rhand, rhand       ],
+            vec![           lhand, lhand, e, e, e, e, e, e, e, rhand, rhand             ],
+            vec![                lhand, e, e, e, e, e, e, e, e, rhand                   ],
+            vec![                     e, e, e, e, e, e, e, e, e                         ],
+            vec![                lfoot, e, e, e, e, e, e, e, e, rfoot                   ],
+            vec![           lfoot, lfoot, e, e, e, e, e, e, e, rfoot, rfoot             ],
+            vec![      lfoot, lfoot, lfoot, e, e, e, e, e, e, rfoot, rfoot, rfoot       ],
+            vec![ lfoot, lfoot, lfoot, lfoot, e, e, e, e, e, rfoot, rfoot, rfoot, rfoot ],
+            vec![                        tail, tail, tail, tail                         ],
+            vec![                           tail, tail, tail                            ],
+            vec![                              tail, tail                               ],
+            vec![                                 tail                                  ],
+        ];
+        assert_eq!(
+            Board::new(Config::default()),
+            Board::from_rows(rows, Config::default()),
+        );
+    }
+
+    #[test]
+    fn test_new_board_with_corner_lines() {
+        use Player::*;
+        let config = Config::default()
+            .with_player_lines(2)
+            .with_corner_lines(Head, 1)
+            .with_corner_lines(LeftFoot, 0);
+        let e = Cell::Empty;
+        let (head, tail, lefthand, righthand, rightfoot) = (
+            Cell::Occupied(Head),
+            Cell::Occupied(Tail),
+            Cell::Occupied(LeftHand),
+            Cell::Occupied(RightHand),
+            Cell::Occupied(RightFoot),
+        );
+        #[rustfmt::skip]
+        let rows = vec![
+            vec![                        head                       ],
+            vec![                        e, e                       ],
+            vec![ lefthand, lefthand, e, e, e, righthand, righthand ],
+            vec![          lefthand, e, e, e, e, righthand          ],
+            vec![                   e, e, e, e, e                   ],
+            vec![                  e, e, e, e, e, rightfoot         ],
+            vec![                e, e, e, e, e, rightfoot, rightfoot],
+            vec![                     tail, tail                    ],
+            vec![                        tail                       ],
+        ];
+        assert_eq!(Board::new(config.clone()), Board::from_rows(rows, config));
+    }
+
+    #[test]
+    fn test_new_quick_board() {
+        let board = Board::new(Config::quick());
+        for piece in PLAYERS.iter() {
+            assert_eq!(board.pieces_of(*piece).count(), 6);
+        }
+        assert_eq!(
+            board.get_piece(Point::new(3, 11)),
+            Some(Cell::Occupied(Player::Head))
+        );
+        assert_eq!(board.get_piece(Point::new(4, 10)), Some(Cell::Empty));
+    }
+
+    #[test]
+    fn test_new_board_with_starts() {
+        use Player::*;
+        let config = Config::default()
+            .with_player_lines(1)
+            .with_start(Head, Tail)
+            .with_start(Tail, Head)
+            .with_start(LeftHand, RightHand);
+        let empty = Cell::Empty;
+        let (head, tail, lefthand, leftfoot, rightfoot) = (
+            Cell::Occupied(Head),
+            Cell::Occupied(Tail),
+            Cell::Occupied(LeftHand),
+            Cell::Occupied(LeftFoot),
+            Cell::Occupied(RightFoot),
+        );
+        #[rustfmt::skip]
+        let rows = vec![
+            vec![             tail                 ],
+            vec![ empty, empty, empty, lefthand    ],
+            vec![      empty, empty, empty         ],
+            vec![ leftfoot, empty, empty, rightfoot],
+            vec![             head                 ],
+        ];
+        assert_eq!(
+            Board::new(config.clone()),
+            Board::from_rows(rows, config.clone())
+        );
+        assert_eq!(config.occupant(LeftHand), None);
+        assert_eq!(config.target_of(Head), Head);
+        assert_eq!(config.target_of(LeftHand), LeftFoot);
+    }
+
+    #[test]
+    fn test_serialize_small_board() {
+        assert_eq!(
+            Board::new(Config {
+                player_lines: 1,
+                ..Config::default()
+            })
+            .serialize(),
+            vec!["    1", " 3 . . 5", "  . . .", " 6 . . 4", "    2"],
+        );
+    }
+
+    #[test]
+    fn test_serialize_standard_board() {
+        assert_eq!(
+            Board::new(Config::default()).serialize(),
+            vec![
+                "             1",
+                "            1 1",
+                "           1 1 1",
+                "          1 1 1 1",
+                " 3 3 3 3 . . . . . 5 5 5 5",
+                "  3 3 3 . . . . . . 5 5 5",
+                "   3 3 . . . . . . . 5 5",
+                "    3 . . . . . . . . 5",
+                "     . . . . . . . . .",
+                "    6 . . . . . . . . 4",
+                "   6 6 . . . . . . . 4 4",
+                "  6 6 6 . . . . . . 4 4 4",
+                " 6 6 6 6 . . . . . 4 4 4 4",
+                "          2 2 2 2",
+                "           2 2 2",
+                "            2 2",
+                "             2",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_render() {
+        let board = Board::new(Config::default().with_player_lines(1));
+        assert_eq!(board.render(Some(Point::new(1, 4))), board.serialize());
+
+        let board = Board::new(
+            Config::default()
+                .with_player_lines(1)
+                .with_color_mode(ColorMode::Ansi),
+        );
+        let lines = board.render(Some(Point::new(1, 4)));
+        assert_eq!(lines[0], "    \x1B[31;1;7m1\x1B[0m");
+        assert_eq!(
+            lines[1],
+            " \x1B[32m3\x1B[0m \x1B[2m.\x1B[0m \x1B[2m.\x1B[0m \x1B[36m5\x1B[0m"
+        );
+        assert_eq!(lines.len(), board.serialize().len());
+    }
+
+    #[test]
+    fn test_render_with_labels() {
+        let board = Board::new(Config::default().with_player_lines(1).with_labels(true));
+        assert_eq!(
+            board.render(None),
+            vec![
+                "   1234567",
+                "1     1",
+                "2  3 . . 5",
+                "3   . . .",
+                "4  6 . . 4",
+                "5     2",
+                "   1234567",
+            ],
+        );
+
+        let board = Board::new(Config::default().with_labels(true));
+        let lines = board.render(None);
+        assert_eq!(lines[0], "             1111111111222222");
+        assert_eq!(lines[1], "    1234567890123456789012345");
+        assert_eq!(lines[2], " 1              1");
+        // The top spot is in column 13.
+        assert_eq!(&lines[0][16..17], "1");
+        assert_eq!(&lines[1][16..17], "3");
+        assert_eq!(&lines[2][16..17], "1");
+        assert_eq!(lines.len(), 17 + 4);
+    }
+
+    #[test]
+    fn test_symbol_themes() {
+        for theme in &[SymbolTheme::Ascii, SymbolTheme::Unicode, SymbolTheme::Emoji] {
+            let config = Config::default()
+                .with_player_lines(1)
+                .with_symbol_theme(*theme);
+            let board = Board::new(config.clone());
+            assert_eq!(Board::deserialize(&board.serialize(), &config), Ok(board));
+        }
+        let board = Board::new(
+            Config::default()
+                .with_player_lines(1)
+                .with_symbol_theme(SymbolTheme::Unicode)
+                .with_symbol(Cell::Empty, "_"),
+        );
+        assert_eq!(
+            board.serialize(),
+            vec!["    ●", " ◆ _ _ ◇", "  _ _ _", " ■ _ _ □", "    ○"],
+        );
+    }
+
+    #[test]
+    fn test_from_ascii_round_trip() {
+        let mut board = Board::default();
+        board
+            .move_piece(Point::new(4, 10), Point::new(5, 11), Player::Head)
+            .unwrap();
+        let lines = board.serialize();
+        assert_eq!(
+            Board::deserialize(&lines, &Config::default()),
+            Ok(board.clone())
+        );
+        let lines: Vec<&str> = lines.iter().map(|x| x.as_str()).collect();
+        assert_eq!(Board::from_ascii(&lines, &Config::default()), Ok(board));
+    }
+
+    #[test]
+    fn test_from_ascii_without_indentation() {
+        let config = Config {
+            player_lines: 1,
+            ..Config::default()
+        };
+        assert_eq!(
+            Board::from_ascii(&["1", "3 . . 5", ". . .", "6 . . 4", "2"], &config),
+            Ok(Board::new(config)),
+        );
+    }
+
+    #[test]
+    fn test_from_ascii_with_errors() {
+        let config = Config {
+            player_lines: 1,
+            ..Config::default()
+        };
+        assert_eq!(
+            Board::from_ascii(&["1", "3 . . 5", ". . .", "6 . . 4"], &config),
+            Err(ParseError::WrongRowCount),
+        );
+        assert_eq!(
+            Board::from_ascii(&["1", "3 . . 5", ". .", "6 . . 4", "2"], &config),
+            Err(ParseError::WrongRowLength),
+        );
+        assert_eq!(
+            Board::from_ascii(&["1", "3 . x 5", ". . .", "6 . . 4", "2"], &config),
+            Err(ParseError::UnknownSymbol),
+        );
+    }
+
+    #[test]
+    fn test_try_move_piece_with_success() {
+        let board = Board::default();
+        assert_eq!(
+            board.try_move_piece(Point::new(4, 10), Point::new(5, 11), Player::Head),
+            Ok(()),
+        );
+    }
+
+    #[test]
+    fn test_try_move_piece_with_no_route_because_too_far() {
+        let board = Board::default();
+        assert_eq!(
+            board.try_move_piece(Point::new(1, 13), Point::new(7, 13), Player::Head),
+            Err(GameError::NoRoute {
+                from: Point::new(1, 13),
+                to: Point::new(7, 13),
+            }),
+        );
+    }
+
+    #[test]
+    fn test_try_move_piece_with_no_route_because_no_middle_piece() {
+        let board = Board::default();
+        assert_eq!(
+            board.try_move_piece(Point::new(4, 10), Point::new(6, 12), Player::Head),
+            Err(GameError::EmptyMidpoint {
+                from: Point::new(4, 10),
+                to: Point::new(6, 12),
+                middle: Point::new(5, 11),
+            }),
+        );
+    }
+
+    #[test]
+    fn test_try_move_piece_with_jumps_in_every_direction() {
+        let mut board = Board::empty(Config::default());
+        board.set_piece(Point::new(9, 13), Player::Head).unwrap();
+        let targets = vec![
+            Point::new(9, 17),
+            Point::new(9, 9),
+            Point::new(7, 15),
+            Point::new(7, 11),
+            Point::new(11, 15),
+            Point::new(11, 11),
+        ];
+        for target in targets {
+            let middle = Point::new((9 + target.row) / 2, (13 + target.column) / 2);
+            assert_eq!(
+                board.try_move_piece(Point::new(9, 13), target, Player::Head),
+                Err(GameError::EmptyMidpoint {
+                    from: Point::new(9, 13),
+                    to: target,
+                    middle,
+                }),
+            );
+            let mut jumpable = board.clone();
+            jumpable.set_piece(middle, Player::Tail).unwrap();
+            assert_eq!(
+                jumpable.try_move_piece(Point::new(9, 13), target, Player::Head),
+                Ok(()),
+            );
+        }
+    }
+
+    #[test]
+    fn test_try_move_piece_with_no_route_because_only_wrong_cell_occupied() {
+        let mut board = Board::empty(Config::default());
+        board.set_piece(Point::new(9, 9), Player::Head).unwrap();
+        board.set_piece(Point::new(8, 12), Player::Tail).unwrap();
+        assert_eq!(
+            board.try_move_piece(Point::new(9, 9), Point::new(9, 13), Player::Head),
+            Err(GameError::EmptyMidpoint {
+                from: Point::new(9, 9),
+                to: Point::new(9, 13),
+                middle: Point::new(9, 11),
+            }),
+        );
+    }
+
+    #[test]
+    fn test_try_move_piece_with_target_occupied() {
+        let board = Board::default();
+        assert_eq!(
+            board.try_move_piece(Point::new(1, 13), Point::new(2, 12), Player::Head),
+            Err(GameError::OccupiedTarget {
+                point: Point::new(2, 12),
+                occupant: Player::Head,
+            }),
+        );
+    }
+
+    #[test]
+    fn test_try_move_piece_with_out_of_bounds() {
+        let board = Board::default();
+        assert_eq!(
+            board.try_move_piece(Point::new(1, 13), Point::new(1, 12), Player::Head),
+            Err(GameError::OutOfBounds(Point::new(1, 12))),
+        );
+    }
+
+    #[test]
+    fn test_try_move_piece_with_non_adjacent_target() {
+        let board = Board::default();
+        for target in [Point::new(5, 15), Point::new(6, 10), Point::new(5, 13)].iter() {
+            assert_eq!(
+                board.try_move_piece(Point::new(4, 10), *target, Player::Head),
+                Err(GameError::NoRoute {
+                    from: Point::new(4, 10),
+                    to: *target,
+                }),
+            );
+        }
+    }
+
+    #[test]
+    fn test_move_piece_in_every_direction() {
+        let mut board = Board::empty(Config::default());
+        let center = board.center();
+        board.set_piece(center, Player::Head).unwrap();
+        for (rows, columns) in DIRECTIONS.iter() {
+            let target = Point::new(center.row + rows, center.column + columns);
+            assert_eq!(board.try_move_piece(center, target, Player::Head), Ok(()));
+            assert_eq!(center.line_to(target), Some(((*rows, *columns), 1)));
+        }
+        assert_eq!(
+            center.line_to(Point::new(center.row + 2, center.column)),
+            None
+        );
+        assert_eq!(center.line_to(center), None);
+    }
+
+    #[test]
+    fn test_directions() {
+        let center = Board::default().center();
+        for direction in Direction::ALL.iter() {
+            let (rows, columns) = direction.offset();
+            assert_eq!(
+                center.line_to(center.step(*direction)),
+                Some(((rows, columns), 1))
+            );
+            assert_eq!(
+                center.line_to(center.jump(*direction)),
+                Some(((rows, columns), 2))
+            );
+            assert_eq!(center.step(*direction).step(direction.opposite()), center);
+        }
+        assert_eq!(Direction::NorthEast.opposite(), Direction::SouthWest);
+        assert_eq!(
+            Point::new(1, 13).step(Direction::SouthEast),
+            Point::new(2, 14)
+        );
+    }
+
+    #[test]
+    fn test_cells() {
+        let board = Board::new(Config::default().with_player_lines(1));
+        let cells: Vec<(Point, Cell)> = board.cells().collect();
+        assert_eq!(cells.len(), 13);
+        assert_eq!(cells[0], (Point::new(1, 4), Player::Head.into()));
+        assert_eq!(cells[1], (Point::new(2, 1), Player::LeftHand.into()));
+        assert_eq!(cells[6], (Point::new(3, 4), Cell::Empty));
+        assert_eq!(cells[12], (Point::new(5, 4), Player::Tail.into()));
+        for &(point, cell) in &cells {
+            assert_eq!(board.get_piece(point), Some(cell));
+        }
+        assert_eq!(cells[0].1.player(), Some(Player::Head));
+        assert!(cells[6].1.is_empty() && cells[6].1.player().is_none());
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let board = Board::default();
+        assert_eq!(board.neighbors(board.center()).count(), 6);
+        assert_eq!(
+            board.neighbors(Point::new(1, 13)).collect::<Vec<_>>(),
+            vec![Point::new(2, 12), Point::new(2, 14)],
+        );
+        assert_eq!(board.neighbors(Point::new(5, 1)).count(), 2);
+    }
+
+    #[test]
+    fn test_try_move_piece_with_wrong_player() {
+        let board = Board::default();
+        assert_eq!(
+            board.try_move_piece(Point::new(4, 10), Point::new(5, 11), Player::Tail),
+            Err(GameError::WrongPlayer {
+                point: Point::new(4, 10),
+                expected: Player::Tail,
+                found: Cell::Occupied(Player::Head),
+            }),
+        );
+    }
+
+    #[test]
+    fn test_new_turn() {
+        assert_eq!(
+            Turn::new(vec![Point::new(4, 10), Point::new(5, 11)]),
+            Ok(Turn::Step {
+                from: Point::new(4, 10),
+                to: Point::new(5, 11),
+            }),
+        );
+        assert_eq!(
+            Turn::new(vec![Point::new(3, 11), Point::new(5, 13)]),
+            Ok(Turn::Jumps(vec![Point::new(3, 11), Point::new(5, 13)])),
+        );
+        assert_eq!(
+            Turn::new(vec![Point::new(3, 11)]),
+            Err(GameError::IncompleteTurn)
+        );
+        assert_eq!(
+            Turn::new(vec![Point::new(1, 13), Point::new(7, 13)]),
+            Err(GameError::NoRoute {
+                from: Point::new(1, 13),
+                to: Point::new(7, 13),
+            }),
+        );
+    }
+
+    #[test]
+    fn test_new_turn_with_exhaustion() {
+        assert_eq!(
+            Turn::new(vec![
+                Point::new(4, 10),
+                Point::new(5, 11),
+                Point::new(6, 12)
+            ]),
+            Err(GameError::Exhausted { segment: 0 }),
+        );
+        assert_eq!(
+            "3/11>5/13>5/15>6/16".parse::<Turn>(),
+            Err(ParseError::InvalidTurn),
+        );
+        assert_eq!(
+            Turn::new(vec![
+                Point::new(4, 10),
+                Point::new(5, 11),
+                Point::new(7, 13)
+            ]),
+            Err(GameError::Exhausted { segment: 0 }),
+        );
+        assert_eq!(
+            Turn::new(vec![
+                Point::new(3, 11),
+                Point::new(5, 13),
+                Point::new(7, 13)
+            ]),
+            Err(GameError::NoRoute {
+                from: Point::new(5, 13),
+                to: Point::new(7, 13),
+            }),
+        );
+        assert_eq!(
+            Turn::new(vec![
+                Point::new(3, 11),
+                Point::new(5, 13),
+                Point::new(6, 14)
+            ]),
+            Err(GameError::Exhausted { segment: 1 }),
+        );
+    }
+
+    #[test]
+    fn test_take_turn_with_jumps_in_every_direction() {
+        let mut board = Board::empty(Config::default());
+        let center = board.center();
+        board.set_piece(center, Player::Head).unwrap();
+        for (rows, columns) in DIRECTIONS.iter() {
+            let middle = Point::new(center.row + rows, center.column + columns);
+            let target = Point::new(center.row + rows * 2, center.column + columns * 2);
+            let turn = Turn::new(vec![center, target]).unwrap();
+            assert_eq!(
+                board.try_turn(&turn, Player::Head),
+                Err(GameError::EmptyMidpoint {
+                    from: center,
+                    to: target,
+                    middle,
+                })
+            );
+            let mut jumped = board.clone();
+            jumped.set_piece(middle, Player::Tail).unwrap();
+            assert_eq!(jumped.try_turn(&turn, Player::Head), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_try_turn_with_success_on_single_move() {
+        let board = Board::default();
+        let turn = Turn::new(vec![Point::new(4, 10), Point::new(5, 11)]).unwrap();
+        assert_eq!(board.try_turn(&turn, Player::Head), Ok(()));
+    }
+
+    #[test]
+    fn test_try_turn_with_success_on_single_jump() {
+        let board = Board::default();
+        let turn = Turn::new(vec![Point::new(3, 11), Point::new(5, 13)]).unwrap();
+        assert_eq!(board.try_turn(&turn, Player::Head), Ok(()));
+    }
+
+    #[test]
+    fn test_try_turn_with_success_on_multiple_jumps() {
+        let mut board = Board::default();
+        board
+            .move_piece(Point::new(4, 10), Point::new(5, 11), Player::Head)
+            .unwrap();
+        let turn = Turn::new(vec![Point::new(3, 11), Point::new(5, 13), Point::new(5, 9)]).unwrap();
+        assert_eq!(board.try_turn(&turn, Player::Head), Ok(()));
+    }
+
+    #[test]
+    fn test_try_turn_with_exhaustion() {
+        let board = Board::default();
+        let turn = Turn::Jumps(vec![
+            Point::new(4, 10),
+            Point::new(5, 11),
+            Point::new(6, 12),
+        ]);
+        assert_eq!(
+            board.try_turn(&turn, Player::Head),
+            Err(GameError::Exhausted { segment: 0 })
+        );
+    }
+
+    #[test]
+    fn test_try_turn_with_illegal_hop_in_chain() {
+        let board = Board::default();
+        let turn = "3/11>5/13>5/9".parse().unwrap();
+        let error = board.try_turn(&turn, Player::Head).unwrap_err();
+        assert_eq!(
+            error,
+            GameError::InChain {
+                segment: 1,
+                error: Box::new(GameError::EmptyMidpoint {
+                    from: Point::new(5, 13),
+                    to: Point::new(5, 9),
+                    middle: Point::new(5, 11),
+                }),
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "Hop 2 of the chain is illegal. Cannot jump from 5/13 to 5/9: midpoint 5/11 is empty."
+        );
+    }
+
+    #[test]
+    fn test_take_turn_with_illegal_hop_in_chain_leaves_board() {
+        let config = Config::default().with_captures(true);
+        let setup = [
+            (Point::new(9, 13), Player::Head),
+            (Point::new(10, 14), Player::Tail),
+        ];
+        let mut board = Board::from_setup(&setup, config).unwrap();
+        let before = board.clone();
+        // The first hop captures, but the second has nothing to jump over.
+        let turn = "9/13>11/15>13/17".parse().unwrap();
+        assert!(board.take_turn(&turn, Player::Head).is_err());
+        assert_eq!(board, before);
+
+        board
+            .take_turn(&"9/13>11/15".parse().unwrap(), Player::Head)
+            .unwrap();
+        assert_eq!(board.get_piece(Point::new(10, 14)), Some(Cell::Empty));
+    }
+
+    #[test]
+    fn test_try_turn_with_revisit() {
+        let setup = [
+            (Point::new(9, 13), Player::Head),
+            (Point::new(10, 14), Player::Tail),
+        ];
+        let board = Board::from_setup(&setup, Config::default()).unwrap();
+        let turn = "9/13>11/15>9/13".parse().unwrap();
+        let error = GameError::Revisited {
+            segment: 1,
+            point: Point::new(9, 13),
+        };
+        assert_eq!(board.try_turn(&turn, Player::Head), Err(error.clone()));
+        assert_eq!(
+            error.to_string(),
+            "Hop 2 of the chain lands on 9/13 again, and chains cannot visit a spot twice."
+        );
+        assert_eq!(
+            board.explain_turn(&turn.points(), Player::Head).error(),
+            Some(error)
+        );
+
+        let board = Board::from_setup(&setup, Config::default().with_revisits(true)).unwrap();
+        assert_eq!(board.try_turn(&turn, Player::Head), Ok(()));
+        // Chains that are generated never need to come back to a spot.
+        assert!(board
+            .destinations(Point::new(9, 13))
+            .iter()
+            .all(|x| !x.points()[1..].contains(&Point::new(9, 13))));
+    }
+
+    #[test]
+    fn test_explain_turn() {
+        let board = Board::default();
+        let points = |text: &str| text.parse::<Turn>().unwrap().points();
+        let analysis = board.explain_turn(&points("3/11>5/13>5/9"), Player::Head);
+        assert_eq!(
+            analysis.segments,
+            vec![
+                Segment {
+                    from: Point::new(3, 11),
+                    to: Point::new(5, 13),
+                    verdict: Verdict::Jump {
+                        over: Point::new(4, 12),
+                        owner: Player::Head,
+                        captures: false,
+                    },
+                },
+                Segment {
+                    from: Point::new(5, 13),
+                    to: Point::new(5, 9),
+                    verdict: Verdict::Illegal(GameError::EmptyMidpoint {
+                        from: Point::new(5, 13),
+                        to: Point::new(5, 9),
+                        middle: Point::new(5, 11),
+                    }),
+                },
+            ]
+        );
+        assert_eq!(
+            analysis.error(),
+            board
+                .try_turn(&"3/11>5/13>5/9".parse().unwrap(), Player::Head)
+                .err()
+        );
+        assert_eq!(
+            analysis.segments[0].to_string(),
+            "3/11 to 5/13: A jump over Head's piece at 4/12."
+        );
+
+        let analysis = board.explain_turn(&points("4/10-5/11"), Player::Head);
+        assert_eq!(analysis.segments[0].verdict, Verdict::Step);
+        assert!(analysis.is_legal());
+
+        let steps = [Point::new(4, 10), Point::new(5, 11), Point::new(6, 12)];
+        let analysis = board.explain_turn(&steps, Player::Head);
+        assert_eq!(
+            analysis.segments[0].verdict,
+            Verdict::Illegal(GameError::Exhausted { segment: 0 })
+        );
+        assert_eq!(analysis.segments[1].verdict, Verdict::Unchecked);
+        assert_eq!(analysis.error(), Some(GameError::Exhausted { segment: 0 }));
+
+        let analysis = board.explain_turn(&[Point::new(4, 10)], Player::Head);
+        assert_eq!(analysis.error(), Some(GameError::IncompleteTurn));
+        let far = [Point::new(4, 10), Point::new(7, 11)];
+        assert_eq!(
+            board.explain_turn(&far, Player::Head).error(),
+            Some(GameError::NoRoute {
+                from: far[0],
+                to: far[1],
+            })
+        );
+    }
+
+    #[test]
+    fn test_explain_turn_with_captures() {
+        let config = Config::default().with_captures(true);
+        let setup = [
+            (Point::new(9, 13), Player::Head),
+            (Point::new(10, 14), Player::Tail),
+            (Point::new(12, 16), Player::Tail),
+        ];
+        let board = Board::from_setup(&setup, config).unwrap();
+        let points = [Point::new(9, 13), Point::new(11, 15), Point::new(13, 17)];
+        let analysis = board.explain_turn(&points, Player::Head);
+        assert!(analysis.is_legal());
+        assert_eq!(
+            analysis.segments[1].verdict,
+            Verdict::Jump {
+                over: Point::new(12, 16),
+                owner: Player::Tail,
+                captures: true,
+            }
+        );
+        assert_eq!(
+            analysis.segments[1].to_string(),
+            "11/15 to 13/17: A jump that captures Tail's piece at 12/16."
+        );
+    }
+
+    #[test]
+    fn test_get_piece_from_board_with_even_player_lines() {
+        let board = Board::default();
+
+        // Out of bounds.
+        assert_eq!(board.get_piece(Point::new(1, 1)), None);
+        assert_eq!(board.get_piece(Point::new(1, 12)), None);
+
+        // Row with odd number of pieces.
+        assert_eq!(
+            board.get_piece(Point::new(1, 13)),
+            Some(Cell::Occupied(Player::Head))
+        );
+        assert_eq!(board.get_piece(Point::new(5, 17)), Some(Cell::Empty));
+        assert_eq!(
+            board.get_piece(Point::new(5, 19)),
+            Some(Cell::Occupied(Player::RightHand))
+        );
+
+        // Row with even number of pieces.
+        assert_eq!(
+            board.get_piece(Point::new(2, 12)),
+            Some(Cell::Occupied(Player::Head))
+        );
+        assert_eq!(
+            board.get_piece(Point::new(6, 2)),
+            Some(Cell::Occupied(Player::LeftHand))
+        );
+        assert_eq!(
+            board.get_piece(Point::new(6, 6)),
+            Some(Cell::Occupied(Player::LeftHand))
+        );
+        assert_eq!(board.get_piece(Point::new(6, 8)), Some(Cell::Empty));
+    }
+
+    #[test]
+    fn test_get_piece_from_board_with_odd_player_lines() {
+        let board = Board::new(Config {
+            player_lines: 3,
+            ..Default::default()
+        });
+
+        // Out of bounds.
+        assert_eq!(board.get_piece(Point::new(1, 1)), None);
+        assert_eq!(board.get_piece(Point::new(1, 12)), None);
+
+        // Row with odd number of pieces.
+        assert_eq!(
+            board.get_piece(Point::new(1, 10)),
+            Some(Cell::Occupied(Player::Head))
+        );
+        assert_eq!(board.get_piece(Point::new(6, 15)), Some(Cell::Empty));
+        assert_eq!(
+            board.get_piece(Point::new(6, 17)),
+            Some(Cell::Occupied(Player::RightHand))
+        );
+
+        // Row with even number of pieces.
+        assert_eq!(
+            board.get_piece(Point::new(2, 9)),
+            Some(Cell::Occupied(Player::Head))
+        );
+        assert_eq!(
+            board.get_piece(Point::new(4, 1)),
+            Some(Cell::Occupied(Player::LeftHand))
+        );
+        assert_eq!(
+            board.get_piece(Point::new(4, 5)),
+            Some(Cell::Occupied(Player::LeftHand))
+        );
+        assert_eq!(board.get_piece(Point::new(4, 7)), Some(Cell::Empty));
+    }
+
+    #[test]
+    fn test_pieces_of() {
+        let mut board = Board::new(Config {
+            player_lines: 2,
+            ..Config::default()
+        });
+        assert_eq!(
+            board.pieces_of(Player::Head).collect::<Vec<_>>(),
+            vec![Point::new(1, 7), Point::new(2, 6), Point::new(2, 8)],
+        );
+        assert_eq!(
+            board.pieces_of(Player::RightFoot).collect::<Vec<_>>(),
+            vec![Point::new(6, 12), Point::new(7, 11), Point::new(7, 13)],
+        );
+
+        board
+            .move_piece(Point::new(2, 6), Point::new(3, 7), Player::Head)
+            .unwrap();
+        board.set_piece(Point::new(1, 7), Player::Tail).unwrap();
+        assert_eq!(
+            board.pieces_of(Player::Head).collect::<Vec<_>>(),
+            vec![Point::new(2, 8), Point::new(3, 7)],
+        );
+        assert_eq!(board.pieces_of(Player::Tail).count(), 4);
+    }
+
+    #[test]
+    fn test_from_setup() {
+        let board = Board::from_setup(
+            &[
+                (Point::new(9, 13), Player::Head),
+                (Point::new(1, 13), Player::LeftFoot),
+            ],
+            Config::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            board.get_piece(Point::new(9, 13)),
+            Some(Cell::Occupied(Player::Head))
+        );
+        assert_eq!(board.pieces_of(Player::LeftFoot).count(), 1);
+        assert_eq!(board.cells().filter(|(_, x)| !x.is_empty()).count(), 2);
+        assert_eq!(
+            Board::from_setup(&[], Config::default()),
+            Ok(Board::empty(Config::default()))
+        );
+
+        let twice = [
+            (Point::new(9, 13), Player::Head),
+            (Point::new(9, 13), Player::Tail),
+        ];
+        assert_eq!(
+            Board::from_setup(&twice, Config::default()),
+            Err(GameError::InvalidSetup)
+        );
+        assert_eq!(
+            Board::from_setup(&[(Point::new(9, 14), Player::Head)], Config::default()),
+            Err(GameError::OutOfBounds(Point::new(9, 14)))
+        );
+    }
+
+    #[test]
+    fn test_config_new() {
+        assert_eq!(Config::new(4), Ok(Config::default()));
+        assert!(Config::new(1).is_ok());
+        assert!(Config::new(MAX_PLAYER_LINES).is_ok());
+        assert_eq!(Config::new(0), Err(ConfigError::TooFewLines));
+        assert_eq!(Config::new(-3), Err(ConfigError::TooFewLines));
+        assert_eq!(
+            Config::new(MAX_PLAYER_LINES + 1),
+            Err(ConfigError::TooManyLines)
+        );
+    }
+
+    #[test]
+    fn test_largest_board() {
+        let config = Config::new(MAX_PLAYER_LINES).unwrap().with_labels(true);
+        let board = Board::new(config.clone());
+        assert_eq!(board.row_count(), 41);
+        assert_eq!(board.center(), Point::new(21, 31));
+        assert_eq!(board.pieces_of(Player::Head).count(), 55);
+
+        let turns = board.legal_turns(Player::LeftHand);
+        assert_eq!(turns.len(), board.legal_turns(Player::RightFoot).len());
+        for turn in &turns {
+            assert_eq!(board.try_turn(turn, Player::LeftHand), Ok(()));
+        }
+        assert_eq!(
+            ai::distance_to_goal(&board, Player::Head),
+            ai::distance_to_goal(&board, Player::Tail)
+        );
+
+        // Every line of the diagram is as wide as the widest row.
+        let lines = board.render(None);
+        assert_eq!(lines.len(), 41 + 4);
+        assert_eq!(lines[0].len(), lines[1].len());
+        assert!(lines.iter().all(|x| x.trim_end().len() <= lines[1].len()));
+        let diagram = board.serialize();
+        let rows: Vec<&str> = diagram.iter().map(|x| x.as_str()).collect();
+        assert_eq!(Board::from_ascii(&rows, &config).unwrap(), board);
+
+        let mut board = Board::empty(config);
+        assert!(!board.has_player_won(Player::Head));
+        let target: Vec<Point> = Board::new(board.config().clone())
+            .pieces_of(Player::Tail)
+            .collect();
+        for point in &target {
+            board.set_piece(*point, Player::Head).unwrap();
+        }
+        assert!(board.has_player_won(Player::Head));
+        board.set_piece(target[54], Cell::Empty).unwrap();
+        assert!(!board.has_player_won(Player::Head));
+    }
+
+    #[test]
+    fn test_pieces_past_one_word() {
+        // 361 spots, so the masks take several words each.
+        let mut board = Board::new(Config::default().with_player_lines(9));
+        let tail = board.pieces_of(Player::Tail).collect::<Vec<_>>();
+        assert_eq!(tail.len(), 45);
+        assert_eq!(tail[44], Point::new(37, 28));
+
+        let target = Point::new(19, 28);
+        board.set_piece(target, Player::Tail).unwrap();
+        assert_eq!(board.get_piece(target), Some(Cell::Occupied(Player::Tail)));
+        let pieces = board.cells().filter(|(_, x)| *x != Cell::Empty).count();
+        assert_eq!(pieces, 6 * 45 + 1);
+        board.set_piece(target, Cell::Empty).unwrap();
+        assert_eq!(board, Board::new(Config::default().with_player_lines(9)));
+    }
+
+    #[test]
+    fn test_hash() {
+        let mut board = Board::default();
+        let start = board.hash();
+        assert_ne!(start, Board::empty(Config::default()).hash());
+        assert_eq!(Board::empty(Config::default()).hash(), 0);
+
+        let turn: Turn = "4/10-5/11".parse().unwrap();
+        let captured = board.make_turn(&turn);
+        let moved = board.hash();
+        assert_ne!(moved, start);
+        let mut rebuilt = Board::from_ascii(
+            &board
+                .serialize()
+                .iter()
+                .map(|x| x.as_str())
+                .collect::<Vec<_>>(),
+            board.config(),
+        )
+        .unwrap();
+        assert_eq!(rebuilt.hash(), moved);
+
+        board.unmake_turn(&turn, &captured);
+        assert_eq!(board.hash(), start);
+        rebuilt.restore(&board.snapshot()).unwrap();
+        assert_eq!(rebuilt.hash(), start);
+
+        // The same piece on another spot, or another piece on the same spot,
+        // gives a different hash.
+        board.set_piece(Point::new(5, 11), Player::Head).unwrap();
+        let head = board.hash();
+        board.set_piece(Point::new(5, 11), Player::Tail).unwrap();
+        assert_ne!(board.hash(), head);
+        assert_ne!(board.hash(), moved);
+    }
+
+    #[test]
+    fn test_snapshot_and_restore() {
+        let mut board = Board::default();
+        let original = board.clone();
+        let snapshot = board.snapshot();
+        board
+            .move_piece(Point::new(4, 10), Point::new(5, 11), Player::Head)
+            .unwrap();
+        assert_ne!(board, original);
+        assert_eq!(board.restore(&snapshot), Ok(()));
+        assert_eq!(board, original);
+
+        let mut small = Board::new(Config::default().with_player_lines(1));
+        assert_eq!(small.restore(&snapshot), Err(GameError::InvalidSetup));
+    }
+
+    #[test]
+    fn test_set_piece() {
+        let mut board = Board::empty(Config::default());
+        assert_eq!(board.get_piece(Point::new(1, 13)), Some(Cell::Empty));
+        assert_eq!(board.set_piece(Point::new(1, 13), Player::Tail), Ok(()));
+        assert_eq!(
+            board.get_piece(Point::new(1, 13)),
+            Some(Cell::Occupied(Player::Tail))
+        );
+        assert_eq!(
+            board.set_piece(Point::new(1, 12), Player::Tail),
+            Err(GameError::OutOfBounds(Point::new(1, 12))),
+        );
+    }
+
+    #[test]
+    fn test_validate_setup() {
+        let config = Config {
+            player_lines: 1,
+            ..Config::default()
+        };
+        assert_eq!(Board::new(config.clone()).validate_setup(), Ok(()));
+
+        let mut board = Board::empty(config);
+        assert_eq!(board.validate_setup(), Err(GameError::InvalidSetup));
+        board.set_piece(Point::new(3, 4), Player::Head).unwrap();
+        assert_eq!(board.validate_setup(), Ok(()));
+        board.set_piece(Point::new(3, 6), Player::Head).unwrap();
+        assert_eq!(board.validate_setup(), Err(GameError::InvalidSetup));
+    }
+
+    #[test]
+    fn test_get_piece_outside_rows() {
+        let board = Board::default();
+        assert_eq!(board.get_piece(Point::new(0, 13)), None);
+        assert_eq!(board.get_piece(Point::new(-1, 13)), None);
+        assert_eq!(board.get_piece(Point::new(18, 13)), None);
+    }
+
+    #[test]
+    fn test_geometry() {
+        let board = Board::default();
+        assert_eq!(board.row_count(), 17);
+        assert_eq!(board.center(), Point::new(9, 13));
+        assert_eq!(board.get_piece(board.center()), Some(Cell::Empty));
+        assert_eq!(board.column_range(0), None);
+        assert_eq!(board.column_range(1), Some((13, 13)));
+        assert_eq!(board.column_range(2), Some((12, 14)));
+        assert_eq!(board.column_range(5), Some((1, 25)));
+        assert_eq!(board.column_range(9), Some((5, 21)));
+        assert_eq!(board.column_range(18), None);
+        assert_eq!(board.extents(), (Point::new(1, 1), Point::new(17, 25)));
+
+        let board = Board::new(Config::default().with_player_lines(1));
+        assert_eq!(board.center(), Point::new(3, 4));
+        assert_eq!(board.column_range(2), Some((1, 7)));
+        assert_eq!(board.extents(), (Point::new(1, 1), Point::new(5, 7)));
+    }
+
+    #[test]
+    fn test_cell_layout() {
+        let board = Board::default();
+        let radius = board.cell_radius();
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-9;
+
+        let (x, y) = board.cell_center(board.center()).unwrap();
+        assert!(close(x, 0.5) && close(y, 0.5));
+        let (x, y) = board.cell_center(Point::new(1, 13)).unwrap();
+        assert!(close(x, 0.5) && close(y, radius));
+        let (x, y) = board.cell_center(Point::new(17, 13)).unwrap();
+        assert!(close(x, 0.5) && close(y, 1.0 - radius));
+
+        // Neighbors are one diameter apart in every direction.
+        let (x1, y1) = board.cell_center(Point::new(5, 1)).unwrap();
+        let (x2, y2) = board.cell_center(Point::new(5, 3)).unwrap();
+        let (x3, y3) = board.cell_center(Point::new(6, 2)).unwrap();
+        assert!(close(x2 - x1, 2.0 * radius) && close(y2, y1));
+        assert!(close(
+            ((x3 - x1).powi(2) + (y3 - y1).powi(2)).sqrt(),
+            2.0 * radius
+        ));
+        assert!(x1 >= radius);
+
+        assert_eq!(board.cell_center(Point::new(1, 12)), None);
+    }
+
+    #[test]
+    fn test_destinations() {
+        let board = Board::default();
+        assert_eq!(
+            board.destinations(Point::new(4, 10)),
+            vec!["4/10-5/9".parse().unwrap(), "4/10-5/11".parse().unwrap()],
+        );
+        assert_eq!(
+            board.destinations(Point::new(3, 11)),
+            vec!["3/11>5/9".parse().unwrap(), "3/11>5/13".parse().unwrap()],
+        );
+        assert_eq!(board.destinations(Point::new(1, 13)), vec![]);
+        assert_eq!(board.destinations(Point::new(9, 13)), vec![]);
+    }
+
+    #[test]
+    fn test_destinations_with_jump_chain() {
+        let mut board = Board::empty(Config::default());
+        board.set_piece(Point::new(9, 9), Player::Head).unwrap();
+        board.set_piece(Point::new(9, 11), Player::Tail).unwrap();
+        board.set_piece(Point::new(9, 15), Player::Tail).unwrap();
+        let turns = board.destinations(Point::new(9, 9));
+        assert!(turns.contains(&"9/9>9/13>9/17".parse().unwrap()));
+        assert!(!turns.iter().any(|turn| turn.target() == Point::new(9, 9)));
+        let mut targets: Vec<Point> = turns.iter().map(|x| x.target()).collect();
+        targets.sort_by_key(|x| (x.row, x.column));
+        targets.dedup();
+        assert_eq!(targets.len(), turns.len());
+        for turn in turns {
+            assert_eq!(board.try_turn(&turn, Player::Head), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_legal_turns() {
+        let board = Board::default();
+        let turns = board.legal_turns(Player::Head);
+        assert_eq!(turns.len(), 14);
+        for turn in turns {
+            assert_eq!(board.try_turn(&turn, Player::Head), Ok(()));
+        }
+    }
+
+    #[test]
+    fn test_has_player_won_yes() {
+        use Player::*;
+        let e = Cell::Empty;
+        let (head, tail, lefthand, righthand, leftfoot, rightfoot) = (
+            Cell::Occupied(Head),
+            Cell::Occupied(Tail),
+            Cell::Occupied(LeftHand),
+            Cell::Occupied(RightHand),
+            Cell::Occupied(LeftFoot),
+            Cell::Occupied(RightFoot),
+        );
+        #[rustfmt::skip]
+        let rows = vec![
+            vec![                        tail                       ],
+            vec![                     tail, tail                    ],
+            vec![ rightfoot, rightfoot, e, e, e, leftfoot, leftfoot ],
+            vec![          rightfoot, e, e, e, e, leftfoot          ],
+            vec![                   e, e, e, e, e                   ],
+            vec![          righthand, e, e, e, e, lefthand          ],
+            vec![ righthand, righthand, e, e, e, lefthand, lefthand ],
+            vec![                     head, head                    ],
+            vec![                        head                       ],
+        ];
+        let board = Board::from_rows(
+            rows,
+            Config {
+                player_lines: 2,
+                ..Default::default()
+            },
+        );
+        assert!(board.has_player_won(Head));
+        assert!(board.has_player_won(LeftHand));
+        assert!(board.has_player_won(RightHand));
+        assert!(board.has_player_won(LeftFoot));
+        assert!(board.has_player_won(RightFoot));
+        assert!(board.has_player_won(Tail));
+    }
+
+    #[test]
+    fn test_has_player_won_with_corner_lines() {
+        let config = Config::default()
+            .with_player_lines(5)
+            .with_corner_lines(Player::Tail, 4);
+        let mut board = Board::empty(config.clone());
+        let start = Board::new(config);
+        for point in start.pieces_of(Player::Head) {
+            board.set_piece(point, Player::Tail).unwrap();
+        }
+        assert!(board.has_player_won(Player::Tail));
+
+        board.set_piece(Point::new(1, 16), Cell::Empty).unwrap();
+        assert!(!board.has_player_won(Player::Tail));
+        board.set_piece(Point::new(1, 16), Player::Tail).unwrap();
+        board.set_piece(Point::new(5, 12), Cell::Empty).unwrap();
+        assert!(board.has_player_won(Player::Tail));
+
+        assert_eq!(start.pieces_of(Player::Head).count(), 15);
+        assert_eq!(start.pieces_of(Player::Tail).count(), 10);
+    }
+
+    #[test]
+    fn test_has_player_won_with_home_blockers() {
+        let config = Config::default()
+            .with_player_lines(2)
+            .with_layout(StartLayout::TwoPlayers);
+        let mut board = Board::empty(config.clone());
+        let target: Vec<Point> = Board::new(config.clone()).pieces_of(Player::Tail).collect();
+        for point in &target {
+            board.set_piece(*point, Player::Head).unwrap();
+        }
+        board.set_piece(target[0], Player::Tail).unwrap();
+        assert!(!board.has_player_won(Player::Head));
+
+        let mut board = Board::from_rows(
+            board.rows().collect(),
+            config.with_goal_rule(GoalRule::HomeBlockers),
+        );
+        assert!(board.has_player_won(Player::Head));
+        for point in &target {
+            board.set_piece(*point, Player::Tail).unwrap();
+        }
+        assert!(!board.has_player_won(Player::Head));
+        board.set_piece(target[0], Player::LeftHand).unwrap();
+        board.set_piece(target[1], Player::Head).unwrap();
+        assert!(!board.has_player_won(Player::Head));
+    }
+
+    #[test]
+    fn test_has_player_won_with_any_occupant() {
+        let config = Config::default()
+            .with_player_lines(2)
+            .with_goal_rule(GoalRule::AnyOccupant);
+        let mut board = Board::empty(config.clone());
+        let target: Vec<Point> = Board::new(config).pieces_of(Player::Tail).collect();
+        for point in &target {
+            board.set_piece(*point, Player::LeftHand).unwrap();
+        }
+        assert!(!board.has_player_won(Player::Head));
+        board.set_piece(target[1], Player::Head).unwrap();
+        assert!(board.has_player_won(Player::Head));
+        assert!(!board.has_player_won(Player::LeftHand));
+        board.set_piece(target[2], Cell::Empty).unwrap();
+        assert!(!board.has_player_won(Player::Head));
+    }
+
+    #[test]
+    fn test_pieces_home_and_finish_by_count() {
+        let config = Config::default()
+            .with_player_lines(2)
+            .with_win_condition(WinCondition::FirstNPiecesHome(2));
+        let mut board = Board::new(config);
+        assert_eq!(board.pieces_home(Player::Head), 0);
+        assert!(!board.has_player_finished(Player::Head));
+
+        board.set_piece(Point::new(9, 7), Player::Head).unwrap();
+        board.set_piece(Point::new(8, 6), Player::Head).unwrap();
+        assert_eq!(board.pieces_home(Player::Head), 2);
+        assert!(board.has_player_finished(Player::Head));
+        assert!(!board.has_player_won(Player::Head));
+    }
+
+    #[test]
+    fn test_has_player_finished_with_turn_limit() {
+        let config = Config::default().with_win_condition(WinCondition::ScoreAtTurnLimit(10));
+        let mut board = Board::empty(config);
+        for point in Board::default().pieces_of(Player::Tail) {
+            board.set_piece(point, Player::Head).unwrap();
         }
+        assert!(board.has_player_won(Player::Head));
+        assert!(!board.has_player_finished(Player::Head));
+        assert_eq!(board.pieces_home(Player::Head), 10);
     }
 
-    pub fn play(&mut self) -> Result<(), GameError> {
-        let turns = vec![
-            vec![Point::new(4, 10), Point::new(5, 11)],   // Head
-            vec![Point::new(14, 16), Point::new(13, 15)], // Tail
-            vec![Point::new(3, 11), Point::new(5, 13), Point::new(5, 9)], // Head
-        ];
+    #[test]
+    fn test_variant_standings() {
+        use Player::*;
+        let finished = [Head, LeftHand];
+        let remaining = [Tail];
+        assert_eq!(
+            Variant::Standard.standings(&finished, &remaining),
+            vec![Head, LeftHand, Tail],
+        );
+        assert_eq!(
+            Variant::Misere.standings(&finished, &remaining),
+            vec![Tail, LeftHand, Head],
+        );
+    }
 
-        let mut total_rounds = 0;
-        let mut total_turns = 0;
-        let mut playing = self.pieces.clone();
-        let mut victorious = Vec::<Piece>::new();
+    #[test]
+    fn test_has_player_won_with_non_opposite_target() {
+        let config = Config::default()
+            .with_player_lines(2)
+            .with_target(Player::Head, Player::LeftFoot);
+        let mut board = Board::new(config);
+        assert!(!board.has_player_won(Player::Head));
+        for point in board.pieces_of(Player::LeftFoot).collect::<Vec<_>>() {
+            board.set_piece(point, Player::Head).unwrap();
+        }
+        assert!(board.has_player_won(Player::Head));
+        assert_eq!(board.pieces_home(Player::Head), 3);
+    }
 
-        'outer: while playing.len() > 1 {
-            for piece in playing.clone() {
-                if total_turns >= turns.len() {
-                    break 'outer;
-                }
+    #[test]
+    fn test_start_layouts() {
+        let config = Config::default().with_layout(StartLayout::TwoPlayers);
+        let board = Board::new(config.clone());
+        assert_eq!(board.pieces_of(Player::Head).count(), 10);
+        assert_eq!(board.pieces_of(Player::Tail).count(), 10);
+        assert_eq!(board.pieces_of(Player::LeftHand).count(), 0);
+        let board = Board::new(config.with_corner_lines(Player::LeftHand, 0));
+        assert!(!board.has_player_won(Player::LeftHand));
 
-                println!("\nNext turn by {:?}\n", &piece);
-                let turn = turns.clone()[total_turns].clone();
-                self.board.take_turn(turn, piece)?;
-                self.board.draw();
-                if self.board.has_player_won(piece) {
-                    println!("\nPlayer {:?} has finished\n", &piece);
-                    playing.retain(|x| x != &piece);
-                    victorious.push(piece);
-                    if playing.len() < 2 {
-                        break 'outer;
-                    }
-                }
+        let board = Board::new(Config::default().with_layout(StartLayout::ThreePlayers));
+        for piece in [Player::Head, Player::RightFoot, Player::LeftFoot].iter() {
+            assert_eq!(board.pieces_of(*piece).count(), 10);
+            assert_eq!(board.pieces_of(piece.opposite()).count(), 0);
+        }
 
-                total_turns += 1;
-            }
-            total_rounds += 1;
+        assert_eq!(StartLayout::for_players(4), Some(StartLayout::FourPlayers));
+        assert_eq!(StartLayout::for_players(5), None);
+        for count in [2, 3, 4, 6].iter() {
+            let layout = StartLayout::for_players(*count).unwrap();
+            assert_eq!(layout.players().len(), *count);
         }
+    }
 
-        println!("\nThe game is over!");
-        println!("It lasted {} rounds", total_rounds + 1);
-        Ok(())
+    #[test]
+    fn test_has_player_won_no() {
+        use Player::*;
+        let e = Cell::Empty;
+        let (head, tail, lefthand, righthand, leftfoot, rightfoot) = (
+            Cell::Occupied(Head),
+            Cell::Occupied(Tail),
+            Cell::Occupied(LeftHand),
+            Cell::Occupied(RightHand),
+            Cell::Occupied(LeftFoot),
+            Cell::Occupied(RightFoot),
+        );
+        #[rustfmt::skip]
+        let rows = vec![
+            vec![                        head                       ],
+            vec![                     tail, tail                    ],
+            vec![ lefthand, rightfoot, e, e, e, leftfoot, righthand ],
+            vec![          rightfoot, e, e, e, e, leftfoot          ],
+            vec![                   e, e, e, e, e                   ],
+            vec![          righthand, e, e, e, e, lefthand          ],
+            vec![ leftfoot, righthand, e, e, e, lefthand, rightfoot ],
+            vec![                     head, head                    ],
+            vec![                        tail                       ],
+        ];
+        let board = Board::from_rows(
+            rows,
+            Config {
+                player_lines: 2,
+                ..Default::default()
+            },
+        );
+        assert!(!board.has_player_won(Head));
+        assert!(!board.has_player_won(LeftHand));
+        assert!(!board.has_player_won(RightHand));
+        assert!(!board.has_player_won(LeftFoot));
+        assert!(!board.has_player_won(RightFoot));
+        assert!(!board.has_player_won(Tail));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    fn endgame(config: Config) -> Game {
+        let mut board = Board::empty(config.with_player_lines(1));
+        board.set_piece(Point::new(4, 3), Player::Head).unwrap();
+        board.set_piece(Point::new(3, 4), Player::Tail).unwrap();
+        Game::new(board, vec![Player::Head, Player::Tail])
+    }
 
     #[test]
-    fn test_new_small_board() {
-        use Piece::*;
+    fn test_game_turn_order() {
+        let mut game = Game::new(Board::default(), vec![Player::Head, Player::Tail]);
+        assert_eq!(game.state(), GameState::InProgress);
+        assert_eq!(game.current_player(), Some(Player::Head));
         assert_eq!(
-            Board::new(Config {
-                player_lines: 1,
-                ..Config::default()
+            game.submit_turn(vec![Point::new(4, 10), Point::new(5, 11)]),
+            Ok("4/10-5/11".parse().unwrap()),
+        );
+        assert_eq!(game.current_player(), Some(Player::Tail));
+        assert_eq!(game.rounds(), 0);
+
+        let before = game.clone();
+        assert_eq!(
+            game.submit_turn(vec![Point::new(5, 11), Point::new(6, 12)]),
+            Err(GameError::WrongPlayer {
+                point: Point::new(5, 11),
+                expected: Player::Tail,
+                found: Cell::Occupied(Player::Head),
             }),
-            Board {
-                #[cfg_attr(rustfmt, rustfmt_skip)]
-                rows: vec![
-                    vec![              Head                 ],
-                    vec![ LeftHand, Empty, Empty, RightHand ],
-                    vec![       Empty, Empty, Empty         ],
-                    vec![ LeftFoot, Empty, Empty, RightFoot ],
-                    vec![              Tail                 ]
-                ],
-                config: Config {
-                    player_lines: 1,
-                    ..Config::default()
-                },
-            },
         );
+        assert_eq!(game, before);
+
+        game.play_turn(&"14/16-13/15".parse().unwrap()).unwrap();
+        assert_eq!(game.current_player(), Some(Player::Head));
+        assert_eq!(game.rounds(), 1);
     }
 
     #[test]
-    fn test_new_standard_board() {
-        use Piece::*;
-        let e = Empty;
-        let lhand = LeftHand;
-        let rhand = RightHand;
-        let lfoot = LeftFoot;
-        let rfoot = RightFoot;
+    fn test_game_play_turn_as() {
+        let mut board = endgame(Config::default()).board;
+        board.set_piece(Point::new(3, 2), Player::LeftHand).unwrap();
+        let mut game = Game::new(board, vec![Player::Head, Player::Tail, Player::LeftHand]);
+        let step = "3/4-2/5".parse().unwrap();
         assert_eq!(
-            Board::new(Config::default()),
-            Board {
-                #[cfg_attr(rustfmt, rustfmt_skip)]
-                rows: vec![
-                    vec![                                 Head                                  ],
-                    vec![                              Head, Head                               ],
-                    vec![                           Head, Head, Head                            ],
-                    vec![                        Head, Head, Head, Head                         ],
-                    vec![ lhand, lhand, lhand, lhand, e, e, e, e, e, rhand, rhand, rhand, rhand ],
-                    vec![      lhand, lhand, lhand, e, e, e, e, e, e, rhand, rhand, rhand       ],
-                    vec![           lhand, lhand, e, e, e, e, e, e, e, rhand, rhand             ],
-                    vec![                lhand, e, e, e, e, e, e, e, e, rhand                   ],
-                    vec![                     e, e, e, e, e, e, e, e, e                         ],
-                    vec![                lfoot, e, e, e, e, e, e, e, e, rfoot                   ],
-                    vec![           lfoot, lfoot, e, e, e, e, e, e, e, rfoot, rfoot             ],
-                    vec![      lfoot, lfoot, lfoot, e, e, e, e, e, e, rfoot, rfoot, rfoot       ],
-                    vec![ lfoot, lfoot, lfoot, lfoot, e, e, e, e, e, rfoot, rfoot, rfoot, rfoot ],
-                    vec![                        Tail, Tail, Tail, Tail                         ],
-                    vec![                           Tail, Tail, Tail                            ],
-                    vec![                              Tail, Tail                               ],
-                    vec![                                 Tail                                  ],
-                ],
-                config: Config::default(),
-            }
-        )
-    }
+            game.play_turn_as(Player::Tail, &step),
+            Err(GameError::NotYourTurn {
+                player: Player::Tail,
+                to_move: Player::Head,
+            })
+        );
+        assert_eq!(game.history().len(), 0);
 
-    #[test]
-    fn test_serialize_small_board() {
+        game.play_turn_as(Player::Head, &"4/3-5/4".parse().unwrap())
+            .unwrap();
+        game.play_turn_as(Player::Tail, &step).unwrap();
+        game.play_turn_as(Player::LeftHand, &"3/2-4/1".parse().unwrap())
+            .unwrap();
+        // Head has finished, so it goes straight back to Tail.
+        assert_eq!(game.current_player(), Some(Player::Tail));
         assert_eq!(
-            Board::new(Config {
-                player_lines: 1,
-                ..Config::default()
+            game.play_turn_as(Player::Head, &"5/4-4/5".parse().unwrap()),
+            Err(GameError::NotYourTurn {
+                player: Player::Head,
+                to_move: Player::Tail,
             })
-            .serialize(),
-            vec!["    1", " 3 . . 5", "  . . .", " 6 . . 4", "    2"],
         );
     }
 
     #[test]
-    fn test_serialize_standard_board() {
-        assert_eq!(
-            Board::new(Config::default()).serialize(),
-            vec![
-                "             1",
-                "            1 1",
-                "           1 1 1",
-                "          1 1 1 1",
-                " 3 3 3 3 . . . . . 5 5 5 5",
-                "  3 3 3 . . . . . . 5 5 5",
-                "   3 3 . . . . . . . 5 5",
-                "    3 . . . . . . . . 5",
-                "     . . . . . . . . .",
-                "    6 . . . . . . . . 4",
-                "   6 6 . . . . . . . 4 4",
-                "  6 6 6 . . . . . . 4 4 4",
-                " 6 6 6 6 . . . . . 4 4 4 4",
-                "          2 2 2 2",
-                "           2 2 2",
-                "            2 2",
-                "             2",
+    fn test_captures() {
+        let config = Config::default().with_captures(true);
+        let mut board = Board::from_setup(
+            &[
+                (Point::new(9, 9), Player::Head),
+                (Point::new(9, 11), Player::Tail),
+                (Point::new(9, 15), Player::Head),
             ],
+            config,
+        )
+        .unwrap();
+        let turn: Turn = "9/9>9/13>9/17".parse().unwrap();
+        assert_eq!(turn.jumped(), vec![Point::new(9, 11), Point::new(9, 15)]);
+        assert_eq!(
+            board.captured_by(&turn, Player::Head),
+            vec![(Point::new(9, 11), Player::Tail)]
         );
-    }
 
-    #[test]
-    fn test_try_move_piece_with_success() {
-        let board = Board::default();
+        let start = board.clone();
+        let captured = board.make_turn(&turn);
+        assert_eq!(captured, vec![(Point::new(9, 11), Player::Tail)]);
+        assert_eq!(board.get_piece(Point::new(9, 11)), Some(Cell::Empty));
+        board.unmake_turn(&turn, &captured);
+        assert_eq!(board, start);
+
+        board.take_turn(&turn, Player::Head).unwrap();
+        assert_eq!(board.get_piece(Point::new(9, 11)), Some(Cell::Empty));
         assert_eq!(
-            board.try_move_piece(Point::new(4, 10), Point::new(5, 11), Piece::Head),
-            Ok(()),
+            board.get_piece(Point::new(9, 15)),
+            Some(Cell::Occupied(Player::Head))
         );
+        // Going back would jump over the captured piece again.
+        assert!(board
+            .try_turn(&"9/17>9/13>9/9".parse().unwrap(), Player::Head)
+            .is_err());
+
+        let mut plain = Board::empty(Config::default());
+        plain.restore(&start.snapshot()).unwrap();
+        assert_eq!(plain.captured_by(&turn, Player::Head), vec![]);
     }
 
     #[test]
-    fn test_try_move_piece_with_no_route_because_too_far() {
-        let board = Board::default();
+    fn test_game_elimination() {
+        let config = Config::default()
+            .with_captures(true)
+            .with_win_condition(WinCondition::Elimination);
+        let board = Board::from_setup(
+            &[
+                (Point::new(9, 9), Player::Head),
+                (Point::new(9, 11), Player::Tail),
+                (Point::new(9, 17), Player::LeftHand),
+            ],
+            config.clone(),
+        )
+        .unwrap();
+        let players = vec![Player::Head, Player::Tail, Player::LeftHand];
+        let mut game = Game::new(board, players);
+        assert!(!game.board.has_player_finished(Player::Head));
+
+        game.play_turn(&"9/9>9/13".parse().unwrap()).unwrap();
         assert_eq!(
-            board.try_move_piece(Point::new(1, 13), Point::new(7, 13), Piece::Head),
-            Err(GameError::NoRoute),
+            game.history()[0].captured,
+            vec![(Point::new(9, 11), Player::Tail)]
         );
+        assert_eq!(game.eliminated(), &[Player::Tail]);
+        assert_eq!(game.playing(), &[Player::Head, Player::LeftHand]);
+        assert_eq!(game.current_player(), Some(Player::LeftHand));
+
+        game.play_turn(&"9/17-9/15".parse().unwrap()).unwrap();
+        game.play_turn(&"9/13>9/17".parse().unwrap()).unwrap();
+        assert_eq!(game.state(), GameState::Finished);
+        assert_eq!(game.finished(), &[Player::Head]);
+        assert_eq!(
+            game.standings(),
+            vec![Player::Head, Player::LeftHand, Player::Tail]
+        );
+
+        game.undo();
+        assert_eq!(game.eliminated(), &[Player::Tail]);
+        assert_eq!(game.current_player(), Some(Player::Head));
+
+        // Without the win condition, losing every piece still ends the game.
+        let board = Board::from_setup(
+            &[
+                (Point::new(9, 9), Player::Head),
+                (Point::new(9, 11), Player::Tail),
+            ],
+            config.with_win_condition(WinCondition::FillTarget),
+        )
+        .unwrap();
+        let mut game = Game::new(board, vec![Player::Head, Player::Tail]);
+        game.play_turn(&"9/9>9/13".parse().unwrap()).unwrap();
+        assert_eq!(game.state(), GameState::Finished);
+        assert_eq!(game.standings(), vec![Player::Head, Player::Tail]);
     }
 
     #[test]
-    fn test_try_move_piece_with_no_route_because_no_middle_piece() {
-        let board = Board::default();
+    fn test_game_seat_info() {
+        let mut game = Game::new(Board::default(), vec![Player::Head, Player::Tail]);
         assert_eq!(
-            board.try_move_piece(Point::new(4, 10), Point::new(6, 12), Piece::Head),
-            Err(GameError::NoRoute),
+            game.seat_info(Player::Tail),
+            Some(&SeatInfo::new(Player::Tail))
         );
+        assert_eq!(game.seat_info(Player::Tail).unwrap().name, "Tail");
+        assert_eq!(game.seat_info(Player::LeftHand), None);
+
+        let info = SeatInfo::new(Player::Tail)
+            .with_name("Ada")
+            .with_rating(1500)
+            .with_controller(Controller::Bot);
+        assert!(game.set_seat_info(Player::Tail, info.clone()));
+        assert!(!game.set_seat_info(Player::LeftHand, info.clone()));
+        game.play_turn(&"4/10-5/11".parse().unwrap()).unwrap();
+        game.undo();
+        assert_eq!(game.seat_info(Player::Tail), Some(&info));
+        assert_eq!(game.seats(), &[Player::Head, Player::Tail]);
     }
 
     #[test]
-    fn test_try_move_piece_with_target_occupied() {
-        let board = Board::default();
+    fn test_game_history() {
+        let mut game = Game::new(Board::default(), vec![Player::Head, Player::Tail]);
+        game.play_turn(&"4/10-5/11".parse().unwrap()).unwrap();
+        let middle = game.board.clone();
+        game.play_turn(&"14/16-13/15".parse().unwrap()).unwrap();
+        assert!(game.play_turn(&"1/13-2/12".parse().unwrap()).is_err());
+
         assert_eq!(
-            board.try_move_piece(Point::new(1, 13), Point::new(2, 12), Piece::Head),
-            Err(GameError::OccupiedTarget),
+            game.history(),
+            &[
+                TurnRecord {
+                    number: 1,
+                    player: Player::Head,
+                    turn: "4/10-5/11".parse().unwrap(),
+                    captured: vec![],
+                    spent: None,
+                    timestamp: None,
+                    clock_remaining: None,
+                    evaluation: None,
+                    comment: None,
+                },
+                TurnRecord {
+                    number: 2,
+                    player: Player::Tail,
+                    turn: "14/16-13/15".parse().unwrap(),
+                    captured: vec![],
+                    spent: None,
+                    timestamp: None,
+                    clock_remaining: None,
+                    evaluation: None,
+                    comment: None,
+                },
+            ],
         );
+        assert_eq!(game.position_after(0), Some(Board::default()));
+        assert_eq!(game.position_after(1), Some(middle));
+        assert_eq!(game.position_after(2), Some(game.board.clone()));
+        assert_eq!(game.position_after(3), None);
     }
 
     #[test]
-    fn test_try_move_piece_with_out_of_bounds() {
-        let board = Board::default();
-        assert_eq!(
-            board.try_move_piece(Point::new(1, 13), Point::new(1, 12), Piece::Head),
-            Err(GameError::OutOfBounds),
+    fn test_game_play_bots() {
+        let mut game = Game::new(Board::default(), vec![Player::Head, Player::Tail]);
+        let mut bots: Vec<Box<dyn ai::Bot>> =
+            vec![Box::new(ai::GreedyBot), Box::new(ai::RandomBot)];
+        let mut events = vec![];
+        game.play_bots(&mut bots, 5, |_, event| events.push(event.clone()))
+            .unwrap();
+        assert_eq!(game.history().len(), 5);
+        assert_eq!(game.current_player(), Some(Player::Tail));
+        assert_eq!(events.len(), 10);
+        assert_eq!(events[0], GameEvent::TurnStarted(Player::Head));
+        assert_eq!(events[1], GameEvent::TurnTaken(game.history()[0].clone()));
+    }
+
+    #[test]
+    fn test_game_undo() {
+        let mut game = Game::new(
+            Board::default(),
+            vec![Player::Head, Player::Tail, Player::LeftHand],
         );
+        assert_eq!(game.undo(), None);
+        game.play_turn(&"4/10-5/11".parse().unwrap()).unwrap();
+        assert!(game.resign(Player::LeftHand));
+        game.play_turn(&"14/16-13/15".parse().unwrap()).unwrap();
+        assert!(game.resign(Player::Head));
+
+        assert_eq!(game.undo().map(|x| x.player), Some(Player::Tail));
+        assert_eq!(game.playing(), &[Player::Head, Player::Tail]);
+        assert_eq!(game.current_player(), Some(Player::Tail));
+        assert_eq!(game.undo().map(|x| x.player), Some(Player::Head));
+        assert_eq!(game.board, Board::default());
+        assert_eq!(game.playing().len(), 3);
     }
 
     #[test]
-    fn test_try_move_piece_with_wrong_player() {
-        let board = Board::default();
+    fn test_game_suggest_turn() {
+        let mut game = endgame(Config::default());
         assert_eq!(
-            board.try_move_piece(Point::new(4, 10), Point::new(5, 11), Piece::Tail),
-            Err(GameError::WrongPlayer),
+            game.suggest_turn(Player::Head),
+            Some("4/3-5/4".parse().unwrap())
         );
+        let hint = game.suggest_turn(Player::Tail).unwrap();
+        assert!(game.board.legal_turns(Player::Tail).contains(&hint));
+        assert_eq!(game.current_player(), Some(Player::Head));
+        assert_eq!(game.suggest_turn(Player::LeftHand), None);
+
+        game.play_turn(&"4/3-5/4".parse().unwrap()).unwrap();
+        assert_eq!(game.suggest_turn(Player::Tail), None);
     }
 
     #[test]
-    fn test_try_turn_with_success_on_single_move() {
-        let board = Board::default();
+    fn test_game_finish() {
+        let mut game = endgame(Config::default());
+        game.play_turn(&"4/3-5/4".parse().unwrap()).unwrap();
+        assert_eq!(game.state(), GameState::Finished);
+        assert_eq!(game.current_player(), None);
+        assert_eq!(game.finished(), &[Player::Head]);
+        assert_eq!(game.standings(), vec![Player::Head, Player::Tail]);
         assert_eq!(
-            board.try_turn(vec![Point::new(4, 10), Point::new(5, 11)], Piece::Head,),
-            Ok(()),
+            game.play_turn(&"3/4-2/3".parse().unwrap()),
+            Err(GameError::GameFinished),
         );
     }
 
     #[test]
-    fn test_try_turn_with_success_on_single_jump() {
-        let board = Board::default();
-        assert_eq!(
-            board.try_turn(vec![Point::new(3, 11), Point::new(5, 13)], Piece::Head,),
-            Ok(()),
+    fn test_game_with_turn_limit() {
+        let mut game =
+            endgame(Config::default().with_win_condition(WinCondition::ScoreAtTurnLimit(1)));
+        game.play_turn(&"4/3-5/4".parse().unwrap()).unwrap();
+        assert_eq!(game.current_player(), Some(Player::Tail));
+        game.play_turn(&"3/4-2/3".parse().unwrap()).unwrap();
+        assert_eq!(game.state(), GameState::Finished);
+        assert_eq!(game.standings(), vec![Player::Head, Player::Tail]);
+    }
+
+    /// A game where both players can step sideways without getting closer.
+    fn shuffle(config: Config) -> (Game, Vec<Turn>) {
+        let mut board = Board::empty(config.with_player_lines(1));
+        board.set_piece(Point::new(3, 2), Player::Head).unwrap();
+        board.set_piece(Point::new(2, 5), Player::Tail).unwrap();
+        let turns = ["3/2-3/4", "2/5-2/3", "3/4-3/2", "2/3-2/5"]
+            .iter()
+            .map(|x| x.parse().unwrap())
+            .collect();
+        (Game::new(board, vec![Player::Head, Player::Tail]), turns)
+    }
+
+    #[test]
+    fn test_game_drawn_by_repetition() {
+        let (mut game, turns) = shuffle(Config::default().with_repetition_limit(2));
+        for turn in &turns[..3] {
+            game.play_turn(turn).unwrap();
+        }
+        assert_eq!(game.state(), GameState::InProgress);
+        game.play_turn(&turns[3]).unwrap();
+        assert_eq!(game.state(), GameState::Drawn(DrawReason::Repetition));
+        assert_eq!(game.current_player(), None);
+        assert_eq!(game.result().winner(), None);
+        assert_eq!(game.play_turn(&turns[0]), Err(GameError::GameFinished));
+
+        game.undo();
+        assert_eq!(game.state(), GameState::InProgress);
+
+        let (mut game, turns) = shuffle(Config::default());
+        for turn in turns.iter().chain(&turns) {
+            game.play_turn(turn).unwrap();
+        }
+        assert_eq!(game.state(), GameState::InProgress);
+    }
+
+    #[test]
+    fn test_game_drawn_without_progress() {
+        let (mut game, turns) = shuffle(Config::default().with_progress_limit(3));
+        game.play_turn(&turns[0]).unwrap();
+        game.play_turn(&turns[1]).unwrap();
+        // Moving closer resets the count.
+        game.play_turn(&"3/4-4/5".parse().unwrap()).unwrap();
+        game.play_turn(&turns[3]).unwrap();
+        game.play_turn(&"4/5-4/3".parse().unwrap()).unwrap();
+        assert_eq!(game.state(), GameState::InProgress);
+        game.play_turn(&turns[1]).unwrap();
+        assert_eq!(game.state(), GameState::Drawn(DrawReason::NoProgress));
+    }
+
+    #[test]
+    fn test_game_resign() {
+        let mut game = Game::new(
+            Board::default(),
+            vec![Player::Head, Player::Tail, Player::LeftHand],
         );
+        game.play_turn(&"4/10-5/11".parse().unwrap()).unwrap();
+        assert!(game.resign(Player::Head));
+        assert!(!game.resign(Player::Head));
+        assert_eq!(game.current_player(), Some(Player::Tail));
+        assert!(game.resign(Player::LeftHand));
+        assert_eq!(game.state(), GameState::Finished);
     }
 
     #[test]
-    fn test_try_turn_with_success_on_multiple_jumps() {
-        let mut board = Board::default();
-        board
-            .move_piece(Point::new(4, 10), Point::new(5, 11), Piece::Head)
+    fn test_game_forfeit_with_cleared_pieces() {
+        let config = Config::default()
+            .with_layout(StartLayout::ThreePlayers)
+            .with_forfeit_rule(ForfeitRule::Clear);
+        let players = config.layout().players();
+        let mut game = Game::new(Board::new(config), players);
+        game.play_turn(&"4/10-5/11".parse().unwrap()).unwrap();
+        assert!(game.forfeit_on_timeout(Player::RightFoot));
+        assert_eq!(game.board.pieces_of(Player::RightFoot).count(), 0);
+        assert_eq!(game.position_after(1), Some(game.board.clone()));
+        assert_eq!(game.current_player(), Some(Player::LeftFoot));
+
+        let placing = game.result().placing(Player::RightFoot).cloned();
+        assert_eq!(placing.map(|x| x.outcome), Some(Outcome::TimedOut));
+
+        game.play_turn(&game.suggest_turn(Player::LeftFoot).unwrap())
             .unwrap();
-        assert_eq!(
-            board.try_turn(
-                vec![Point::new(3, 11), Point::new(5, 13), Point::new(5, 9)],
-                Piece::Head,
-            ),
-            Ok(()),
+        game.undo();
+        assert_eq!(game.board.pieces_of(Player::RightFoot).count(), 0);
+
+        let mut frozen = Game::new(
+            Board::new(Config::default()),
+            vec![Player::Head, Player::Tail],
         );
+        frozen.resign(Player::Tail);
+        assert_eq!(frozen.board, Board::default());
     }
 
     #[test]
-    fn test_try_turn_with_exhaustion() {
-        let board = Board::default();
+    fn test_game_timed_turns() {
+        let mut game = Game::new(
+            Board::default(),
+            vec![Player::Head, Player::Tail, Player::LeftHand],
+        );
+        game.set_time_control(clock::TimeControl::Increment {
+            base: Duration::from_secs(10),
+            increment: Duration::from_secs(2),
+        });
+        let step = "4/10-5/11".parse().unwrap();
+        game.play_timed_turn(&step, Duration::from_secs(4)).unwrap();
+        let clock = game.clock().unwrap();
+        assert_eq!(clock.remaining(Player::Head), Some(Duration::from_secs(8)));
+        assert_eq!(game.history()[0].spent, Some(Duration::from_secs(4)));
+
+        // An illegal turn costs nothing, but running out of time does.
+        assert!(game.play_timed_turn(&step, Duration::from_secs(1)).is_err());
         assert_eq!(
-            board.try_turn(
-                vec![Point::new(4, 10), Point::new(5, 11), Point::new(6, 12)],
-                Piece::Head,
-            ),
-            Err(GameError::Exhausted),
+            game.play_timed_turn(&"14/16-13/15".parse().unwrap(), Duration::from_secs(11)),
+            Err(GameError::TimeExpired)
+        );
+        assert_eq!(game.playing(), &[Player::Head, Player::LeftHand]);
+        assert_eq!(game.current_player(), Some(Player::LeftHand));
+        let placing = game.result().placing(Player::Tail).cloned();
+        assert_eq!(placing.map(|x| x.outcome), Some(Outcome::TimedOut));
+
+        let turn = game.suggest_turn(Player::LeftHand).unwrap();
+        game.play_timed_turn(&turn, Duration::from_secs(3)).unwrap();
+        assert_eq!(game.history()[1].timestamp, Some(Duration::from_secs(7)));
+        assert_eq!(
+            game.history()[1].clock_remaining,
+            Some(Duration::from_secs(9))
         );
+
+        game.undo();
+        assert_eq!(game.history().len(), 1);
+        let clock = game.clock().unwrap();
+        assert_eq!(clock.remaining(Player::Head), Some(Duration::from_secs(8)));
+        assert_eq!(clock.remaining(Player::Tail), Some(Duration::from_secs(0)));
     }
 
     #[test]
-    fn test_get_piece_from_board_with_even_player_lines() {
-        let board = Board::default();
-
-        // Out of bounds.
-        assert_eq!(board.get_piece(Point::new(1, 1)), None);
-        assert_eq!(board.get_piece(Point::new(1, 12)), None);
+    fn test_game_turn_metadata() {
+        let mut game = Game::new(Board::default(), vec![Player::Head, Player::Tail]);
+        assert!(!game.set_turn_comment(Some(String::from("too early"))));
+        assert!(!game.set_turn_evaluation(Some(5)));
 
-        // Row with odd number of pieces.
-        assert_eq!(board.get_piece(Point::new(1, 13)), Some(Piece::Head));
-        assert_eq!(board.get_piece(Point::new(5, 17)), Some(Piece::Empty));
-        assert_eq!(board.get_piece(Point::new(5, 19)), Some(Piece::RightHand));
+        game.play_turn(&"4/10-5/11".parse().unwrap()).unwrap();
+        assert!(game.set_turn_comment(Some(String::from("a quiet start"))));
+        assert!(game.set_turn_evaluation(Some(-3)));
+        game.play_turn(&"14/16-13/15".parse().unwrap()).unwrap();
+        assert!(game.set_turn_comment(Some(String::from("mirrored"))));
+        assert_eq!(game.history()[0].evaluation, Some(-3));
+        assert_eq!(game.history()[1].evaluation, None);
 
-        // Row with even number of pieces.
-        assert_eq!(board.get_piece(Point::new(2, 12)), Some(Piece::Head));
-        assert_eq!(board.get_piece(Point::new(6, 2)), Some(Piece::LeftHand));
-        assert_eq!(board.get_piece(Point::new(6, 6)), Some(Piece::LeftHand));
-        assert_eq!(board.get_piece(Point::new(6, 8)), Some(Piece::Empty));
+        // Undoing keeps what was noted about the turns that are left.
+        game.undo();
+        let record = &game.history()[0];
+        assert_eq!(record.comment.as_deref(), Some("a quiet start"));
+        assert_eq!(record.evaluation, Some(-3));
+        assert_eq!(record.clock_remaining, None);
     }
 
     #[test]
-    fn test_get_piece_from_board_with_odd_player_lines() {
-        let board = Board::new(Config {
-            player_lines: 3,
-            ..Default::default()
-        });
+    fn test_game_clock_changes() {
+        let mut game = Game::new(Board::default(), vec![Player::Head, Player::Tail]);
+        assert!(!game.pause_clock());
+        assert!(!game.set_remaining_time(Player::Head, Duration::from_secs(5)));
+        game.set_time_control(clock::TimeControl::Absolute(Duration::from_secs(10)));
 
-        // Out of bounds.
-        assert_eq!(board.get_piece(Point::new(1, 1)), None);
-        assert_eq!(board.get_piece(Point::new(1, 12)), None);
+        game.play_timed_turn(&"4/10-5/11".parse().unwrap(), Duration::from_secs(4))
+            .unwrap();
+        assert!(game.pause_clock());
+        assert!(game.clock().unwrap().is_paused());
+        game.play_timed_turn(&"14/16-13/15".parse().unwrap(), Duration::from_secs(30))
+            .unwrap();
+        assert!(game.resume_clock());
+        assert!(!game.resume_clock());
+        assert!(game.set_remaining_time(Player::Head, Duration::from_secs(20)));
+        assert!(!game.set_remaining_time(Player::LeftHand, Duration::from_secs(20)));
+        game.play_timed_turn(&"5/11-6/12".parse().unwrap(), Duration::from_secs(1))
+            .unwrap();
+        let clock = game.clock().unwrap();
+        assert_eq!(clock.remaining(Player::Head), Some(Duration::from_secs(19)));
+        assert_eq!(clock.remaining(Player::Tail), Some(Duration::from_secs(10)));
 
-        // Row with odd number of pieces.
-        assert_eq!(board.get_piece(Point::new(1, 10)), Some(Piece::Head));
-        assert_eq!(board.get_piece(Point::new(6, 15)), Some(Piece::Empty));
-        assert_eq!(board.get_piece(Point::new(6, 17)), Some(Piece::RightHand));
+        // Undoing replays the pause and the correction before the last turn.
+        game.undo();
+        let clock = game.clock().unwrap();
+        assert!(!clock.is_paused());
+        assert_eq!(clock.remaining(Player::Head), Some(Duration::from_secs(20)));
+        assert_eq!(clock.remaining(Player::Tail), Some(Duration::from_secs(10)));
+        game.undo();
+        let clock = game.clock().unwrap();
+        assert!(clock.is_paused());
+        assert_eq!(clock.remaining(Player::Head), Some(Duration::from_secs(6)));
+    }
 
-        // Row with even number of pieces.
-        assert_eq!(board.get_piece(Point::new(2, 9)), Some(Piece::Head));
-        assert_eq!(board.get_piece(Point::new(4, 1)), Some(Piece::LeftHand));
-        assert_eq!(board.get_piece(Point::new(4, 5)), Some(Piece::LeftHand));
-        assert_eq!(board.get_piece(Point::new(4, 7)), Some(Piece::Empty));
+    #[test]
+    fn test_game_play_bots_on_the_clock() {
+        struct Slow;
+        impl ai::Bot for Slow {
+            fn choose(&mut self, game: &Game) -> Option<Turn> {
+                sleep(Duration::from_millis(20));
+                ai::GreedyBot.choose(game)
+            }
+        }
+
+        let mut game = Game::new(Board::default(), vec![Player::Head, Player::Tail]);
+        game.set_time_control(clock::TimeControl::PerMove(Duration::from_millis(10)));
+        let mut bots: Vec<Box<dyn ai::Bot>> = vec![Box::new(Slow), Box::new(ai::GreedyBot)];
+        let mut events = vec![];
+        game.play_bots(&mut bots, 5, |_, event| events.push(event.clone()))
+            .unwrap();
+        assert!(events.contains(&GameEvent::TimedOut(Player::Head)));
+        assert_eq!(game.playing(), &[Player::Tail]);
+        assert_eq!(game.state(), GameState::Finished);
     }
 
     #[test]
-    fn test_has_player_won_yes() {
-        use Piece::*;
-        let e = Empty;
-        let board = Board {
-            #[cfg_attr(rustfmt, rustfmt_skip)]
-            rows: vec![
-                vec![                        Tail                       ],
-                vec![                     Tail, Tail                    ],
-                vec![ RightFoot, RightFoot, e, e, e, LeftFoot, LeftFoot ],
-                vec![          RightFoot, e, e, e, e, LeftFoot          ],
-                vec![                   e, e, e, e, e                   ],
-                vec![          RightHand, e, e, e, e, LeftHand          ],
-                vec![ RightHand, RightHand, e, e, e, LeftHand, LeftHand ],
-                vec![                     Head, Head                    ],
-                vec![                        Head                       ],
+    fn test_game_result() {
+        let config = Config::default()
+            .with_captures(true)
+            .with_win_condition(WinCondition::Elimination);
+        let board = Board::from_setup(
+            &[
+                (Point::new(9, 9), Player::Head),
+                (Point::new(9, 11), Player::Tail),
+                (Point::new(9, 17), Player::LeftHand),
             ],
-            config: Config {
-                player_lines: 2,
-                ..Default::default()
-            },
-        };
-        assert!(board.has_player_won(Head));
-        assert!(board.has_player_won(LeftHand));
-        assert!(board.has_player_won(RightHand));
-        assert!(board.has_player_won(LeftFoot));
-        assert!(board.has_player_won(RightFoot));
-        assert!(board.has_player_won(Tail));
+            config,
+        )
+        .unwrap();
+        let mut game = Game::new(board, vec![Player::Head, Player::Tail, Player::LeftHand]);
+        game.play_turn(&"9/9>9/13".parse().unwrap()).unwrap();
+        let result = game.result();
+        assert_eq!(result.state, GameState::InProgress);
+        assert_eq!(result.winner(), None);
+        assert_eq!(
+            result.placing(Player::Head).unwrap().outcome,
+            Outcome::Playing
+        );
+        assert_eq!(
+            result.placing(Player::Tail),
+            Some(&Placing {
+                player: Player::Tail,
+                outcome: Outcome::Eliminated,
+                turns: 0,
+                left_after: Some(1),
+            })
+        );
+
+        game.play_turn(&"9/17-9/15".parse().unwrap()).unwrap();
+        game.play_turn(&"9/13>9/17".parse().unwrap()).unwrap();
+        let result = game.result();
+        assert_eq!(result.winner(), Some(Player::Head));
+        assert_eq!((result.turns, result.rounds), (3, 2));
+        let placings: Vec<_> = result
+            .placings
+            .iter()
+            .map(|x| (x.player, x.outcome, x.turns, x.left_after))
+            .collect();
+        assert_eq!(
+            placings,
+            vec![
+                (Player::Head, Outcome::Finished, 2, Some(3)),
+                (Player::LeftHand, Outcome::Eliminated, 1, Some(3)),
+                (Player::Tail, Outcome::Eliminated, 0, Some(1)),
+            ]
+        );
     }
 
     #[test]
-    fn test_has_player_won_no() {
-        use Piece::*;
-        let e = Empty;
-        let board = Board {
-            #[cfg_attr(rustfmt, rustfmt_skip)]
-            rows: vec![
-                vec![                        Head                       ],
-                vec![                     Tail, Tail                    ],
-                vec![ LeftHand, RightFoot, e, e, e, LeftFoot, RightHand ],
-                vec![          RightFoot, e, e, e, e, LeftFoot          ],
-                vec![                   e, e, e, e, e                   ],
-                vec![          RightHand, e, e, e, e, LeftHand          ],
-                vec![ LeftFoot, RightHand, e, e, e, LeftHand, RightFoot ],
-                vec![                     Head, Head                    ],
-                vec![                        Tail                       ],
+    fn test_game_result_with_resignations() {
+        let mut game = Game::new(
+            Board::default(),
+            vec![Player::Head, Player::Tail, Player::LeftHand],
+        );
+        game.play_turn(&"4/10-5/11".parse().unwrap()).unwrap();
+        game.resign(Player::Tail);
+        game.resign(Player::LeftHand);
+        let result = game.result();
+        assert_eq!(result.state, GameState::Finished);
+        assert_eq!(result.winner(), Some(Player::Head));
+        let placings: Vec<_> = result
+            .placings
+            .iter()
+            .map(|x| (x.player, x.outcome, x.left_after))
+            .collect();
+        assert_eq!(
+            placings,
+            vec![
+                (Player::Head, Outcome::Playing, None),
+                (Player::LeftHand, Outcome::Resigned, Some(1)),
+                (Player::Tail, Outcome::Resigned, Some(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_game_play_events() {
+        let config = Config::default()
+            .with_captures(true)
+            .with_win_condition(WinCondition::Elimination);
+        let board = Board::from_setup(
+            &[
+                (Point::new(9, 9), Player::Head),
+                (Point::new(9, 11), Player::Tail),
+                (Point::new(9, 17), Player::LeftHand),
             ],
-            config: Config {
-                player_lines: 2,
-                ..Default::default()
-            },
-        };
-        assert!(!board.has_player_won(Head));
-        assert!(!board.has_player_won(LeftHand));
-        assert!(!board.has_player_won(RightHand));
-        assert!(!board.has_player_won(LeftFoot));
-        assert!(!board.has_player_won(RightFoot));
-        assert!(!board.has_player_won(Tail));
+            config,
+        )
+        .unwrap();
+        let mut game = Game::new(board, vec![Player::Head, Player::Tail, Player::LeftHand]);
+        let turns: Vec<Turn> = ["9/9>9/13", "9/17-9/15", "9/13>9/17", "9/17-9/15"]
+            .iter()
+            .map(|x| x.parse().unwrap())
+            .collect();
+        let mut events = vec![];
+        game.play(&turns, |_, event| events.push(event.clone()))
+            .unwrap();
+        assert_eq!(game.history().len(), 3);
+        assert_eq!(
+            events
+                .iter()
+                .filter(|x| matches!(x, GameEvent::TurnTaken(_)))
+                .count(),
+            3
+        );
+        assert_eq!(
+            events[events.len() - 2..],
+            [
+                GameEvent::PlayerFinished(Player::Head),
+                GameEvent::GameOver(game.result()),
+            ]
+        );
     }
 }
@@ -1,7 +1,1079 @@
-use stelsalto::{Board, Game, Piece};
+use std::io::{self, BufRead, Write};
+use std::time::{Duration, Instant};
+use stelsalto::ai::{AlphaBeta, Bot, Difficulty, GreedyBot, Mcts, RandomBot};
+use stelsalto::analysis::{self, Score};
+use stelsalto::clock::TimeControl;
+use stelsalto::coach;
+use stelsalto::engine::Engine;
+use stelsalto::import::Importer;
+use stelsalto::ndjson::EventLog;
+#[cfg(feature = "net")]
+use stelsalto::net::{Client, Host, Update};
+use stelsalto::positions;
+use stelsalto::puzzle::{self, Problem};
+#[cfg(feature = "net")]
+use stelsalto::simul::Simul;
+use stelsalto::tikz;
+use stelsalto::tournament::{Format, Tournament};
+use stelsalto::training;
+use stelsalto::turn_log;
+use stelsalto::{
+    Board, Cell, ColorMode, Config, Controller, Game, GameError, GameEvent, GameState, GoalRule,
+    Pacing, Player, Point, StartLayout, SymbolTheme, Turn, Variant, WinCondition, MAX_PLAYER_LINES,
+};
 
-fn main() -> Result<(), Box<std::error::Error>> {
-    let mut game = Game::new(Board::default(), vec![Piece::Head, Piece::Tail]);
-    game.play()?;
+/// Number of turns in the demo game between the built-in bots.
+const DEMO_TURNS: usize = 20;
+
+/// Rounds that `--vary-openings` lets the computer vary its turns in.
+const OPENING_ROUNDS: usize = 3;
+
+/// Print a diagram from `Board::render` or `Game::render`.
+fn draw(rows: Vec<String>) {
+    for row in rows {
+        println!("{}", row);
+    }
+}
+
+/// Print what happens in the demo game as the bots play it.
+fn report(game: &Game, event: &GameEvent) {
+    match event {
+        GameEvent::TurnStarted(player) => {
+            println!("\nNext turn by {:?}\n", player);
+            println!("{:?} is thinking...", player);
+        }
+        GameEvent::TurnTaken(_) => draw(game.render()),
+        GameEvent::PlayerFinished(player) => match game.board.config().variant() {
+            Variant::Standard => println!("\nPlayer {:?} has finished\n", player),
+            Variant::Misere => println!("\nPlayer {:?} has finished and is out\n", player),
+        },
+        GameEvent::TimedOut(player) => println!("\nPlayer {:?} ran out of time\n", player),
+        GameEvent::GameOver(_) => {}
+    }
+}
+
+/// Print a prompt and read one line, or `None` at the end of input.
+fn prompt(text: &str) -> io::Result<Option<String>> {
+    print!("{}", text);
+    io::stdout().flush()?;
+    let mut line = String::new();
+    if io::stdin().lock().read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
+    Ok(Some(line.trim().to_string()))
+}
+
+/// Play in a game hosted by `serve`, taking turns from the terminal.
+#[cfg(feature = "net")]
+fn play_online(mut client: Client, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    client.join(name)?;
+    while let Some(update) = client.next_update()? {
+        let ask = matches!(
+            update,
+            Update::Position(..) | Update::Declined(_) | Update::Error(_)
+        );
+        match update {
+            Update::Welcome { player, .. } => println!("You are playing as {:?}", player),
+            Update::Joined { player, name } => println!("{} joined as {:?}", name, player),
+            Update::Start => println!("\nThe game has started!"),
+            Update::Position(board, _) => draw(board.render(None)),
+            Update::Played { player, turn } => println!("\n{:?} played {}", player, turn),
+            Update::Finished(player) => println!("{:?} has finished!", player),
+            Update::Resigned(player) => println!("{:?} resigned", player),
+            Update::Timeout(player) => println!("{:?} ran out of time", player),
+            Update::Arbiter => println!("You are the arbiter"),
+            Update::Paused => println!("The arbiter paused the clock"),
+            Update::Resumed => println!("The arbiter restarted the clock"),
+            Update::Adjusted { player, remaining } => println!(
+                "The arbiter gave {:?} {}:{:02} left",
+                player,
+                remaining.as_secs() / 60,
+                remaining.as_secs() % 60
+            ),
+            Update::Left(player) => println!("{:?} lost their connection", player),
+            Update::Back(player) => println!("{:?} is back", player),
+            Update::Takeback(player) if Some(player) == client.player() => {
+                println!("Waiting for the others to answer your takeback")
+            }
+            Update::Takeback(player) => {
+                let text = format!(
+                    "{:?} asks to take back their last turn. Accept? (y/n) ",
+                    player
+                );
+                let accept = match prompt(&text)? {
+                    Some(line) => line.eq_ignore_ascii_case("y"),
+                    None => return Ok(()),
+                };
+                client.answer_takeback(accept)?;
+            }
+            Update::Accepted(player) => println!("{:?} accepted the takeback", player),
+            Update::Declined(player) => println!("{:?} declined the takeback", player),
+            Update::TookBack(player) => println!("\n{:?} took back their last turn", player),
+            Update::Over(None) => {
+                println!("\nThe game is a draw.");
+                break;
+            }
+            Update::Over(Some(standings)) => {
+                println!("\nThe game is over!");
+                for (i, player) in standings.iter().enumerate() {
+                    println!("{}. {:?}", i + 1, player);
+                }
+                break;
+            }
+            Update::Error(reason) if client.player().is_none() => return Err(reason.into()),
+            Update::Error(reason) => println!("{}", reason),
+        }
+        if !ask || !client.is_my_turn() {
+            continue;
+        }
+        loop {
+            let text = format!(
+                "Your turn, {:?} (or resign, or takeback): ",
+                client.player().unwrap()
+            );
+            let line = match prompt(&text)? {
+                Some(line) => line,
+                None => return Ok(()),
+            };
+            if line == "resign" {
+                client.resign()?;
+                break;
+            }
+            if line == "takeback" {
+                client.request_takeback()?;
+                break;
+            }
+            match line.parse::<Turn>() {
+                Ok(turn) => {
+                    client.submit_turn(&turn)?;
+                    break;
+                }
+                Err(e) => println!("{}", e),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_cell(text: &str) -> Option<Cell> {
+    let player = match text.to_lowercase().as_str() {
+        "head" => Player::Head,
+        "tail" => Player::Tail,
+        "lefthand" => Player::LeftHand,
+        "righthand" => Player::RightHand,
+        "leftfoot" => Player::LeftFoot,
+        "rightfoot" => Player::RightFoot,
+        "empty" => return Some(Cell::Empty),
+        _ => return None,
+    };
+    Some(Cell::Occupied(player))
+}
+
+/// Compose a position by hand. Commands are read one per line:
+/// `place <row>/<column> <piece>`, `remove <row>/<column>`, `clear`,
+/// `reset` (to the starting setup), `show`, `validate`, `save <path>`,
+/// `load <path>`, `position <name>`, `tikz <path>`, `play`, and `quit`.
+/// After `play`, this gives back the position to start a game from.
+fn edit(config: Config) -> Result<Option<Board>, Box<dyn std::error::Error>> {
+    let mut board = Board::empty(config.clone());
+    draw(board.render(None));
+
+    while let Some(line) = prompt("edit> ")? {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        match words.as_slice() {
+            ["place", point, piece] => match (point.parse::<Point>().ok(), parse_cell(piece)) {
+                (Some(point), Some(cell)) => match board.set_piece(point, cell) {
+                    Ok(()) => draw(board.render(None)),
+                    Err(e) => println!("Cannot place piece: {}", e),
+                },
+                _ => println!("Usage: place <row>/<column> <piece>"),
+            },
+            ["remove", point] => match point.parse::<Point>().ok() {
+                Some(point) => match board.set_piece(point, Cell::Empty) {
+                    Ok(()) => draw(board.render(None)),
+                    Err(e) => println!("Cannot remove piece: {}", e),
+                },
+                None => println!("Usage: remove <row>/<column>"),
+            },
+            ["clear"] => {
+                board.clear();
+                draw(board.render(None));
+            }
+            ["reset"] => {
+                board = Board::new(config.clone());
+                draw(board.render(None));
+            }
+            ["show"] => draw(board.render(None)),
+            ["validate"] => match board.validate_setup() {
+                Ok(()) => println!("The setup is valid"),
+                Err(e) => println!("The setup is invalid: {}", e),
+            },
+            ["save", path] => match board.validate_setup() {
+                Ok(()) => match std::fs::write(path, board.serialize().join("\n") + "\n") {
+                    Ok(()) => println!("Saved to {}", path),
+                    Err(e) => println!("Cannot save to {}: {}", path, e),
+                },
+                Err(e) => println!("Refusing to save: {}", e),
+            },
+            ["load", path] => match std::fs::read_to_string(path) {
+                Ok(text) => {
+                    let lines: Vec<&str> = text.lines().collect();
+                    match Board::from_ascii(&lines, &config) {
+                        Ok(loaded) => {
+                            board = loaded;
+                            draw(board.render(None));
+                        }
+                        Err(e) => println!("Cannot load {}: {}", path, e),
+                    }
+                }
+                Err(e) => println!("Cannot load {}: {}", path, e),
+            },
+            ["position", name] => match positions::by_name(name) {
+                Some(loaded) => {
+                    board = loaded;
+                    draw(board.render(None));
+                }
+                None => println!("Known positions: {}", positions::NAMES.join(", ")),
+            },
+            ["tikz", path] => match std::fs::write(path, tikz::export(&board)) {
+                Ok(()) => println!("Exported to {}", path),
+                Err(e) => println!("Cannot export to {}: {}", path, e),
+            },
+            #[cfg(feature = "image")]
+            ["png", path] => match board.render_png(path, Default::default()) {
+                Ok(()) => println!("Exported to {}", path),
+                Err(e) => println!("Cannot export to {}: {}", path, e),
+            },
+            ["play"] => match board.validate_setup() {
+                Ok(()) => return Ok(Some(board)),
+                Err(e) => println!("Refusing to play: {}", e),
+            },
+            ["quit"] => break,
+            [] => {}
+            _ => println!(concat!(
+                "Commands: place, remove, clear, reset, show, validate, save, load,\n",
+                "position, tikz, play, quit"
+            )),
+        }
+    }
+    Ok(None)
+}
+
+/// How much players sharing a terminal get to see of each other's turns.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Privacy {
+    Shared,
+    /// The screen is cleared between turns.
+    Private,
+    /// Like `Private`, but the scrollback is cleared too and the last turn
+    /// is not highlighted, so nobody can look back at the jump chain that
+    /// the previous player planned out.
+    Hidden,
+}
+
+/// Local game where several people share one terminal. Unless `privacy`
+/// is `Shared`, the screen is cleared between turns and the next player
+/// has to confirm that they have taken over. Each player can type `confirm` to toggle
+/// whether their turns are previewed before being applied. With `coaching`,
+/// rejected turns are explained and legal alternatives are suggested.
+/// With `events`, every turn, undo, resignation, timeout, change to the
+/// clock, and the result is also written as NDJSON.
+/// Typing `save <path>` writes the game to a file that can be resumed,
+/// `hint` suggests a turn, `undo` takes back the last turn, and `resign`
+/// leaves the game.
+/// Seats with `Controller::Bot` are played by the computer, using `bot`.
+fn hotseat(
+    mut game: Game,
+    bot: &mut dyn Bot,
+    privacy: Privacy,
+    coaching: bool,
+    mut events: Option<EventLog<Box<dyn Write + Send>>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut confirming: Vec<Player> = vec![];
+    let started = Instant::now();
+    if let Some(events) = &mut events {
+        events.start(&game)?;
+    }
+
+    let is_bot = |game: &Game, player| {
+        game.seat_info(player)
+            .is_some_and(|x| x.controller == Controller::Bot)
+    };
+    let seat_name = |game: &Game, player| {
+        game.seat_info(player)
+            .map_or_else(|| format!("{:?}", player), |x| x.name.clone())
+    };
+
+    'outer: while let Some(piece) = game.current_player() {
+        let seat = seat_name(&game, piece);
+        let name = format!("{} ({})", seat, game.board.config().symbol(piece));
+        let thinking = Instant::now();
+        let (turn, comment) = if is_bot(&game, piece) {
+            match bot.choose(&game) {
+                Some(turn) => {
+                    println!("\n{} plays {}", name, turn);
+                    (turn, None)
+                }
+                None => {
+                    println!("\n{} has no legal turn", name);
+                    break 'outer;
+                }
+            }
+        } else {
+            if privacy != Privacy::Shared {
+                print!("\x1B[2J");
+                if privacy == Privacy::Hidden {
+                    print!("\x1B[3J");
+                }
+                print!("\x1B[H");
+                if prompt(&format!("Pass the terminal to {} and press Enter ", name))?.is_none() {
+                    break 'outer;
+                }
+            }
+
+            loop {
+                println!();
+                match privacy {
+                    Privacy::Hidden => draw(game.board.render(None)),
+                    _ => draw(game.render()),
+                }
+                let left = match game.clock().and_then(|x| x.remaining(piece)) {
+                    Some(left) => {
+                        format!(", {}:{:02} left", left.as_secs() / 60, left.as_secs() % 60)
+                    }
+                    None => String::new(),
+                };
+                let line = match prompt(&format!("\n{} to move{}: ", name, left))? {
+                    Some(line) => line,
+                    None => break 'outer,
+                };
+                let (line, comment) = match line.find('#') {
+                    Some(index) => (
+                        line[..index].trim().to_string(),
+                        Some(line[index + 1..].trim().to_string()),
+                    ),
+                    None => (line, None),
+                };
+                if let Some(path) = line.strip_prefix("save ") {
+                    match game.save(path.trim()) {
+                        Ok(()) => println!("Saved to {}", path.trim()),
+                        Err(e) => println!("Cannot save: {}", e),
+                    }
+                    continue;
+                }
+                match line.as_str() {
+                    "" => continue,
+                    "quit" => break 'outer,
+                    "undo" => {
+                        // Take back the bots' turns too, so that a person
+                        // gets to move again.
+                        let mut undone = 0;
+                        while let Some(record) = game.undo() {
+                            if let Some(events) = &mut events {
+                                events.undo(&record)?;
+                            }
+                            undone += 1;
+                            match game.current_player() {
+                                Some(x) if is_bot(&game, x) => {}
+                                _ => break,
+                            }
+                        }
+                        if undone == 0 {
+                            println!("There is nothing to undo");
+                        }
+                        continue 'outer;
+                    }
+                    "resign" => {
+                        game.resign(piece);
+                        println!("{} resigned", name);
+                        if let Some(events) = &mut events {
+                            events.resign(piece)?;
+                        }
+                        continue 'outer;
+                    }
+                    "hint" => {
+                        match game.suggest_turn(piece) {
+                            Some(turn) => println!("You could try {}", turn),
+                            None => println!("There is no legal turn"),
+                        }
+                        continue;
+                    }
+                    "confirm" => {
+                        if confirming.contains(&piece) {
+                            confirming.retain(|x| *x != piece);
+                            println!("Turns will be applied immediately");
+                        } else {
+                            confirming.push(piece);
+                            println!("Turns will be previewed first");
+                        }
+                        continue;
+                    }
+                    _ => {}
+                }
+                let turn = match line.parse::<Turn>() {
+                    Ok(turn) => turn,
+                    Err(e) => {
+                        println!("{} Try something like 4/10-5/11 or 3/11>5/13.", e);
+                        continue;
+                    }
+                };
+                if let Err(e) = game.board.try_turn(&turn, piece) {
+                    let advice = if coaching {
+                        coach::advise(&game.board, &turn, piece)
+                    } else {
+                        None
+                    };
+                    match advice {
+                        Some(advice) => {
+                            println!("Illegal turn: {}", advice.rule);
+                            if !advice.suggestions.is_empty() {
+                                let suggestions: Vec<String> =
+                                    advice.suggestions.iter().map(|x| x.to_string()).collect();
+                                println!("You could try: {}", suggestions.join(", "));
+                            }
+                        }
+                        _ => {
+                            println!("Illegal turn: {}", e);
+                            // Show where a longer chain goes wrong.
+                            if turn.points().len() > 2 {
+                                let analysis = game.board.explain_turn(&turn.points(), piece);
+                                for segment in analysis.segments {
+                                    println!("  {}", segment);
+                                }
+                            }
+                        }
+                    }
+                    continue;
+                }
+                if confirming.contains(&piece) {
+                    let mut preview = game.board.clone();
+                    preview.take_turn(&turn, piece)?;
+                    draw(preview.render(None));
+                    if prompt("Play this turn? [y/N] ")?.as_deref() != Some("y") {
+                        continue;
+                    }
+                }
+                break (turn, comment);
+            }
+        };
+        // Without a clock, this only records how long the turn took.
+        if let Err(e) = game.play_timed_turn(&turn, thinking.elapsed()) {
+            if e != GameError::TimeExpired {
+                return Err(e.into());
+            }
+            println!("\n{} ran out of time", name);
+            if let Some(events) = &mut events {
+                events.timeout(piece)?;
+            }
+            continue;
+        }
+        game.set_turn_comment(comment);
+        if is_bot(&game, piece) {
+            if let Score::Advantage(score) = analysis::evaluate(&game.board, piece) {
+                game.set_turn_evaluation(Some(score));
+            }
+        }
+        if let Some(events) = &mut events {
+            events.turn(&game, started.elapsed())?;
+            events.clock(&game)?;
+        }
+
+        if game.finished().last() == Some(&piece) {
+            match game.board.config().variant() {
+                Variant::Standard => println!("\n{} has finished!", name),
+                Variant::Misere => println!("\n{} has finished and is out!", name),
+            }
+            if let Some(events) = &mut events {
+                events.finished(piece)?;
+            }
+        }
+    }
+
+    println!();
+    draw(game.render());
+    println!("\nThe game is over!");
+    if let GameState::Drawn(reason) = game.state() {
+        println!("It is a draw, because {}.", reason);
+    }
+    let decided = !game.finished().is_empty() || !game.eliminated().is_empty();
+    let standings = if game.state() == GameState::InProgress || !decided {
+        game.finished().to_vec()
+    } else {
+        game.standings()
+    };
+    for (place, piece) in standings.iter().enumerate() {
+        println!("{}. {}", place + 1, seat_name(&game, *piece));
+    }
+    if let Some(events) = &mut events {
+        events.result(&standings)?;
+    }
+    println!("\nTurns:");
+    for line in turn_log::format(&turn_log::from_game(&game)) {
+        println!("{}", line);
+    }
     Ok(())
 }
+
+/// Apply a transcript from stdin, turn by turn, as it arrives.
+fn import(config: Config) -> Result<(), Box<dyn std::error::Error>> {
+    let seats = config.layout().players();
+    let mut importer = Importer::new(Board::new(config), seats);
+    let stdin = io::stdin();
+    for line in stdin.lock().lines() {
+        match importer.feed(&line?) {
+            Ok(Some((player, turn))) => println!("{:?} played {}", player, turn),
+            Ok(None) => {}
+            Err(e) => {
+                eprintln!("Import failed at {}", e);
+                std::process::exit(1);
+            }
+        }
+    }
+    draw(importer.board().render(None));
+    println!("Imported {} turns", importer.turns().len());
+    Ok(())
+}
+
+fn tournament(config: Config, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let value = |flag: &str| {
+        args.iter()
+            .position(|x| x == flag)
+            .map(|i| args.get(i + 1).ok_or(format!("{} needs a value", flag)))
+            .transpose()
+    };
+    let names = value("--entrants")?.map_or("random,greedy,easy,medium", |x| x.as_str());
+    let mut tournament = Tournament::new(config);
+    for name in names.split(',') {
+        tournament = match name {
+            "random" => tournament.with_entrant(name, || Box::new(RandomBot)),
+            "greedy" => tournament.with_entrant(name, || Box::new(GreedyBot)),
+            "easy" => tournament.with_entrant(name, || {
+                Box::new(AlphaBeta::with_difficulty(Difficulty::Easy))
+            }),
+            "medium" => tournament.with_entrant(name, || {
+                Box::new(AlphaBeta::with_difficulty(Difficulty::Medium))
+            }),
+            "hard" => tournament.with_entrant(name, || {
+                Box::new(AlphaBeta::with_difficulty(Difficulty::Hard))
+            }),
+            "mcts" => tournament.with_entrant(name, || Box::new(Mcts::default())),
+            _ => {
+                return Err(format!(
+                    "Unknown entrant {}; choose from random, greedy, easy, medium, hard, and mcts",
+                    name
+                )
+                .into())
+            }
+        };
+    }
+    if let Some(games) = value("--games")? {
+        tournament = tournament.with_games(games.parse().map_err(|_| "--games must be a number")?);
+    }
+    if let Some(rounds) = value("--swiss")? {
+        let rounds = rounds
+            .parse()
+            .map_err(|_| "--swiss must be a number of rounds")?;
+        tournament = tournament.with_format(Format::Swiss { rounds });
+    }
+
+    let quiet = args.iter().any(|x| x == "--csv" || x == "--json");
+    let names = names.split(',').collect::<Vec<_>>();
+    let report = tournament.run(|game| {
+        if !quiet {
+            let result = match game.winner {
+                Some(winner) => format!("{} wins", names[winner]),
+                None => String::from("draw"),
+            };
+            println!(
+                "{} vs {}: {} after {} turns",
+                names[game.first], names[game.second], result, game.turns
+            );
+        }
+    })?;
+
+    if args.iter().any(|x| x == "--csv") {
+        print!("{}", report.to_csv());
+    } else if args.iter().any(|x| x == "--json") {
+        println!("{}", report.to_json());
+    } else {
+        println!();
+        for (place, x) in report.standings.iter().enumerate() {
+            println!(
+                "{}. {} with {} points: {} won, {} drawn, {} lost, {:.1} turns per game",
+                place + 1,
+                x.name,
+                x.score,
+                x.wins,
+                x.draws,
+                x.losses,
+                x.average_turns
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Check a collection of puzzles, as described in `stelsalto::puzzle`,
+/// failing if any of them is unsound.
+fn solve_puzzles(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    if args
+        .iter()
+        .skip_while(|x| *x != "puzzle")
+        .nth(1)
+        .map(|x| x.as_str())
+        != Some("solve")
+    {
+        return Err("Use puzzle solve --file <path>".into());
+    }
+    let path = match args.iter().position(|x| x == "--file") {
+        Some(i) => args.get(i + 1).ok_or("--file needs a path")?,
+        None => return Err("puzzle solve needs --file <path>".into()),
+    };
+    let max_turns = match args.iter().position(|x| x == "--max-turns") {
+        Some(i) => args
+            .get(i + 1)
+            .and_then(|x| x.parse().ok())
+            .ok_or("--max-turns must be a number")?,
+        None => 8,
+    };
+    let puzzles = puzzle::parse(&std::fs::read_to_string(path)?)?;
+    let mut unsound = 0;
+    for (index, puzzle) in puzzles.iter().enumerate() {
+        let name = puzzle
+            .name
+            .clone()
+            .unwrap_or_else(|| format!("Puzzle {}", index + 1));
+        let report = puzzle.check(max_turns);
+        match report.optimal {
+            Some(turns) => println!("{}: {} turns at best", name, turns),
+            None => println!("{}: no solution in {} turns", name, max_turns),
+        }
+        for problem in &report.problems {
+            match problem {
+                Problem::IllegalTurn(turn, error) => {
+                    println!("  Turn {} of the solution is illegal: {}", turn + 1, error)
+                }
+                Problem::Unfinished => println!("  The solution does not finish"),
+                Problem::Shorter(line) => {
+                    let turns: Vec<String> = line.iter().map(|x| x.to_string()).collect();
+                    println!("  Shorter solution: {}", turns.join(" "))
+                }
+                Problem::NoSolution => {}
+            }
+        }
+        if !report.is_sound() {
+            unsound += 1;
+        }
+    }
+    if unsound > 0 {
+        return Err(format!("{} of {} puzzles are unsound", unsound, puzzles.len()).into());
+    }
+    println!("All {} puzzles are sound", puzzles.len());
+    Ok(())
+}
+
+/// Let computer players play each other and write what they did for
+/// training evaluators, as described in `stelsalto::training`.
+fn self_play(config: Config, args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let games = match args.iter().position(|x| x == "--games") {
+        Some(i) => args
+            .get(i + 1)
+            .and_then(|x| x.parse().ok())
+            .ok_or("--games must be a number")?,
+        None => 10,
+    };
+    let mut out: Box<dyn Write> = match args.iter().position(|x| x == "--data") {
+        Some(i) => Box::new(std::fs::File::create(
+            args.get(i + 1).ok_or("--data needs a path")?,
+        )?),
+        None => Box::new(io::stdout()),
+    };
+    for number in 0..games {
+        let mut config = config.clone();
+        if let Some(seed) = config.seed() {
+            config = config.with_seed(seed.wrapping_mul(31).wrapping_add(number));
+        }
+        let seats = config.layout().players();
+        let mut bots: Vec<Box<dyn Bot>> = seats
+            .iter()
+            .map(|_| Box::new(Mcts::new(200)) as Box<dyn Bot>)
+            .collect();
+        let mut game = Game::new(Board::new(config), seats);
+        let samples = training::self_play(&mut game, &mut bots, 1000)?;
+        training::write_samples(&samples, &mut out)?;
+        eprintln!("Game {} of {}: {} turns", number + 1, games, samples.len());
+    }
+    Ok(())
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let quick = args.iter().any(|x| x == "--quick");
+    let mut config = if quick {
+        Config::quick()
+    } else {
+        Config::default()
+    };
+    if let Some(i) = args.iter().position(|x| x == "--lines") {
+        let lines = args
+            .get(i + 1)
+            .and_then(|x| x.parse().ok())
+            .filter(|x| Config::new(*x).is_ok())
+            .ok_or_else(|| format!("--lines must be from 1 to {}", MAX_PLAYER_LINES))?;
+        config = config.with_player_lines(lines);
+    }
+    if args.iter().any(|x| x == "--misere") {
+        config = config.with_variant(Variant::Misere);
+    }
+    if args.iter().any(|x| x == "--captures") {
+        config = config.with_captures(true);
+    }
+    if args.iter().any(|x| x == "--revisits") {
+        config = config.with_revisits(true);
+    }
+    if args.iter().any(|x| x == "--eliminate") {
+        config = config
+            .with_captures(true)
+            .with_win_condition(WinCondition::Elimination);
+    }
+    if let Some(i) = args.iter().position(|x| x == "--goal") {
+        let rule = match args.get(i + 1).map(|x| x.as_str()) {
+            Some("own") => GoalRule::OwnPieces,
+            Some("blockers") => GoalRule::HomeBlockers,
+            Some("occupied") => GoalRule::AnyOccupant,
+            _ => return Err("--goal must be own, blockers, or occupied".into()),
+        };
+        config = config.with_goal_rule(rule);
+    }
+    if let Some(i) = args.iter().position(|x| x == "--symbols") {
+        let theme = match args.get(i + 1).map(|x| x.as_str()) {
+            Some("ascii") => SymbolTheme::Ascii,
+            Some("unicode") => SymbolTheme::Unicode,
+            Some("emoji") => SymbolTheme::Emoji,
+            _ => return Err("--symbols must be ascii, unicode, or emoji".into()),
+        };
+        config = config.with_symbol_theme(theme);
+    }
+    if let Some(i) = args.iter().position(|x| x == "--seed") {
+        let seed = args
+            .get(i + 1)
+            .and_then(|x| x.parse().ok())
+            .ok_or("--seed must be a number")?;
+        config = config.with_seed(seed);
+    }
+    if args.iter().any(|x| x == "--labels") {
+        config = config.with_labels(true);
+    }
+    if args.iter().any(|x| x == "--color") {
+        config = config.with_color_mode(ColorMode::Ansi);
+    }
+
+    let layout = match args.iter().position(|x| x == "--players") {
+        Some(i) => args
+            .get(i + 1)
+            .and_then(|x| x.parse().ok())
+            .and_then(StartLayout::for_players)
+            .ok_or("--players must be 2, 3, 4, or 6")?,
+        None if args.iter().any(|x| x == "edit") => StartLayout::SixPlayers,
+        None => StartLayout::TwoPlayers,
+    };
+    config = config.with_layout(layout);
+    let time: Option<TimeControl> = match args.iter().position(|x| x == "--time") {
+        Some(i) => Some(
+            args.get(i + 1)
+                .and_then(|x| x.parse().ok())
+                .ok_or("--time must be blitz, rapid, classical, or minutes+increment like 5+3")?,
+        ),
+        None => None,
+    };
+    // Without these, players could shuffle back and forth forever.
+    config = config
+        .with_repetition_limit(3)
+        .with_progress_limit(50 * layout.players().len());
+
+    let composed = if args.iter().any(|x| x == "edit") {
+        match edit(config.clone())? {
+            Some(board) => Some(board),
+            None => return Ok(()),
+        }
+    } else {
+        None
+    };
+    if args.iter().any(|x| x == "import") {
+        return import(config);
+    }
+    if args.iter().any(|x| x == "engine") {
+        let stdin = io::stdin();
+        Engine::new(config).run(stdin.lock(), io::stdout())?;
+        return Ok(());
+    }
+    if args.iter().any(|x| x == "selfplay") {
+        return self_play(config, &args);
+    }
+    if args.iter().any(|x| x == "tournament") {
+        return tournament(config, &args);
+    }
+    if args.iter().any(|x| x == "puzzle") {
+        return solve_puzzles(&args);
+    }
+    let events = match args.iter().position(|x| x == "--log") {
+        Some(i) => {
+            let out: Box<dyn Write + Send> = match args.get(i + 1).map(|x| x.as_str()) {
+                Some("-") => Box::new(io::stdout()),
+                Some(path) => Box::new(
+                    std::fs::OpenOptions::new()
+                        .create(true)
+                        .append(true)
+                        .open(path)?,
+                ),
+                None => return Err("--log needs a path, or - for stdout".into()),
+            };
+            Some(EventLog::new(out))
+        }
+        None => None,
+    };
+    if args.iter().any(|x| x == "simul") {
+        #[cfg(feature = "net")]
+        {
+            let address = args
+                .iter()
+                .skip_while(|x| *x != "simul")
+                .nth(1)
+                .filter(|x| !x.starts_with("--"));
+            let boards = match args.iter().position(|x| x == "--boards") {
+                Some(i) => args
+                    .get(i + 1)
+                    .and_then(|x| x.parse().ok())
+                    .filter(|x| *x > 0)
+                    .ok_or("--boards must be a positive number")?,
+                None => 4,
+            };
+            let mut simul = Simul::new(config, boards, "Exhibitor");
+            if let Some(i) = args.iter().position(|x| x == "--think") {
+                let millis = args
+                    .get(i + 1)
+                    .and_then(|x| x.parse().ok())
+                    .ok_or("--think must be a number of milliseconds")?;
+                simul = simul.with_think_time(Duration::from_millis(millis));
+            }
+            if let Some(control) = time {
+                simul = simul.with_time_control(control);
+            }
+            let listener =
+                std::net::TcpListener::bind(address.map_or("0.0.0.0:7878", |x| x.as_str()))?;
+            println!(
+                "Waiting for opponents on {} with {} boards",
+                listener.local_addr()?,
+                boards
+            );
+            let simul = stelsalto::simul::serve(listener, simul)?;
+            for (index, host) in simul.boards().iter().enumerate() {
+                println!("\nBoard {}:", index + 1);
+                draw(host.game().render());
+            }
+            return Ok(());
+        }
+        #[cfg(not(feature = "net"))]
+        return Err("Playing over the network needs the net feature".into());
+    }
+    if args.iter().any(|x| x == "serve" || x == "connect") {
+        #[cfg(feature = "net")]
+        {
+            let name = match args.iter().position(|x| x == "--name") {
+                Some(i) => Some(args.get(i + 1).ok_or("--name needs a name")?.as_str()),
+                None => None,
+            };
+            let address = args
+                .iter()
+                .skip_while(|x| *x != "serve" && *x != "connect")
+                .nth(1)
+                .filter(|x| !x.starts_with("--"));
+            if args.iter().any(|x| x == "connect") {
+                let address = address.ok_or("connect needs an address like 192.168.0.2:7878")?;
+                return play_online(Client::connect(address.as_str())?, name.unwrap_or("Guest"));
+            }
+
+            let listener =
+                std::net::TcpListener::bind(address.map_or("0.0.0.0:7878", |x| x.as_str()))?;
+            let port = listener.local_addr()?.port();
+            println!("Waiting for players on {}", listener.local_addr()?);
+            let seats = config.layout().players();
+            let mut host = Host::new(Board::new(config), seats);
+            if let Some(control) = time {
+                host = host.with_time_control(control);
+            }
+            if let Some(i) = args.iter().position(|x| x == "--arbiter") {
+                let token = args.get(i + 1).ok_or("--arbiter needs a token")?;
+                host = host.with_arbiter(token);
+            }
+            if let Some(events) = events {
+                host = host.with_event_log(events);
+            }
+            for (i, _) in args.iter().enumerate().filter(|(_, x)| *x == "--webhook") {
+                let url = args.get(i + 1).ok_or("--webhook needs a URL")?;
+                let webhook = stelsalto::net::Webhook::new(url)?;
+                host = host.with_event_log(EventLog::new(Box::new(webhook)));
+            }
+            let server = std::thread::spawn(move || stelsalto::net::serve(listener, host));
+            if let Some(name) = name {
+                play_online(Client::connect(("127.0.0.1", port))?, name)?;
+            }
+            let host = server.join().expect("the server does not panic")?;
+            draw(host.game().render());
+            return Ok(());
+        }
+        #[cfg(not(feature = "net"))]
+        return Err("Playing over the network needs the net feature".into());
+    }
+    if args.iter().any(|x| x == "tui") {
+        #[cfg(feature = "tui")]
+        {
+            let seats = config.layout().players();
+            let game = stelsalto::tui::run(Game::new(Board::new(config), seats))?;
+            draw(game.render());
+            return Ok(());
+        }
+        #[cfg(not(feature = "tui"))]
+        return Err("The terminal interface needs the tui feature".into());
+    }
+    if args.iter().any(|x| x == "demo") {
+        if quick {
+            return Err("The demo game only supports the standard setup".into());
+        }
+
+        let mut game = Game::new(Board::default(), vec![Player::Head, Player::Tail]);
+        game.set_pacing(Pacing {
+            delay: Duration::from_millis(500),
+        });
+        let mut bots: Vec<Box<dyn Bot>> = vec![Box::new(GreedyBot), Box::new(RandomBot)];
+        game.play_bots(&mut bots, DEMO_TURNS, report)?;
+
+        let result = game.result();
+        println!("\nThe game is over!");
+        if let GameState::Drawn(reason) = result.state {
+            println!("It is a draw, because {}.", reason);
+        }
+        println!("It lasted {} turns", result.turns);
+        if !game.finished().is_empty() {
+            for (place, placing) in result.placings.iter().enumerate() {
+                println!(
+                    "{}. {:?} in {} turns",
+                    place + 1,
+                    placing.player,
+                    placing.turns
+                );
+            }
+        }
+        return Ok(());
+    }
+
+    let mut game = match args.iter().position(|x| x == "--resume") {
+        Some(i) => match args.get(i + 1) {
+            Some(path) => Game::load(path)?,
+            None => return Err("--resume needs the path of a saved game".into()),
+        },
+        None => match composed {
+            // Only players with pieces on the composed board take part.
+            Some(board) => {
+                let seats = config
+                    .layout()
+                    .players()
+                    .into_iter()
+                    .filter(|x| board.pieces_of(*x).next().is_some())
+                    .collect();
+                Game::new(board, seats)
+            }
+            None => {
+                let seats = config.layout().players();
+                Game::new(Board::new(config), seats)
+            }
+        },
+    };
+    if let Some(control) = time {
+        game.set_time_control(control);
+    }
+    if let Some(i) = args.iter().position(|x| x == "--names") {
+        let names = args.get(i + 1).ok_or("--names needs a list like Ada,Bob")?;
+        for (player, name) in game.seats().to_vec().into_iter().zip(names.split(',')) {
+            if let Some(info) = game.seat_info(player).cloned() {
+                game.set_seat_info(player, info.with_name(name.trim()));
+            }
+        }
+    }
+    if let Some(i) = args.iter().position(|x| x == "--bots") {
+        let bots = match args.get(i + 1).and_then(|x| x.parse::<usize>().ok()) {
+            Some(count) if count < game.playing().len() => {
+                game.playing()[game.playing().len() - count..].to_vec()
+            }
+            _ => return Err("--bots must be less than the number of players".into()),
+        };
+        for player in bots {
+            if let Some(info) = game.seat_info(player).cloned() {
+                game.set_seat_info(player, info.with_controller(Controller::Bot));
+            }
+        }
+    }
+    let difficulty = match args.iter().position(|x| x == "--difficulty") {
+        Some(i) => args
+            .get(i + 1)
+            .and_then(|x| x.parse().ok())
+            .ok_or("--difficulty must be easy, medium, or hard")?,
+        None => Difficulty::Medium,
+    };
+    #[cfg(feature = "parallel")]
+    let threads = match args.iter().position(|x| x == "--threads") {
+        Some(i) => args
+            .get(i + 1)
+            .and_then(|x| x.parse().ok())
+            .ok_or("--threads must be a number, or 0 for one per core")?,
+        None => 1,
+    };
+    #[cfg(not(feature = "parallel"))]
+    if args.iter().any(|x| x == "--threads") {
+        return Err("Searching on several threads needs the parallel feature".into());
+    }
+    let mut bot: Box<dyn Bot> = if args.iter().any(|x| x == "--mcts") {
+        let bot = Mcts::default().with_time_budget(Duration::from_secs(2));
+        #[cfg(feature = "parallel")]
+        let bot = bot.with_threads(threads);
+        Box::new(bot)
+    } else if args.iter().any(|x| x == "--network") {
+        #[cfg(feature = "nn")]
+        {
+            let path = args
+                .iter()
+                .skip_while(|x| *x != "--network")
+                .nth(1)
+                .ok_or("--network needs the path of a network")?;
+            let network = stelsalto::nn::Network::load_for(path, &game.board)?;
+            let bot = AlphaBeta::with_evaluator(1, network).with_budget(difficulty.budget());
+            #[cfg(feature = "parallel")]
+            let bot = bot.with_threads(threads);
+            Box::new(bot)
+        }
+        #[cfg(not(feature = "nn"))]
+        return Err("Loading a network needs the nn feature".into());
+    } else {
+        let mut bot = AlphaBeta::with_difficulty(difficulty);
+        if args.iter().any(|x| x == "--vary-openings") {
+            // A step less progress than the best still makes a fair opening.
+            bot = bot.with_opening_variety(OPENING_ROUNDS, 1);
+        }
+        #[cfg(feature = "parallel")]
+        let bot = bot.with_threads(threads);
+        Box::new(bot)
+    };
+    hotseat(
+        game,
+        bot.as_mut(),
+        if args.iter().any(|x| x == "--hide-turns") {
+            Privacy::Hidden
+        } else if args.iter().any(|x| x == "--private") {
+            Privacy::Private
+        } else {
+            Privacy::Shared
+        },
+        args.iter().any(|x| x == "--coach"),
+        events,
+    )
+}
@@ -0,0 +1,222 @@
+//! Machine-readable game log with one JSON object per line, for analytics
+//! pipelines and replayers. Every line has an `event` field, which is one of
+//! `start`, `turn`, `undo`, `finished`, `resign`, `timeout`, `clock`, or
+//! `result`. Lines are flushed as soon as they are written, so the log can
+//! be followed while the game is running.
+//!
+//! Turns are numbered as in `TurnRecord::number`. An `undo` line gives the
+//! number of the turn that was taken back, so a turn played after it reuses
+//! that number, the same as in `Game::history`.
+
+use crate::{Game, Player, TurnRecord};
+use std::fmt;
+use std::io::{self, Write};
+use std::time::Duration;
+
+fn players(pieces: &[Player]) -> String {
+    let names: Vec<String> = pieces.iter().map(|x| format!("\"{:?}\"", x)).collect();
+    format!("[{}]", names.join(","))
+}
+
+/// A JSON string, with quotes and control characters escaped.
+pub(crate) fn string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+pub struct EventLog<W: Write> {
+    out: W,
+}
+
+impl<W: Write> fmt::Debug for EventLog<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventLog").finish_non_exhaustive()
+    }
+}
+
+impl<W: Write> EventLog<W> {
+    pub fn new(out: W) -> Self {
+        Self { out }
+    }
+
+    fn write(&mut self, line: String) -> io::Result<()> {
+        writeln!(self.out, "{}", line)?;
+        self.out.flush()
+    }
+
+    /// Record the players still in a game, and who sits in each seat.
+    pub fn start(&mut self, game: &Game) -> io::Result<()> {
+        let seats: Vec<String> = game
+            .playing()
+            .iter()
+            .filter_map(|x| Some((x, game.seat_info(*x)?)))
+            .map(|(player, info)| {
+                format!(
+                    "{{\"player\":\"{:?}\",\"name\":{},\"rating\":{},\"controller\":\"{:?}\"}}",
+                    player,
+                    string(&info.name),
+                    info.rating.map_or(String::from("null"), |x| x.to_string()),
+                    info.controller
+                )
+            })
+            .collect();
+        self.write(format!(
+            "{{\"event\":\"start\",\"players\":{},\"seats\":[{}]}}",
+            players(game.playing()),
+            seats.join(",")
+        ))
+    }
+
+    /// Record the last turn in a game's history, with the time since the
+    /// start of the game.
+    pub fn turn(&mut self, game: &Game, elapsed: Duration) -> io::Result<()> {
+        let record = match game.history().last() {
+            Some(record) => record,
+            None => return Ok(()),
+        };
+        self.write(format!(
+            "{{\"event\":\"turn\",\"number\":{},\"player\":\"{:?}\",\"turn\":\"{}\",\"elapsed_ms\":{}}}",
+            record.number,
+            record.player,
+            record.turn,
+            elapsed.as_millis()
+        ))
+    }
+
+    /// Record a turn that was taken back, as given by `Game::undo`.
+    pub fn undo(&mut self, record: &TurnRecord) -> io::Result<()> {
+        self.write(format!(
+            "{{\"event\":\"undo\",\"number\":{},\"player\":\"{:?}\"}}",
+            record.number, record.player
+        ))
+    }
+
+    pub fn finished(&mut self, player: Player) -> io::Result<()> {
+        self.player_event("finished", player)
+    }
+
+    pub fn resign(&mut self, player: Player) -> io::Result<()> {
+        self.player_event("resign", player)
+    }
+
+    pub fn timeout(&mut self, player: Player) -> io::Result<()> {
+        self.player_event("timeout", player)
+    }
+
+    fn player_event(&mut self, event: &str, player: Player) -> io::Result<()> {
+        self.write(format!(
+            "{{\"event\":\"{}\",\"player\":\"{:?}\"}}",
+            event, player
+        ))
+    }
+
+    /// Record how much time everyone on the clock has left, if the game
+    /// has one.
+    pub fn clock(&mut self, game: &Game) -> io::Result<()> {
+        let clock = match game.clock() {
+            Some(clock) => clock,
+            None => return Ok(()),
+        };
+        let remaining: Vec<String> = game
+            .seats()
+            .iter()
+            .filter_map(|x| Some((x, clock.remaining(*x)?)))
+            .map(|(player, left)| format!("\"{:?}\":{}", player, left.as_millis()))
+            .collect();
+        self.write(format!(
+            "{{\"event\":\"clock\",\"paused\":{},\"remaining_ms\":{{{}}}}}",
+            clock.is_paused(),
+            remaining.join(",")
+        ))
+    }
+
+    /// Record the final standings, from best to worst.
+    pub fn result(&mut self, standings: &[Player]) -> io::Result<()> {
+        self.write(format!(
+            "{{\"event\":\"result\",\"standings\":{}}}",
+            players(standings)
+        ))
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::TimeControl;
+    use crate::{Board, Controller, SeatInfo};
+
+    #[test]
+    fn test_event_log() {
+        let mut game = Game::new(Board::default(), vec![Player::Head, Player::Tail]);
+        game.set_seat_info(
+            Player::Tail,
+            SeatInfo::new(Player::Tail)
+                .with_name("Ada \"the bot\"")
+                .with_rating(1500)
+                .with_controller(Controller::Bot),
+        );
+        let mut log = EventLog::new(vec![]);
+        log.start(&game).unwrap();
+        game.play_turn(&"3/11>5/13".parse().unwrap()).unwrap();
+        log.turn(&game, Duration::from_millis(1500)).unwrap();
+        log.finished(Player::Head).unwrap();
+        game.resign(Player::Tail);
+        log.resign(Player::Tail).unwrap();
+        log.result(&[Player::Head, Player::Tail]).unwrap();
+        assert_eq!(
+            String::from_utf8(log.into_inner()).unwrap(),
+            concat!(
+                "{\"event\":\"start\",\"players\":[\"Head\",\"Tail\"],\"seats\":[",
+                "{\"player\":\"Head\",\"name\":\"Head\",\"rating\":null,\"controller\":\"Human\"},",
+                "{\"player\":\"Tail\",\"name\":\"Ada \\\"the bot\\\"\",\"rating\":1500,\"controller\":\"Bot\"}]}\n",
+                "{\"event\":\"turn\",\"number\":1,\"player\":\"Head\",\"turn\":\"3/11>5/13\",\"elapsed_ms\":1500}\n",
+                "{\"event\":\"finished\",\"player\":\"Head\"}\n",
+                "{\"event\":\"resign\",\"player\":\"Tail\"}\n",
+                "{\"event\":\"result\",\"standings\":[\"Head\",\"Tail\"]}\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_undo_and_clock() {
+        let mut game = Game::new(Board::default(), vec![Player::Head, Player::Tail]);
+        game.set_time_control(TimeControl::Absolute(Duration::from_secs(60)));
+        let mut log = EventLog::new(vec![]);
+        game.play_timed_turn(&"4/10-5/11".parse().unwrap(), Duration::from_secs(5))
+            .unwrap();
+        log.turn(&game, Duration::from_secs(5)).unwrap();
+        log.clock(&game).unwrap();
+        game.play_turn(&"14/16-13/15".parse().unwrap()).unwrap();
+        log.turn(&game, Duration::from_secs(9)).unwrap();
+        let undone = game.undo().unwrap();
+        log.undo(&undone).unwrap();
+        game.play_turn(&"14/12-13/11".parse().unwrap()).unwrap();
+        log.turn(&game, Duration::from_secs(12)).unwrap();
+        game.pause_clock();
+        log.clock(&game).unwrap();
+        assert_eq!(
+            String::from_utf8(log.into_inner()).unwrap(),
+            concat!(
+                "{\"event\":\"turn\",\"number\":1,\"player\":\"Head\",\"turn\":\"4/10-5/11\",\"elapsed_ms\":5000}\n",
+                "{\"event\":\"clock\",\"paused\":false,\"remaining_ms\":{\"Head\":55000,\"Tail\":60000}}\n",
+                "{\"event\":\"turn\",\"number\":2,\"player\":\"Tail\",\"turn\":\"14/16-13/15\",\"elapsed_ms\":9000}\n",
+                "{\"event\":\"undo\",\"number\":2,\"player\":\"Tail\"}\n",
+                "{\"event\":\"turn\",\"number\":2,\"player\":\"Tail\",\"turn\":\"14/12-13/11\",\"elapsed_ms\":12000}\n",
+                "{\"event\":\"clock\",\"paused\":true,\"remaining_ms\":{\"Head\":55000,\"Tail\":60000}}\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,1505 @@
+//! Hosting games over TCP, built with the `net` feature.
+//!
+//! Clients send one command per line:
+//!
+//! * `join <name>` takes the next free seat. The reply is
+//!   `welcome <player> <token>`, where the token can be used to come back.
+//! * `rejoin <token>` takes a seat back after losing the connection.
+//! * `turn <turn>` plays a turn, such as `turn 4/10-5/11`, for the seat
+//!   that the client holds, as long as it is that seat's turn.
+//! * `resign` takes the client's seat out of the game.
+//! * `arbiter <token>` makes the client the arbiter, if the server was
+//!   given that token with `Host::with_arbiter`. The arbiter does not need
+//!   a seat, and can send `pause` and `resume` to stop and restart the
+//!   clock, or `adjust <player> <seconds>` to give a player more time, or
+//!   less with a negative number.
+//! * `takeback` asks to take back the client's last turn, along with every
+//!   turn since. Everyone else still playing is asked with
+//!   `takeback <player>`, and answers with `accept` or `decline`.
+//!
+//! The server tells everyone `joined <player> <name>` as seats fill up,
+//! then `start` and `position <fen>` once every seat is taken. After each
+//! turn, it sends `played <player> <turn>`, `finished <player>` for anyone
+//! who finished with it, and the new `position <fen>`. Players who leave
+//! or rejoin are announced with `left <player>` and `back <player>`, and
+//! the game waits for them if it is their turn. Whoever rejoins is sent
+//! the seats and position again. It ends with `over <player> ...` listing
+//! everyone from first to last, including those who resigned, or
+//! `over draw`. Each answer to a takeback is announced with
+//! `accepted <player>` or `declined <player>`. Once everyone has accepted,
+//! the server sends `tookback <player>` and the rewound `position <fen>`.
+//! A request that is still waiting when a turn is played or someone
+//! resigns is dropped. With a time control, the server times each turn
+//! from when it sent the position, and takes out anyone who runs out with
+//! `timeout <player>` and the new position. The arbiter's changes are
+//! announced with `paused`, `resumed`, and `adjusted <player> <seconds>`,
+//! giving the player's time left in whole seconds. No turns can be played
+//! while the clock is paused. Commands that cannot be carried out are
+//! answered with `error <reason>`, only to the client that sent them.
+//!
+//! `Host` keeps track of the game and the seats without doing any
+//! networking itself, and `serve` runs one over a `TcpListener`. On the
+//! other end, `Client` sends the commands and reads back each `Update`.
+//! With `Host::with_event_log`, everything that happens in the game is also
+//! written as NDJSON, as described in `ndjson`, so that other programs can
+//! follow the game without speaking this protocol. A `Webhook` can be used
+//! as the log to POST each line to a URL instead, such as for a chat bot or
+//! a dashboard.
+
+use crate::clock::TimeControl;
+use crate::ndjson::EventLog;
+use crate::notation::player_named;
+use crate::{Board, Game, GameError, GameState, Player, Turn};
+use rand::Rng;
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often `serve` checks whether the player to move has run out of
+/// time while nothing else is happening.
+pub(crate) const CLOCK_CHECK: Duration = Duration::from_millis(100);
+
+/// How long a `Webhook` waits for the other end before giving up on a line.
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Number that `serve` gives each connection, in the order they arrive.
+pub type ClientId = usize;
+
+/// A line for the server to send.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Message {
+    /// The client to send it to, or `None` for everyone.
+    pub to: Option<ClientId>,
+    pub text: String,
+}
+
+impl Message {
+    pub(crate) fn to(client: ClientId, text: impl Into<String>) -> Self {
+        Self {
+            to: Some(client),
+            text: text.into(),
+        }
+    }
+
+    fn all(text: impl Into<String>) -> Self {
+        Self {
+            to: None,
+            text: text.into(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Holder {
+    name: String,
+    token: String,
+    /// The connection that controls the seat, unless it was lost.
+    client: Option<ClientId>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Seat {
+    player: Player,
+    holder: Option<Holder>,
+}
+
+/// A takeback that is waiting for the other players to answer.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Takeback {
+    player: Player,
+    accepted: Vec<Player>,
+}
+
+/// Where `Host` writes the game's events.
+pub type HostLog = EventLog<Box<dyn Write + Send>>;
+
+#[derive(Debug)]
+pub struct Host {
+    game: Game,
+    seats: Vec<Seat>,
+    takeback: Option<Takeback>,
+    /// When the player to move was sent the position, moved on by however
+    /// long the clock was paused for since.
+    turn_started: Option<Instant>,
+    /// When the clock was paused, if it is.
+    paused_at: Option<Instant>,
+    /// What a client has to send with `arbiter` to become one.
+    arbiter_token: Option<String>,
+    arbiters: Vec<ClientId>,
+    /// When every seat was taken, for the times in `logs`.
+    started_at: Option<Instant>,
+    logs: Vec<HostLog>,
+}
+
+impl Host {
+    pub fn new(board: Board, players: Vec<Player>) -> Self {
+        let seats = players
+            .iter()
+            .map(|x| Seat {
+                player: *x,
+                holder: None,
+            })
+            .collect();
+        Self {
+            game: Game::new(board, players),
+            seats,
+            takeback: None,
+            turn_started: None,
+            paused_at: None,
+            arbiter_token: None,
+            arbiters: vec![],
+            started_at: None,
+            logs: vec![],
+        }
+    }
+
+    /// Also write every game event to `log`. There can be several logs.
+    pub fn with_event_log(mut self, log: HostLog) -> Self {
+        self.logs.push(log);
+        self
+    }
+
+    /// Let whoever sends `arbiter <token>` pause and adjust the clock.
+    pub fn with_arbiter(mut self, token: &str) -> Self {
+        self.arbiter_token = Some(token.to_string());
+        self
+    }
+
+    /// Put every seat on a clock, with each turn timed by the server.
+    pub fn with_time_control(mut self, control: TimeControl) -> Self {
+        self.game.set_time_control(control);
+        self
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// Whether every seat has been taken, which starts the game.
+    pub fn is_started(&self) -> bool {
+        self.seats.iter().all(|x| x.holder.is_some())
+    }
+
+    /// Whether the game is over and nobody is connected anymore.
+    pub fn is_done(&self) -> bool {
+        self.game.state() != GameState::InProgress
+            && self
+                .seats
+                .iter()
+                .all(|x| x.holder.as_ref().is_none_or(|x| x.client.is_none()))
+    }
+
+    /// The player whose seat a client holds.
+    pub fn player_of(&self, client: ClientId) -> Option<Player> {
+        self.seats
+            .iter()
+            .find(|x| x.holder.as_ref().is_some_and(|x| x.client == Some(client)))
+            .map(|x| x.player)
+    }
+
+    /// Carry out one command from a client and give back what to send.
+    pub fn handle(&mut self, client: ClientId, line: &str) -> Vec<Message> {
+        let line = line.trim();
+        let (command, rest) = match line.split_once(char::is_whitespace) {
+            Some((command, rest)) => (command, rest.trim()),
+            None => (line, ""),
+        };
+        let result = match command {
+            "join" => self.join(client, rest),
+            "rejoin" => self.rejoin(client, rest),
+            "turn" => self.turn(client, rest),
+            "resign" => self.resign(client),
+            "takeback" => self.request_takeback(client),
+            "accept" => self.answer_takeback(client, true),
+            "decline" => self.answer_takeback(client, false),
+            "arbiter" => self.claim_arbiter(client, rest),
+            "pause" => self.pause(client, true),
+            "resume" => self.pause(client, false),
+            "adjust" => self.adjust(client, rest),
+            _ => Err(format!("Unknown command: {}", command)),
+        };
+        result.unwrap_or_else(|e| vec![Message::to(client, format!("error {}", e))])
+    }
+
+    /// Free up a client's seat for `rejoin` when its connection is lost.
+    pub fn disconnect(&mut self, client: ClientId) -> Vec<Message> {
+        self.arbiters.retain(|x| *x != client);
+        let player = match self.player_of(client) {
+            Some(player) => player,
+            None => return vec![],
+        };
+        if let Some(holder) = self.holder_mut(player) {
+            holder.client = None;
+        }
+        vec![Message::all(format!("left {:?}", player))]
+    }
+
+    fn holder_mut(&mut self, player: Player) -> Option<&mut Holder> {
+        self.seats
+            .iter_mut()
+            .find(|x| x.player == player)
+            .and_then(|x| x.holder.as_mut())
+    }
+
+    fn join(&mut self, client: ClientId, name: &str) -> Result<Vec<Message>, String> {
+        if let Some(player) = self.player_of(client) {
+            return Err(format!("Already seated as {:?}", player));
+        }
+        if name.is_empty() {
+            return Err(String::from("Expected a name"));
+        }
+        let seat = self
+            .seats
+            .iter_mut()
+            .find(|x| x.holder.is_none())
+            .ok_or("The game is full")?;
+        let token = format!("{:016x}", rand::thread_rng().gen::<u64>());
+        seat.holder = Some(Holder {
+            name: name.to_string(),
+            token: token.clone(),
+            client: Some(client),
+        });
+        let player = seat.player;
+
+        let mut messages = vec![Message::to(
+            client,
+            format!("welcome {:?} {}", player, token),
+        )];
+        messages.extend(
+            self.joined()
+                .into_iter()
+                .filter(|x| !x.starts_with(&format!("joined {:?} ", player)))
+                .map(|x| Message::to(client, x)),
+        );
+        messages.push(Message::all(format!("joined {:?} {}", player, name)));
+        if self.is_started() {
+            messages.push(Message::all("start"));
+            messages.push(Message::all(format!("position {}", self.game.to_fen())));
+            self.turn_started = Some(Instant::now());
+            self.started_at = self.turn_started;
+            self.log(|log, game| {
+                log.start(game)?;
+                log.clock(game)
+            });
+        }
+        Ok(messages)
+    }
+
+    fn rejoin(&mut self, client: ClientId, token: &str) -> Result<Vec<Message>, String> {
+        if let Some(player) = self.player_of(client) {
+            return Err(format!("Already seated as {:?}", player));
+        }
+        let seat = self
+            .seats
+            .iter_mut()
+            .find(|x| x.holder.as_ref().is_some_and(|x| x.token == token))
+            .ok_or("Unknown token")?;
+        let player = seat.player;
+        if let Some(holder) = seat.holder.as_mut() {
+            holder.client = Some(client);
+        }
+
+        let mut messages = vec![Message::to(
+            client,
+            format!("welcome {:?} {}", player, token),
+        )];
+        messages.extend(self.joined().into_iter().map(|x| Message::to(client, x)));
+        if self.is_started() {
+            messages.push(Message::to(client, "start"));
+            messages.push(Message::to(
+                client,
+                format!("position {}", self.game.to_fen()),
+            ));
+        }
+        if let Some(over) = self.over() {
+            messages.push(Message::to(client, over));
+        }
+        messages.push(Message::all(format!("back {:?}", player)));
+        Ok(messages)
+    }
+
+    fn turn(&mut self, client: ClientId, text: &str) -> Result<Vec<Message>, String> {
+        let player = self.player_of(client).ok_or("Join the game first")?;
+        if !self.is_started() {
+            return Err(String::from("The game has not started"));
+        }
+        if self.paused_at.is_some() {
+            return Err(String::from("The clock is paused"));
+        }
+        let turn: Turn = text.parse().map_err(|e| format!("{}", e))?;
+        let finished = self.game.finished().len();
+        let spent = self.turn_started.map(|x| x.elapsed());
+        let result = match (self.game.clock(), spent) {
+            (Some(_), Some(spent)) if self.game.current_player() == Some(player) => {
+                self.game.play_timed_turn(&turn, spent)
+            }
+            _ => self.game.play_turn_as(player, &turn),
+        };
+        match result {
+            Ok(()) => {}
+            Err(GameError::TimeExpired) => return Ok(self.timed_out(player)),
+            Err(e) => return Err(e.to_string()),
+        }
+        self.takeback = None;
+
+        let elapsed = self.started_at.map(|x| x.elapsed()).unwrap_or_default();
+        self.log(|log, game| {
+            log.turn(game, elapsed)?;
+            for player in &game.finished()[finished..] {
+                log.finished(*player)?;
+            }
+            log.clock(game)
+        });
+        let mut messages = vec![Message::all(format!("played {:?} {}", player, turn))];
+        for player in &self.game.finished()[finished..] {
+            messages.push(Message::all(format!("finished {:?}", player)));
+        }
+        messages.extend(self.after_change());
+        Ok(messages)
+    }
+
+    fn resign(&mut self, client: ClientId) -> Result<Vec<Message>, String> {
+        let player = self.player_of(client).ok_or("Join the game first")?;
+        if !self.is_started() {
+            return Err(String::from("The game has not started"));
+        }
+        if !self.game.resign(player) {
+            return Err(format!("{:?} is not in the game", player));
+        }
+        self.takeback = None;
+        self.log(|log, _| log.resign(player));
+        let mut messages = vec![Message::all(format!("resigned {:?}", player))];
+        messages.extend(self.after_change());
+        Ok(messages)
+    }
+
+    fn request_takeback(&mut self, client: ClientId) -> Result<Vec<Message>, String> {
+        let player = self.playing_player(client)?;
+        if self.takeback.is_some() {
+            return Err(String::from("A takeback has already been asked for"));
+        }
+        if !self.game.history().iter().any(|x| x.player == player) {
+            return Err(String::from("There is no turn to take back"));
+        }
+        self.takeback = Some(Takeback {
+            player,
+            accepted: vec![],
+        });
+        let mut messages = vec![Message::all(format!("takeback {:?}", player))];
+        // With nobody else left to ask, there is no need to wait.
+        messages.extend(self.finish_takeback());
+        Ok(messages)
+    }
+
+    fn answer_takeback(&mut self, client: ClientId, accept: bool) -> Result<Vec<Message>, String> {
+        let player = self.playing_player(client)?;
+        let takeback = self
+            .takeback
+            .as_mut()
+            .ok_or("Nobody has asked for a takeback")?;
+        if takeback.player == player {
+            return Err(String::from("Cannot answer your own takeback"));
+        }
+        if !accept {
+            self.takeback = None;
+            return Ok(vec![Message::all(format!("declined {:?}", player))]);
+        }
+        if !takeback.accepted.contains(&player) {
+            takeback.accepted.push(player);
+        }
+        let mut messages = vec![Message::all(format!("accepted {:?}", player))];
+        messages.extend(self.finish_takeback());
+        Ok(messages)
+    }
+
+    /// Rewind the game to before the requester's last turn, if everyone
+    /// else still playing has accepted the takeback.
+    fn finish_takeback(&mut self) -> Vec<Message> {
+        let takeback = match &self.takeback {
+            Some(takeback) => takeback,
+            None => return vec![],
+        };
+        let waiting = self
+            .game
+            .playing()
+            .iter()
+            .any(|x| *x != takeback.player && !takeback.accepted.contains(x));
+        if waiting {
+            return vec![];
+        }
+        let player = takeback.player;
+        self.takeback = None;
+        if let Some(last) = self.game.history().iter().rposition(|x| x.player == player) {
+            while self.game.history().len() > last {
+                if let Some(record) = self.game.undo() {
+                    self.log(|log, _| log.undo(&record));
+                }
+            }
+        }
+        let mut messages = vec![Message::all(format!("tookback {:?}", player))];
+        messages.extend(self.after_change());
+        messages
+    }
+
+    fn claim_arbiter(&mut self, client: ClientId, token: &str) -> Result<Vec<Message>, String> {
+        if self.arbiter_token.as_deref() != Some(token) {
+            return Err(String::from("Unknown token"));
+        }
+        if !self.arbiters.contains(&client) {
+            self.arbiters.push(client);
+        }
+        Ok(vec![Message::to(client, "arbiter")])
+    }
+
+    fn pause(&mut self, client: ClientId, pause: bool) -> Result<Vec<Message>, String> {
+        self.check_arbiter(client)?;
+        let changed = match pause {
+            true => self.game.pause_clock(),
+            false => self.game.resume_clock(),
+        };
+        if !changed {
+            return Err(String::from(match pause {
+                true => "The clock is already paused",
+                false => "The clock is not paused",
+            }));
+        }
+        self.log(|log, game| log.clock(game));
+        if pause {
+            self.paused_at = Some(Instant::now());
+            return Ok(vec![Message::all("paused")]);
+        }
+        if let (Some(started), Some(paused_at)) = (self.turn_started, self.paused_at.take()) {
+            self.turn_started = Some(started + paused_at.elapsed());
+        }
+        Ok(vec![Message::all("resumed")])
+    }
+
+    fn adjust(&mut self, client: ClientId, text: &str) -> Result<Vec<Message>, String> {
+        self.check_arbiter(client)?;
+        let (player, seconds) = text
+            .split_once(' ')
+            .and_then(|(player, seconds)| {
+                Some((player_named(player)?, seconds.trim().parse::<i64>().ok()?))
+            })
+            .ok_or("Expected a player and seconds, like Head 30")?;
+        let remaining = self
+            .game
+            .clock()
+            .and_then(|x| x.remaining(player))
+            .ok_or_else(|| format!("{:?} is not on the clock", player))?;
+        let change = Duration::from_secs(seconds.unsigned_abs());
+        let remaining = match seconds < 0 {
+            true => remaining.saturating_sub(change),
+            false => remaining + change,
+        };
+        self.game.set_remaining_time(player, remaining);
+        self.log(|log, game| log.clock(game));
+        Ok(vec![Message::all(format!(
+            "adjusted {:?} {}",
+            player,
+            remaining.as_secs()
+        ))])
+    }
+
+    /// Make sure that a client is the arbiter of a game with a clock.
+    fn check_arbiter(&self, client: ClientId) -> Result<(), String> {
+        if !self.arbiters.contains(&client) {
+            return Err(String::from("Only the arbiter can do that"));
+        }
+        if self.game.clock().is_none() {
+            return Err(String::from("The game has no clock"));
+        }
+        Ok(())
+    }
+
+    /// The player whose seat a client holds, as long as the game is going
+    /// and they are still in it.
+    fn playing_player(&self, client: ClientId) -> Result<Player, String> {
+        let player = self.player_of(client).ok_or("Join the game first")?;
+        if !self.is_started() {
+            return Err(String::from("The game has not started"));
+        }
+        if self.game.state() != GameState::InProgress {
+            return Err(String::from("The game is over"));
+        }
+        if !self.game.playing().contains(&player) {
+            return Err(format!("{:?} is not in the game", player));
+        }
+        Ok(player)
+    }
+
+    /// Take out the player to move if they have run out of time. `serve`
+    /// calls this every so often, so that a player who stops moving does
+    /// not hold up the game forever.
+    pub fn check_clock(&mut self) -> Vec<Message> {
+        let (clock, started, player) = match (
+            self.game.clock(),
+            self.turn_started,
+            self.game.current_player(),
+        ) {
+            (Some(clock), Some(started), Some(player)) => (clock, started, player),
+            _ => return vec![],
+        };
+        if !clock.is_flagged(player, started.elapsed()) {
+            return vec![];
+        }
+        self.game.forfeit_on_timeout(player);
+        self.timed_out(player)
+    }
+
+    /// Act as if `time` has gone by on the clock, so that tests do not
+    /// have to wait for it.
+    #[cfg(test)]
+    fn pass_time(&mut self, time: Duration) {
+        self.turn_started = self.turn_started.map(|x| x - time);
+        self.paused_at = self.paused_at.map(|x| x - time);
+    }
+
+    fn timed_out(&mut self, player: Player) -> Vec<Message> {
+        self.takeback = None;
+        self.log(|log, game| {
+            log.timeout(player)?;
+            log.clock(game)
+        });
+        let mut messages = vec![Message::all(format!("timeout {:?}", player))];
+        messages.extend(self.after_change());
+        messages
+    }
+
+    /// The new position, and the result if the game just ended. This also
+    /// starts timing the next turn.
+    fn after_change(&mut self) -> Vec<Message> {
+        self.turn_started = Some(Instant::now());
+        let mut messages = vec![Message::all(format!("position {}", self.game.to_fen()))];
+        if let Some(over) = self.over() {
+            messages.push(Message::all(over));
+            let standings: Vec<Player> = match self.game.state() {
+                GameState::Finished => self
+                    .game
+                    .result()
+                    .placings
+                    .iter()
+                    .map(|x| x.player)
+                    .collect(),
+                _ => self.game.finished().to_vec(),
+            };
+            self.log(|log, _| log.result(&standings));
+        }
+        messages
+    }
+
+    /// Write to every event log. A log that cannot be written to does not
+    /// hold up the game, so errors are left out.
+    fn log(&mut self, mut write: impl FnMut(&mut HostLog, &Game) -> io::Result<()>) {
+        for log in &mut self.logs {
+            let _ = write(log, &self.game);
+        }
+    }
+
+    fn joined(&self) -> Vec<String> {
+        self.seats
+            .iter()
+            .filter_map(|x| Some((x.player, x.holder.as_ref()?)))
+            .map(|(player, holder)| format!("joined {:?} {}", player, holder.name))
+            .collect()
+    }
+
+    fn over(&self) -> Option<String> {
+        match self.game.state() {
+            GameState::InProgress => None,
+            GameState::Drawn(_) => Some(String::from("over draw")),
+            GameState::Finished => {
+                let standings: Vec<String> = self
+                    .game
+                    .result()
+                    .placings
+                    .iter()
+                    .map(|x| format!("{:?}", x.player))
+                    .collect();
+                Some(format!("over {}", standings.join(" ")))
+            }
+        }
+    }
+}
+
+pub(crate) enum Event {
+    Connected(ClientId, TcpStream),
+    Line(ClientId, String),
+    Closed(ClientId),
+}
+
+/// Accept clients on `listener`, reading each one's lines on a thread of
+/// its own, and hand everything that happens to one place.
+pub(crate) fn listen(listener: TcpListener) -> mpsc::Receiver<Event> {
+    let (sender, events) = mpsc::channel();
+    thread::spawn(move || {
+        for (client, stream) in listener.incoming().enumerate() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(_) => continue,
+            };
+            let reader = match stream.try_clone() {
+                Ok(reader) => reader,
+                Err(_) => continue,
+            };
+            if sender.send(Event::Connected(client, stream)).is_err() {
+                break;
+            }
+            let sender = sender.clone();
+            thread::spawn(move || {
+                for line in BufReader::new(reader).lines() {
+                    match line {
+                        Ok(line) => {
+                            if sender.send(Event::Line(client, line)).is_err() {
+                                return;
+                            }
+                        }
+                        Err(_) => break,
+                    }
+                }
+                let _ = sender.send(Event::Closed(client));
+            });
+        }
+    });
+    events
+}
+
+pub(crate) fn deliver(streams: &mut HashMap<ClientId, TcpStream>, messages: Vec<Message>) {
+    for message in messages {
+        for (client, stream) in streams.iter_mut() {
+            if message.to.is_none_or(|x| x == *client) {
+                // A client that cannot be reached will be closed by its
+                // reader, which frees its seat.
+                let _ = writeln!(stream, "{}", message.text);
+            }
+        }
+    }
+}
+
+/// Accept clients on `listener` and run the game for them. Returns once
+/// the game is over and every client has left.
+pub fn serve(listener: TcpListener, mut host: Host) -> io::Result<Host> {
+    let events = listen(listener);
+    let mut streams: HashMap<ClientId, TcpStream> = HashMap::new();
+    loop {
+        let event = match events.recv_timeout(CLOCK_CHECK) {
+            Ok(event) => Some(event),
+            Err(mpsc::RecvTimeoutError::Timeout) => None,
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        };
+        let messages = match event {
+            None => host.check_clock(),
+            Some(Event::Connected(client, stream)) => {
+                streams.insert(client, stream);
+                vec![]
+            }
+            Some(Event::Line(client, line)) => host.handle(client, &line),
+            Some(Event::Closed(client)) => {
+                streams.remove(&client);
+                host.disconnect(client)
+            }
+        };
+        deliver(&mut streams, messages);
+        if host.is_done() && streams.is_empty() {
+            break;
+        }
+    }
+    Ok(host)
+}
+
+/// An event log that POSTs each line to a plain `http://` URL, with the
+/// line as an `application/x-ndjson` body. Lines are sent in order from a
+/// thread of their own, so that a slow or missing server does not hold up
+/// the game, and lines that cannot be delivered are dropped.
+#[derive(Debug)]
+pub struct Webhook {
+    pending: Vec<u8>,
+    lines: mpsc::Sender<Vec<u8>>,
+}
+
+impl Webhook {
+    pub fn new(url: &str) -> Result<Self, String> {
+        let rest = url
+            .strip_prefix("http://")
+            .ok_or_else(|| format!("Only http:// webhooks are supported: {}", url))?;
+        let (authority, path) = match rest.find('/') {
+            Some(index) => (&rest[..index], &rest[index..]),
+            None => (rest, "/"),
+        };
+        if authority.is_empty() {
+            return Err(format!("No host in webhook: {}", url));
+        }
+        let address = match authority.contains(':') {
+            true => authority.to_string(),
+            false => format!("{}:80", authority),
+        };
+        let (authority, path) = (authority.to_string(), path.to_string());
+
+        let (sender, lines) = mpsc::channel::<Vec<u8>>();
+        thread::spawn(move || {
+            for line in lines {
+                let _ = post(&address, &authority, &path, &line);
+            }
+        });
+        Ok(Self {
+            pending: vec![],
+            lines: sender,
+        })
+    }
+}
+
+fn post(address: &str, authority: &str, path: &str, body: &[u8]) -> io::Result<()> {
+    let mut stream = TcpStream::connect(address)?;
+    stream.set_read_timeout(Some(WEBHOOK_TIMEOUT))?;
+    stream.set_write_timeout(Some(WEBHOOK_TIMEOUT))?;
+    write!(
+        stream,
+        "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: application/x-ndjson\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        path,
+        authority,
+        body.len()
+    )?;
+    stream.write_all(body)?;
+    stream.flush()?;
+    // Wait for the reply, so that lines arrive one after another.
+    let mut status = String::new();
+    BufReader::new(stream).read_line(&mut status)?;
+    Ok(())
+}
+
+/// Lines are sent once they are complete and the log is flushed, which
+/// `EventLog` does after each one.
+impl Write for Webhook {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.pending.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        while let Some(end) = self.pending.iter().position(|x| *x == b'\n') {
+            let line: Vec<u8> = self.pending.drain(..=end).collect();
+            self.lines
+                .send(line)
+                .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "The webhook stopped"))?;
+        }
+        Ok(())
+    }
+}
+
+/// Something that the server said, as read by `Client::next_update`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Update {
+    /// The client now holds a seat.
+    Welcome {
+        player: Player,
+        token: String,
+    },
+    Joined {
+        player: Player,
+        name: String,
+    },
+    Start,
+    /// The board and who is to move, after a turn or when resyncing.
+    Position(Box<Board>, Option<Player>),
+    Played {
+        player: Player,
+        turn: Turn,
+    },
+    Finished(Player),
+    Resigned(Player),
+    /// A player ran out of time and is out of the game.
+    Timeout(Player),
+    /// This client is now the arbiter.
+    Arbiter,
+    Paused,
+    Resumed,
+    /// The arbiter changed a player's time left.
+    Adjusted {
+        player: Player,
+        remaining: Duration,
+    },
+    Left(Player),
+    Back(Player),
+    /// A player asked to take back their last turn.
+    Takeback(Player),
+    Accepted(Player),
+    Declined(Player),
+    /// The game was rewound to before this player's last turn.
+    TookBack(Player),
+    /// Everyone from first to last, or `None` for a draw.
+    Over(Option<Vec<Player>>),
+    /// A command from this client could not be carried out.
+    Error(String),
+}
+
+impl Update {
+    fn parse(line: &str) -> Option<Self> {
+        let (kind, rest) = line.split_once(' ').unwrap_or((line, ""));
+        let mut words = rest.split_whitespace();
+        let player = |x: Option<&str>| x.and_then(player_named);
+        Some(match kind {
+            "welcome" => Update::Welcome {
+                player: player(words.next())?,
+                token: words.next()?.to_string(),
+            },
+            "joined" => {
+                let (player, name) = rest.split_once(' ')?;
+                Update::Joined {
+                    player: player_named(player)?,
+                    name: name.to_string(),
+                }
+            }
+            "start" => Update::Start,
+            "position" => {
+                let (board, to_move) = Board::from_fen(rest).ok()?;
+                Update::Position(Box::new(board), to_move)
+            }
+            "played" => Update::Played {
+                player: player(words.next())?,
+                turn: words.next()?.parse().ok()?,
+            },
+            "finished" => Update::Finished(player(words.next())?),
+            "resigned" => Update::Resigned(player(words.next())?),
+            "timeout" => Update::Timeout(player(words.next())?),
+            "arbiter" => Update::Arbiter,
+            "paused" => Update::Paused,
+            "resumed" => Update::Resumed,
+            "adjusted" => Update::Adjusted {
+                player: player(words.next())?,
+                remaining: Duration::from_secs(words.next()?.parse().ok()?),
+            },
+            "left" => Update::Left(player(words.next())?),
+            "back" => Update::Back(player(words.next())?),
+            "takeback" => Update::Takeback(player(words.next())?),
+            "accepted" => Update::Accepted(player(words.next())?),
+            "declined" => Update::Declined(player(words.next())?),
+            "tookback" => Update::TookBack(player(words.next())?),
+            "over" if rest == "draw" => Update::Over(None),
+            "over" => Update::Over(Some(words.map(player_named).collect::<Option<Vec<_>>>()?)),
+            "error" => Update::Error(rest.to_string()),
+            _ => return None,
+        })
+    }
+}
+
+/// A connection to a server run by `serve`. It keeps track of the seat
+/// and the position from what the server sends, so a frontend only has to
+/// draw them and submit turns when `is_my_turn` says so.
+#[derive(Debug)]
+pub struct Client {
+    stream: TcpStream,
+    reader: BufReader<TcpStream>,
+    player: Option<Player>,
+    token: Option<String>,
+    position: Option<(Board, Option<Player>)>,
+}
+
+impl Client {
+    pub fn connect(address: impl ToSocketAddrs) -> io::Result<Self> {
+        let stream = TcpStream::connect(address)?;
+        Ok(Self {
+            reader: BufReader::new(stream.try_clone()?),
+            stream,
+            player: None,
+            token: None,
+            position: None,
+        })
+    }
+
+    /// The seat that the server gave this client, once it has.
+    pub fn player(&self) -> Option<Player> {
+        self.player
+    }
+
+    /// What to pass to `rejoin` to take the seat back after reconnecting.
+    pub fn token(&self) -> Option<&str> {
+        self.token.as_deref()
+    }
+
+    /// The latest position from the server, once the game has started.
+    pub fn board(&self) -> Option<&Board> {
+        self.position.as_ref().map(|(board, _)| board)
+    }
+
+    pub fn to_move(&self) -> Option<Player> {
+        self.position.as_ref().and_then(|(_, to_move)| *to_move)
+    }
+
+    pub fn is_my_turn(&self) -> bool {
+        self.player.is_some() && self.to_move() == self.player
+    }
+
+    fn send(&mut self, line: &str) -> io::Result<()> {
+        writeln!(self.stream, "{}", line)?;
+        self.stream.flush()
+    }
+
+    pub fn join(&mut self, name: &str) -> io::Result<()> {
+        self.send(&format!("join {}", name))
+    }
+
+    pub fn rejoin(&mut self, token: &str) -> io::Result<()> {
+        self.send(&format!("rejoin {}", token))
+    }
+
+    pub fn submit_turn(&mut self, turn: &Turn) -> io::Result<()> {
+        self.send(&format!("turn {}", turn))
+    }
+
+    pub fn resign(&mut self) -> io::Result<()> {
+        self.send("resign")
+    }
+
+    /// Become the arbiter, with the token that the server was given.
+    pub fn claim_arbiter(&mut self, token: &str) -> io::Result<()> {
+        self.send(&format!("arbiter {}", token))
+    }
+
+    pub fn pause_clock(&mut self) -> io::Result<()> {
+        self.send("pause")
+    }
+
+    pub fn resume_clock(&mut self) -> io::Result<()> {
+        self.send("resume")
+    }
+
+    /// Give a player more time, or less with a negative number of seconds.
+    pub fn adjust_clock(&mut self, player: Player, seconds: i64) -> io::Result<()> {
+        self.send(&format!("adjust {:?} {}", player, seconds))
+    }
+
+    pub fn request_takeback(&mut self) -> io::Result<()> {
+        self.send("takeback")
+    }
+
+    /// Answer the takeback that someone else asked for.
+    pub fn answer_takeback(&mut self, accept: bool) -> io::Result<()> {
+        self.send(if accept { "accept" } else { "decline" })
+    }
+
+    /// Wait for the next thing that the server says. Gives `None` once the
+    /// server closes the connection.
+    pub fn next_update(&mut self) -> io::Result<Option<Update>> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let update = Update::parse(line.trim_end()).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Unexpected message: {}", line.trim_end()),
+            )
+        })?;
+        match &update {
+            Update::Welcome { player, token } => {
+                self.player = Some(*player);
+                self.token = Some(token.clone());
+            }
+            Update::Position(board, to_move) => {
+                self.position = Some((board.as_ref().clone(), *to_move))
+            }
+            _ => {}
+        }
+        Ok(Some(update))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, StartLayout};
+    use std::sync::{Arc, Mutex};
+
+    fn host() -> Host {
+        let board = Board::new(Config::default().with_layout(StartLayout::TwoPlayers));
+        Host::new(board, vec![Player::Head, Player::Tail])
+    }
+
+    fn texts(messages: &[Message]) -> Vec<&str> {
+        messages.iter().map(|x| x.text.as_str()).collect()
+    }
+
+    #[test]
+    fn test_lobby_and_turns() {
+        let mut host = host();
+        let messages = host.handle(0, "join Ada");
+        assert!(messages[0].text.starts_with("welcome Head "));
+        assert_eq!(messages[1], Message::all("joined Head Ada"));
+        assert_eq!(
+            texts(&host.handle(0, "turn 4/10-5/11")),
+            &["error The game has not started"]
+        );
+        assert_eq!(
+            texts(&host.handle(0, "join Ada")),
+            &["error Already seated as Head"]
+        );
+
+        let messages = host.handle(1, "join Bob");
+        assert!(messages[0].text.starts_with("welcome Tail "));
+        assert_eq!(messages[1], Message::to(1, "joined Head Ada"));
+        assert_eq!(messages[2], Message::all("joined Tail Bob"));
+        assert_eq!(messages[3], Message::all("start"));
+        assert!(host.is_started());
+        assert_eq!(
+            texts(&host.handle(2, "join Cy")),
+            &["error The game is full"]
+        );
+
+        assert_eq!(
+            host.handle(1, "turn 14/16-13/15")[0].text,
+            "error It is Head's turn, not Tail's."
+        );
+        let messages = host.handle(0, "turn 4/10-5/11");
+        assert_eq!(messages[0], Message::all("played Head 4/10-5/11"));
+        assert_eq!(
+            messages[1],
+            Message::all(format!("position {}", host.game().to_fen()))
+        );
+
+        let messages = host.handle(1, "resign");
+        assert_eq!(texts(&messages)[..1], ["resigned Tail"]);
+        assert_eq!(messages.last(), Some(&Message::all("over Head Tail")));
+    }
+
+    #[test]
+    fn test_rejoin() {
+        let mut host = host();
+        let token = host.handle(0, "join Ada")[0]
+            .text
+            .rsplit(' ')
+            .next()
+            .unwrap()
+            .to_string();
+        host.handle(1, "join Bob");
+        assert_eq!(host.disconnect(0), vec![Message::all("left Head")]);
+        assert_eq!(host.disconnect(0), vec![]);
+        assert_eq!(
+            texts(&host.handle(2, "rejoin nope")),
+            &["error Unknown token"]
+        );
+
+        let messages = host.handle(2, &format!("rejoin {}", token));
+        assert_eq!(
+            texts(&messages)[..4],
+            [
+                format!("welcome Head {}", token).as_str(),
+                "joined Head Ada",
+                "joined Tail Bob",
+                "start"
+            ]
+        );
+        assert_eq!(messages.last(), Some(&Message::all("back Head")));
+        assert_eq!(host.player_of(2), Some(Player::Head));
+        assert!(host.handle(2, "turn 4/10-5/11")[0]
+            .text
+            .starts_with("played"));
+    }
+
+    #[test]
+    fn test_takeback() {
+        let mut host = host();
+        host.handle(0, "join Ada");
+        host.handle(1, "join Bob");
+        assert_eq!(
+            texts(&host.handle(0, "takeback")),
+            &["error There is no turn to take back"]
+        );
+        host.handle(0, "turn 4/10-5/11");
+        host.handle(1, "turn 14/16-13/15");
+        let before = host.game().to_fen();
+        host.handle(0, "turn 5/11-6/12");
+
+        assert_eq!(
+            texts(&host.handle(1, "accept")),
+            &["error Nobody has asked for a takeback"]
+        );
+        assert_eq!(texts(&host.handle(0, "takeback")), &["takeback Head"]);
+        assert_eq!(
+            texts(&host.handle(0, "accept")),
+            &["error Cannot answer your own takeback"]
+        );
+        assert_eq!(texts(&host.handle(1, "decline")), &["declined Tail"]);
+        assert_eq!(host.game().history().len(), 3);
+
+        host.handle(0, "takeback");
+        let messages = host.handle(1, "accept");
+        assert_eq!(
+            texts(&messages),
+            &[
+                "accepted Tail",
+                "tookback Head",
+                format!("position {}", before).as_str()
+            ]
+        );
+        assert_eq!(host.game().history().len(), 2);
+        assert_eq!(host.game().current_player(), Some(Player::Head));
+
+        // Taking back Tail's turn takes back everything since, too.
+        host.handle(0, "turn 4/12-5/13");
+        host.handle(1, "takeback");
+        host.handle(0, "accept");
+        assert_eq!(host.game().history().len(), 1);
+        assert_eq!(host.game().current_player(), Some(Player::Tail));
+
+        // A turn drops a request that is still waiting.
+        host.handle(0, "takeback");
+        host.handle(1, "turn 14/16-13/15");
+        assert_eq!(
+            texts(&host.handle(1, "accept")),
+            &["error Nobody has asked for a takeback"]
+        );
+    }
+
+    #[test]
+    fn test_time_control() {
+        let control = TimeControl::PerMove(Duration::from_secs(10));
+        let mut host = host().with_time_control(control);
+        host.handle(0, "join Ada");
+        host.handle(1, "join Bob");
+        assert_eq!(host.check_clock(), vec![]);
+        assert!(host.handle(0, "turn 4/10-5/11")[0]
+            .text
+            .starts_with("played"));
+
+        host.pass_time(Duration::from_secs(11));
+        let messages = host.handle(1, "turn 14/16-13/15");
+        assert_eq!(texts(&messages)[0], "timeout Tail");
+        assert_eq!(messages.last(), Some(&Message::all("over Head Tail")));
+    }
+
+    #[test]
+    fn test_check_clock() {
+        let control = TimeControl::PerMove(Duration::from_secs(10));
+        let mut host = host().with_time_control(control);
+        host.handle(0, "join Ada");
+        host.handle(1, "join Bob");
+        host.pass_time(Duration::from_secs(9));
+        assert_eq!(host.check_clock(), vec![]);
+        host.pass_time(Duration::from_secs(2));
+        let messages = host.check_clock();
+        assert_eq!(texts(&messages)[0], "timeout Head");
+        assert_eq!(messages.last(), Some(&Message::all("over Tail Head")));
+        assert_eq!(host.check_clock(), vec![]);
+    }
+
+    #[test]
+    fn test_arbiter() {
+        let control = TimeControl::PerMove(Duration::from_secs(10));
+        let mut host = host().with_time_control(control).with_arbiter("secret");
+        host.handle(0, "join Ada");
+        host.handle(1, "join Bob");
+        assert_eq!(
+            texts(&host.handle(0, "pause")),
+            &["error Only the arbiter can do that"]
+        );
+        assert_eq!(
+            texts(&host.handle(2, "arbiter nope")),
+            &["error Unknown token"]
+        );
+        assert_eq!(
+            host.handle(2, "arbiter secret"),
+            vec![Message::to(2, "arbiter")]
+        );
+
+        assert_eq!(host.handle(2, "pause"), vec![Message::all("paused")]);
+        assert_eq!(
+            texts(&host.handle(2, "pause")),
+            &["error The clock is already paused"]
+        );
+        assert_eq!(
+            texts(&host.handle(0, "turn 4/10-5/11")),
+            &["error The clock is paused"]
+        );
+        // Time spent paused does not count.
+        host.pass_time(Duration::from_secs(11));
+        assert_eq!(host.check_clock(), vec![]);
+        assert_eq!(host.handle(2, "resume"), vec![Message::all("resumed")]);
+        assert_eq!(host.check_clock(), vec![]);
+
+        assert_eq!(
+            host.handle(2, "adjust Head 100"),
+            vec![Message::all("adjusted Head 110")]
+        );
+        assert_eq!(
+            host.game().clock().unwrap().remaining(Player::Head),
+            Some(Duration::from_secs(110))
+        );
+        assert_eq!(
+            host.handle(2, "adjust Head -200"),
+            vec![Message::all("adjusted Head 0")]
+        );
+        assert_eq!(
+            texts(&host.handle(2, "adjust LeftHand 5")),
+            &["error LeftHand is not on the clock"]
+        );
+        assert_eq!(
+            texts(&host.handle(2, "adjust Head soon")),
+            &["error Expected a player and seconds, like Head 30"]
+        );
+
+        host.disconnect(2);
+        assert_eq!(
+            texts(&host.handle(2, "resume")),
+            &["error Only the arbiter can do that"]
+        );
+    }
+
+    /// A log that tests can read back while the host still has it.
+    #[derive(Clone, Default)]
+    struct SharedLog(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedLog {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedLog {
+        fn lines(&self) -> Vec<String> {
+            let text = String::from_utf8(self.0.lock().unwrap().clone()).unwrap();
+            text.lines().map(|x| x.to_string()).collect()
+        }
+    }
+
+    #[test]
+    fn test_event_log() {
+        let out = SharedLog::default();
+        let mut host = host().with_event_log(EventLog::new(Box::new(out.clone())));
+        host.handle(0, "join Ada");
+        assert_eq!(out.lines(), Vec::<String>::new());
+        host.handle(1, "join Bob");
+        host.handle(0, "turn 4/10-5/11");
+        host.handle(1, "turn 14/16-13/15");
+        host.handle(1, "takeback");
+        host.handle(0, "accept");
+        host.handle(1, "turn 14/12-13/11");
+        host.handle(0, "resign");
+
+        let lines = out.lines();
+        assert!(lines[0].starts_with("{\"event\":\"start\",\"players\":[\"Head\",\"Tail\"]"));
+        let turn = |number: usize, player: &str, turn: &str| {
+            format!(
+                "{{\"event\":\"turn\",\"number\":{},\"player\":\"{}\",\"turn\":\"{}\",\"elapsed_ms\":",
+                number, player, turn
+            )
+        };
+        assert!(lines[1].starts_with(&turn(1, "Head", "4/10-5/11")));
+        assert!(lines[2].starts_with(&turn(2, "Tail", "14/16-13/15")));
+        assert_eq!(
+            lines[3],
+            "{\"event\":\"undo\",\"number\":2,\"player\":\"Tail\"}"
+        );
+        assert!(lines[4].starts_with(&turn(2, "Tail", "14/12-13/11")));
+        assert_eq!(
+            lines[5..],
+            [
+                "{\"event\":\"resign\",\"player\":\"Head\"}",
+                "{\"event\":\"result\",\"standings\":[\"Tail\",\"Head\"]}",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_event_log_with_clock() {
+        let out = SharedLog::default();
+        let control = TimeControl::PerMove(Duration::from_secs(10));
+        let mut host = host()
+            .with_time_control(control)
+            .with_arbiter("secret")
+            .with_event_log(EventLog::new(Box::new(out.clone())));
+        host.handle(0, "join Ada");
+        host.handle(1, "join Bob");
+        host.handle(2, "arbiter secret");
+        host.handle(2, "pause");
+        host.handle(2, "adjust Tail -4");
+        host.handle(2, "resume");
+        host.pass_time(Duration::from_secs(11));
+        host.check_clock();
+
+        let lines = out.lines();
+        let clock = |paused: bool, head: u64, tail: u64| {
+            format!(
+                "{{\"event\":\"clock\",\"paused\":{},\"remaining_ms\":{{\"Head\":{},\"Tail\":{}}}}}",
+                paused, head, tail
+            )
+        };
+        assert_eq!(
+            lines[1..],
+            [
+                clock(false, 10000, 10000),
+                clock(true, 10000, 10000),
+                clock(true, 10000, 6000),
+                clock(false, 10000, 6000),
+                String::from("{\"event\":\"timeout\",\"player\":\"Head\"}"),
+                clock(false, 0, 6000),
+                String::from("{\"event\":\"result\",\"standings\":[\"Tail\",\"Head\"]}"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_webhook() {
+        assert!(Webhook::new("https://example.com/hook").is_err());
+        assert!(Webhook::new("http:///hook").is_err());
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        let (sender, requests) = mpsc::channel();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut reader = BufReader::new(stream.unwrap());
+                let mut head = vec![];
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" {
+                        break;
+                    }
+                    if let Some(value) = line.strip_prefix("Content-Length: ") {
+                        length = value.trim().parse().unwrap();
+                    }
+                    head.push(line.trim_end().to_string());
+                }
+                let mut body = vec![0; length];
+                io::Read::read_exact(&mut reader, &mut body).unwrap();
+                write!(reader.get_mut(), "HTTP/1.1 204 No Content\r\n\r\n").unwrap();
+                sender
+                    .send((head[0].clone(), String::from_utf8(body).unwrap()))
+                    .unwrap();
+            }
+        });
+
+        let mut host = host().with_event_log(EventLog::new(Box::new(Webhook::new(&url).unwrap())));
+        host.handle(0, "join Ada");
+        host.handle(1, "join Bob");
+        host.handle(0, "turn 4/10-5/11");
+        host.handle(1, "resign");
+
+        let next = || requests.recv_timeout(Duration::from_secs(10)).unwrap();
+        let (request, body) = next();
+        assert_eq!(request, "POST /hook HTTP/1.1");
+        assert!(body.starts_with("{\"event\":\"start\",\"players\":[\"Head\",\"Tail\"]"));
+        assert!(next().1.starts_with(
+            "{\"event\":\"turn\",\"number\":1,\"player\":\"Head\",\"turn\":\"4/10-5/11\","
+        ));
+        assert_eq!(next().1, "{\"event\":\"resign\",\"player\":\"Tail\"}\n");
+        assert_eq!(
+            next().1,
+            "{\"event\":\"result\",\"standings\":[\"Head\",\"Tail\"]}\n"
+        );
+    }
+
+    #[test]
+    fn test_serve() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || serve(listener, host()).unwrap());
+
+        let connect = |name: &str| {
+            let mut stream = TcpStream::connect(address).unwrap();
+            writeln!(stream, "join {}", name).unwrap();
+            let lines = BufReader::new(stream.try_clone().unwrap()).lines();
+            (stream, lines.map(|x| x.unwrap()))
+        };
+        let (mut ada, mut ada_lines) = connect("Ada");
+        assert!(ada_lines.next().unwrap().starts_with("welcome Head "));
+        assert_eq!(ada_lines.next().unwrap(), "joined Head Ada");
+        let (bob, mut bob_lines) = connect("Bob");
+        assert!(bob_lines.next().unwrap().starts_with("welcome Tail "));
+
+        assert_eq!(ada_lines.next().unwrap(), "joined Tail Bob");
+        assert_eq!(ada_lines.next().unwrap(), "start");
+        assert!(ada_lines.next().unwrap().starts_with("position "));
+        writeln!(ada, "resign").unwrap();
+        assert_eq!(ada_lines.next().unwrap(), "resigned Head");
+        assert!(ada_lines.next().unwrap().starts_with("position "));
+        assert_eq!(ada_lines.next().unwrap(), "over Tail Head");
+
+        drop((ada, ada_lines, bob, bob_lines));
+        let host = server.join().unwrap();
+        assert_eq!(host.game().state(), GameState::Finished);
+    }
+
+    #[test]
+    fn test_client() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let server = thread::spawn(move || serve(listener, host()).unwrap());
+
+        let mut ada = Client::connect(address).unwrap();
+        ada.join("Ada").unwrap();
+        let update = ada.next_update().unwrap().unwrap();
+        assert_eq!(ada.player(), Some(Player::Head));
+        assert_eq!(
+            update,
+            Update::Welcome {
+                player: Player::Head,
+                token: ada.token().unwrap().to_string(),
+            }
+        );
+        let mut bob = Client::connect(address).unwrap();
+        bob.join("Bob").unwrap();
+
+        let next = |client: &mut Client| client.next_update().unwrap().unwrap();
+        assert_eq!(
+            next(&mut ada),
+            Update::Joined {
+                player: Player::Head,
+                name: String::from("Ada"),
+            }
+        );
+        assert_eq!(
+            next(&mut ada),
+            Update::Joined {
+                player: Player::Tail,
+                name: String::from("Bob"),
+            }
+        );
+        assert_eq!(next(&mut ada), Update::Start);
+        assert!(matches!(
+            next(&mut ada),
+            Update::Position(_, Some(Player::Head))
+        ));
+        assert!(ada.is_my_turn());
+
+        let turn: Turn = "4/10-5/11".parse().unwrap();
+        ada.submit_turn(&turn).unwrap();
+        assert_eq!(
+            next(&mut ada),
+            Update::Played {
+                player: Player::Head,
+                turn,
+            }
+        );
+        next(&mut ada);
+        assert!(!ada.is_my_turn());
+        assert_eq!(
+            ada.board().unwrap().get_piece(crate::Point::new(5, 11)),
+            Some(crate::Cell::Occupied(Player::Head))
+        );
+
+        ada.submit_turn(&"14/16-13/15".parse().unwrap()).unwrap();
+        assert_eq!(
+            next(&mut ada),
+            Update::Error(String::from("It is Tail's turn, not Head's."))
+        );
+        bob.resign().unwrap();
+        assert_eq!(next(&mut ada), Update::Resigned(Player::Tail));
+        next(&mut ada);
+        assert_eq!(
+            next(&mut ada),
+            Update::Over(Some(vec![Player::Head, Player::Tail]))
+        );
+
+        drop((ada, bob));
+        server.join().unwrap();
+    }
+}
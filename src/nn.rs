@@ -0,0 +1,245 @@
+//! Small feed-forward networks for scoring positions, built with the `nn`
+//! feature. A network reads the position from `training::encode` and gives
+//! back a single number, so it can be trained on the samples from
+//! `training::self_play` and then used by `ai::AlphaBeta` like any other
+//! `Evaluator`.
+//!
+//! Networks are stored as text. Everything after a `#` on a line is a
+//! comment, and the rest is a list of layers, each written as
+//! `layer <inputs> <outputs> <relu|linear>` followed by one row for each
+//! output with its weight for every input and then its bias:
+//!
+//! ```text
+//! layer 242 16 relu
+//! 0.12 -0.5 ... 0.03
+//! ...
+//! layer 16 1 linear
+//! 0.7 0.1 ... -0.2
+//! ```
+//!
+//! Numbers are separated by any whitespace, so rows may span lines. Each
+//! layer takes as many inputs as the one before it gives, and the last one
+//! gives a single output.
+
+use crate::ai::Evaluator;
+use crate::training::encode;
+use crate::{Board, ParseError, Player};
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+/// How much the output of a network is scaled by before it is rounded, so
+/// that outputs around 1 still tell positions apart.
+pub const SCALE: f32 = 1000.0;
+
+/// Number of inputs that a network needs for positions on a board, which
+/// depends on its size and on the number of players in its layout.
+pub fn inputs_for(board: &Board) -> usize {
+    let player = board.config().layout().players()[0];
+    encode(board, player).concat().len()
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Activation {
+    Relu,
+    Linear,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Layer {
+    /// One row for each output, with a weight for each input.
+    weights: Vec<Vec<f32>>,
+    biases: Vec<f32>,
+    activation: Activation,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Network {
+    layers: Vec<Layer>,
+}
+
+impl Network {
+    /// Read a network from a file in the format described above.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        std::fs::read_to_string(path)?
+            .parse()
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Read a network like `load`, and check that it can score positions
+    /// on `board`, since using it on a board of a different size or with
+    /// a different number of players would panic.
+    pub fn load_for<P: AsRef<Path>>(path: P, board: &Board) -> io::Result<Self> {
+        let network = Self::load(path)?;
+        let needed = inputs_for(board);
+        if network.inputs() != needed {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "the network reads {} inputs, but this board needs {}",
+                    network.inputs(),
+                    needed
+                ),
+            ));
+        }
+        Ok(network)
+    }
+
+    /// Number of values that the network reads, which has to match the
+    /// length of `training::encode` for the boards it is used on.
+    pub fn inputs(&self) -> usize {
+        self.layers[0].weights[0].len()
+    }
+
+    /// Run the network on some inputs. Panics if there are not exactly
+    /// `inputs` of them.
+    pub fn forward(&self, inputs: &[f32]) -> f32 {
+        assert_eq!(inputs.len(), self.inputs(), "wrong number of inputs");
+        let mut values = inputs.to_vec();
+        for layer in &self.layers {
+            values = layer
+                .weights
+                .iter()
+                .zip(&layer.biases)
+                .map(|(row, bias)| {
+                    let sum = row.iter().zip(&values).map(|(w, x)| w * x).sum::<f32>() + bias;
+                    match layer.activation {
+                        Activation::Relu => sum.max(0.0),
+                        Activation::Linear => sum,
+                    }
+                })
+                .collect();
+        }
+        values[0]
+    }
+}
+
+impl FromStr for Network {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut tokens = text
+            .lines()
+            .flat_map(|line| line.split('#').next().unwrap_or("").split_whitespace());
+        let mut layers: Vec<Layer> = vec![];
+        while let Some(token) = tokens.next() {
+            if token != "layer" {
+                return Err(ParseError::InvalidWeights);
+            }
+            let mut size = || -> Result<usize, ParseError> {
+                match tokens.next().and_then(|x| x.parse().ok()) {
+                    Some(0) | None => Err(ParseError::InvalidWeights),
+                    Some(size) => Ok(size),
+                }
+            };
+            let (inputs, outputs) = (size()?, size()?);
+            let activation = match tokens.next() {
+                Some("relu") => Activation::Relu,
+                Some("linear") => Activation::Linear,
+                _ => return Err(ParseError::InvalidWeights),
+            };
+            if layers.last().is_some_and(|x| x.biases.len() != inputs) {
+                return Err(ParseError::InvalidWeights);
+            }
+
+            let mut layer = Layer {
+                weights: vec![],
+                biases: vec![],
+                activation,
+            };
+            for _ in 0..outputs {
+                let mut row = (0..=inputs)
+                    .map(|_| tokens.next().and_then(|x| x.parse().ok()))
+                    .collect::<Option<Vec<f32>>>()
+                    .ok_or(ParseError::InvalidWeights)?;
+                layer.biases.push(row.pop().expect("row has a bias"));
+                layer.weights.push(row);
+            }
+            layers.push(layer);
+        }
+        match layers.last() {
+            Some(layer) if layer.biases.len() == 1 => Ok(Self { layers }),
+            _ => Err(ParseError::InvalidWeights),
+        }
+    }
+}
+
+impl Evaluator for Network {
+    fn evaluate(&self, board: &Board, player: Player) -> i32 {
+        let inputs: Vec<f32> = encode(board, player)
+            .concat()
+            .into_iter()
+            .map(f32::from)
+            .collect();
+        (self.forward(&inputs) * SCALE).round() as i32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{AlphaBeta, Bot};
+    use crate::{Config, Game, StartLayout};
+
+    /// Two inputs for every spot on the two-player board.
+    const INPUTS: usize = 242;
+
+    /// Counts the player's own pieces, then doubles that and adds one.
+    fn text() -> String {
+        let mine: Vec<&str> = (0..INPUTS)
+            .map(|x| if x < INPUTS / 2 { "1" } else { "0" })
+            .collect();
+        let zeros = vec!["0"; INPUTS];
+        format!(
+            "# counts\nlayer {} 2 relu\n{} 0\n{} 0.5\nlayer 2 1 linear\n2 4 -1\n",
+            INPUTS,
+            mine.join(" "),
+            zeros.join(" ")
+        )
+    }
+
+    #[test]
+    fn test_evaluate() {
+        let network: Network = text().parse().unwrap();
+        assert_eq!(network.inputs(), INPUTS);
+        let board = Board::new(Config::default().with_layout(StartLayout::TwoPlayers));
+        assert_eq!(inputs_for(&board), INPUTS);
+        assert_eq!(network.evaluate(&board, Player::Head), 21000);
+
+        let game = Game::new(board, vec![Player::Head, Player::Tail]);
+        assert!(AlphaBeta::with_evaluator(1, network)
+            .choose(&game)
+            .is_some());
+    }
+
+    #[test]
+    fn test_load_for() {
+        let path = std::env::temp_dir().join(format!("stelsalto-{}.net", std::process::id()));
+        std::fs::write(&path, text()).unwrap();
+        let two = Board::new(Config::default().with_layout(StartLayout::TwoPlayers));
+        let loaded = Network::load_for(&path, &two);
+        let six = Network::load_for(&path, &Board::default());
+        std::fs::remove_file(&path).unwrap();
+        assert!(loaded.is_ok());
+        assert_eq!(six.unwrap_err().kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let invalid = [
+            String::new(),
+            String::from("layer 2 1 linear\n1 2"),
+            String::from("layer 2 1 tanh\n1 2 3"),
+            String::from("layer 2 2 linear\n1 2 3 4 5 6"),
+            String::from("layer 2 2 linear\n1 2 3 4 5 6\nlayer 3 1 linear\n1 2 3 4"),
+            String::from("layer 2 1 linear\n1 2 x"),
+            text().replace("# counts", "counts"),
+        ];
+        for text in &invalid {
+            assert_eq!(text.parse::<Network>(), Err(ParseError::InvalidWeights));
+        }
+        assert!("layer 2 2 linear\n1 2 3 4 5 6\nlayer 2 1 linear\n1 2 3"
+            .parse::<Network>()
+            .is_ok());
+    }
+}
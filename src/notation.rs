@@ -0,0 +1,125 @@
+//! Text notation for points and turns.
+//!
+//! A point is written as `row/column`, using the padded indices of `Point`.
+//! A step joins two points with a dash (`4/10-5/11`), and a jump chain
+//! joins every visited point with an arrow (`3/11>5/13>5/9`). When parsing,
+//! points may also be separated by whitespace, and the kind of turn is
+//! inferred from the distances involved.
+
+use crate::{ParseError, Point, Turn};
+use std::fmt;
+use std::str::FromStr;
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.row, self.column)
+    }
+}
+
+impl FromStr for Point {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.trim().splitn(2, '/');
+        let row = parts.next().and_then(|x| x.trim().parse().ok());
+        let column = parts.next().and_then(|x| x.trim().parse().ok());
+        match (row, column) {
+            (Some(row), Some(column)) => Ok(Point::new(row, column)),
+            _ => Err(ParseError::InvalidPoint),
+        }
+    }
+}
+
+/// The player with a name as `Debug` writes it, such as `Head`, which is
+/// how text protocols refer to players.
+#[cfg(any(feature = "net", feature = "wasm"))]
+pub(crate) fn player_named(name: &str) -> Option<crate::Player> {
+    crate::PLAYERS
+        .iter()
+        .copied()
+        .find(|x| format!("{:?}", x) == name)
+}
+
+impl fmt::Display for Turn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Turn::Step { from, to } => write!(f, "{}-{}", from, to),
+            Turn::Jumps(points) => {
+                let points: Vec<String> = points.iter().map(|x| x.to_string()).collect();
+                write!(f, "{}", points.join(">"))
+            }
+        }
+    }
+}
+
+impl Turn {
+    /// Read a turn written in this notation, the same as `str::parse`.
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        text.parse()
+    }
+}
+
+impl FromStr for Turn {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let points = s
+            .split(|c: char| c == '-' || c == '>' || c.is_whitespace())
+            .filter(|x| !x.is_empty())
+            .map(|x| x.parse())
+            .collect::<Result<Vec<Point>, ParseError>>()?;
+        Turn::new(points).map_err(|_| ParseError::InvalidTurn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_round_trip() {
+        assert_eq!(Point::new(3, 11).to_string(), "3/11");
+        assert_eq!("3/11".parse(), Ok(Point::new(3, 11)));
+        assert_eq!(" 3 / 11 ".parse(), Ok(Point::new(3, 11)));
+    }
+
+    #[test]
+    fn test_point_with_errors() {
+        assert_eq!("3".parse::<Point>(), Err(ParseError::InvalidPoint));
+        assert_eq!("3/x".parse::<Point>(), Err(ParseError::InvalidPoint));
+        assert_eq!("".parse::<Point>(), Err(ParseError::InvalidPoint));
+    }
+
+    #[test]
+    fn test_turn_round_trip() {
+        for text in &["4/10-5/11", "3/11>5/13", "3/11>5/13>5/9"] {
+            assert_eq!(text.parse::<Turn>().unwrap().to_string(), *text);
+            assert_eq!(Turn::parse(text).unwrap().to_string(), *text);
+        }
+    }
+
+    #[test]
+    fn test_turn_with_whitespace() {
+        assert_eq!(
+            "3/11 5/13".parse(),
+            Ok(Turn::Jumps(vec![Point::new(3, 11), Point::new(5, 13)])),
+        );
+        assert_eq!(
+            "4/10 5/11".parse(),
+            Ok(Turn::Step {
+                from: Point::new(4, 10),
+                to: Point::new(5, 11),
+            }),
+        );
+    }
+
+    #[test]
+    fn test_turn_with_errors() {
+        assert_eq!("4/10".parse::<Turn>(), Err(ParseError::InvalidTurn));
+        assert_eq!(
+            "4/10-5/11-6/12".parse::<Turn>(),
+            Err(ParseError::InvalidTurn)
+        );
+        assert_eq!("4/10-x".parse::<Turn>(), Err(ParseError::InvalidPoint));
+    }
+}
@@ -0,0 +1,132 @@
+//! Named reference positions on the standard board, shared by tests,
+//! benchmarks and engine developers. Apart from `opening`, they are
+//! played between `Head` (1) and `Tail` (2).
+
+use crate::{Board, Config};
+
+/// Every position that `by_name` knows about.
+pub const NAMES: [&str; 5] = ["opening", "opening-2p", "traffic-jam", "race", "blockade"];
+
+#[rustfmt::skip]
+const OPENING_2P: [&str; 17] = [
+    "            1",
+    "           1 1",
+    "          1 1 1",
+    "         1 1 1 1",
+    ". . . . . . . . . . . . .",
+    " . . . . . . . . . . . .",
+    "  . . . . . . . . . . .",
+    "   . . . . . . . . . .",
+    "    . . . . . . . . .",
+    "   . . . . . . . . . .",
+    "  . . . . . . . . . . .",
+    " . . . . . . . . . . . .",
+    ". . . . . . . . . . . . .",
+    "         2 2 2 2",
+    "          2 2 2",
+    "           2 2",
+    "            2",
+];
+
+/// Both sides crowd the center, so most pieces can jump but few can step.
+#[rustfmt::skip]
+const TRAFFIC_JAM: [&str; 17] = [
+    "            .",
+    "           . .",
+    "          . . .",
+    "         . . . .",
+    ". . . . . . . . . . . . .",
+    " . . . . 1 . . . . . . .",
+    "  . . . . 1 1 2 . . . .",
+    "   . . . 1 2 1 1 . . .",
+    "    . . . 2 1 2 1 . .",
+    "   . . . 2 1 2 2 . . .",
+    "  . . . . 2 1 2 . . . .",
+    " . . . . . 2 . . . . . .",
+    ". . . . . . . . . . . . .",
+    "         . . . .",
+    "          . . .",
+    "           . .",
+    "            .",
+];
+
+/// The sides have passed each other, so it only comes down to speed.
+#[rustfmt::skip]
+const RACE: [&str; 17] = [
+    "            .",
+    "           . .",
+    "          . 2 .",
+    "         2 . . 2",
+    ". . . . 2 . 2 . 2 . . . .",
+    " . . . . . 2 . 2 . . . .",
+    "  . . . . . 2 2 . . . .",
+    "   . . . . . . . . . .",
+    "    . . . . . . . . .",
+    "   . . . . . . . . . .",
+    "  . . . . 1 1 . . . . .",
+    " . . . . 1 . 1 . . . . .",
+    ". . . . 1 . 1 . 1 . . . .",
+    "         1 . . 1",
+    "          . 1 .",
+    "           . .",
+    "            .",
+];
+
+/// Tail never left the tip of its corner, so Head cannot fill its target.
+#[rustfmt::skip]
+const BLOCKADE: [&str; 17] = [
+    "            .",
+    "           2 2",
+    "          2 2 2",
+    "         2 2 2 .",
+    ". . . . . . 2 . . . . . .",
+    " . . . . . . . . . . . .",
+    "  . . . . . . . . . . .",
+    "   . . . . . . . . . .",
+    "    . . . . . . . . .",
+    "   . . . . . . . . . .",
+    "  . . . . . . . . . . .",
+    " . . . . . . . . . . . .",
+    ". . . . . 1 . . . . . . .",
+    "         1 1 1 1",
+    "          1 1 1",
+    "           1 1",
+    "            2",
+];
+
+/// A reference position by name, or `None` if there is no such position.
+pub fn by_name(name: &str) -> Option<Board> {
+    let diagram = match name {
+        "opening" => return Some(Board::default()),
+        "opening-2p" => &OPENING_2P,
+        "traffic-jam" => &TRAFFIC_JAM,
+        "race" => &RACE,
+        "blockade" => &BLOCKADE,
+        _ => return None,
+    };
+    Some(Board::from_ascii(diagram, &Config::default()).expect("bundled positions are valid"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    #[test]
+    fn test_by_name() {
+        for name in NAMES.iter() {
+            let board = by_name(name).unwrap();
+            assert_eq!(board.validate_setup(), Ok(()), "{}", name);
+            assert_eq!(board.pieces_of(Player::Head).count(), 10, "{}", name);
+            assert_eq!(board.pieces_of(Player::Tail).count(), 10, "{}", name);
+        }
+        assert_eq!(by_name("nowhere"), None);
+    }
+
+    #[test]
+    fn test_blockade() {
+        let board = by_name("blockade").unwrap();
+        assert_eq!(board.pieces_home(Player::Head), 9);
+        assert!(!board.has_player_won(Player::Head));
+    }
+}
@@ -0,0 +1,70 @@
+//! Counters for seeing how much work the computer players do, such as when
+//! comparing board representations. Only built with the `profile` feature,
+//! so that normal builds do not pay for the counting.
+//!
+//! The counters are shared by every search in the process, so reset them
+//! before the part to be measured.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static SEARCHED: AtomicU64 = AtomicU64::new(0);
+static TABLE_HITS: AtomicU64 = AtomicU64::new(0);
+static PLAYOUTS: AtomicU64 = AtomicU64::new(0);
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Counts {
+    /// Positions visited by `AlphaBeta`.
+    pub searched: u64,
+    /// Positions that `AlphaBeta` found in its transposition table.
+    pub table_hits: u64,
+    /// Random games played out by `Mcts`.
+    pub playouts: u64,
+}
+
+/// The counts since the start or the last `reset`.
+pub fn counts() -> Counts {
+    Counts {
+        searched: SEARCHED.load(Ordering::Relaxed),
+        table_hits: TABLE_HITS.load(Ordering::Relaxed),
+        playouts: PLAYOUTS.load(Ordering::Relaxed),
+    }
+}
+
+pub fn reset() {
+    SEARCHED.store(0, Ordering::Relaxed);
+    TABLE_HITS.store(0, Ordering::Relaxed);
+    PLAYOUTS.store(0, Ordering::Relaxed);
+}
+
+pub(crate) fn count_searched() {
+    SEARCHED.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn count_table_hit() {
+    TABLE_HITS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn count_playout() {
+    PLAYOUTS.fetch_add(1, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{AlphaBeta, Bot, Mcts};
+    use crate::{Game, Player};
+
+    #[test]
+    fn test_counts() {
+        // Other tests may be searching at the same time, so only check
+        // that the counts go up.
+        let game = Game::new(crate::Board::default(), vec![Player::Head, Player::Tail]);
+        let before = counts();
+        AlphaBeta::new(3).choose(&game);
+        Mcts::new(10).choose(&game);
+        let after = counts();
+        assert!(after.searched > before.searched);
+        assert!(after.table_hits >= before.table_hits);
+        assert!(after.playouts >= before.playouts + 10);
+    }
+}
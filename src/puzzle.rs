@@ -0,0 +1,376 @@
+//! Collections of solitaire puzzles, where the player to move has to finish
+//! in as few turns as possible while every other piece stays where it is,
+//! and checking them with `analysis::fastest_finish` for puzzle authors.
+//!
+//! A collection is a JSON array of puzzles, each with a position as in
+//! `Board::from_fen` that has a player to move, the stated solution as a
+//! list of turns, and optionally a name:
+//!
+//! ```text
+//! [
+//!     {"name": "Ladder", "position": "1/11/111/... 1 home:1 standard 2", "solution": ["9/13>11/15", "11/15-12/14"]}
+//! ]
+//! ```
+//!
+//! A puzzle is sound if its solution can be played, finishes, and nothing
+//! shorter does.
+
+use crate::analysis::fastest_finish;
+use crate::{Board, GameError, ParseError, Player, Turn};
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::Chars;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Puzzle {
+    pub name: Option<String>,
+    pub board: Board,
+    pub player: Player,
+    pub solution: Vec<Turn>,
+}
+
+/// Something wrong with a puzzle.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Problem {
+    /// A turn of the stated solution cannot be played, counting from 0.
+    IllegalTurn(usize, GameError),
+    /// The stated solution can be played, but the player has not finished
+    /// afterward.
+    Unfinished,
+    /// This solution has fewer turns than the stated one.
+    Shorter(Vec<Turn>),
+    /// There is no way to finish in as many turns as were searched.
+    NoSolution,
+}
+
+/// What `Puzzle::check` found.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Report {
+    /// The fewest turns that finish, unless no solution was found.
+    pub optimal: Option<usize>,
+    pub problems: Vec<Problem>,
+}
+
+impl Report {
+    pub fn is_sound(&self) -> bool {
+        self.problems.is_empty()
+    }
+}
+
+impl Puzzle {
+    /// Play the stated solution and look for a shorter one. If the stated
+    /// solution does not work, solutions of up to `max_turns` are looked
+    /// for instead.
+    pub fn check(&self, max_turns: usize) -> Report {
+        let mut problems = vec![];
+        let mut board = self.board.clone();
+        let mut works = true;
+        for (index, turn) in self.solution.iter().enumerate() {
+            if let Err(error) = board.take_turn(turn, self.player) {
+                problems.push(Problem::IllegalTurn(index, error));
+                works = false;
+                break;
+            }
+        }
+        if works && !board.has_player_finished(self.player) {
+            problems.push(Problem::Unfinished);
+            works = false;
+        }
+
+        let optimal = if works {
+            match self.solution.len().checked_sub(1) {
+                Some(turns) => match fastest_finish(&self.board, self.player, turns) {
+                    Some(line) => {
+                        let turns = line.len();
+                        problems.push(Problem::Shorter(line));
+                        turns
+                    }
+                    None => self.solution.len(),
+                },
+                None => 0,
+            }
+        } else {
+            match fastest_finish(&self.board, self.player, max_turns) {
+                Some(line) => line.len(),
+                None => {
+                    problems.push(Problem::NoSolution);
+                    return Report {
+                        optimal: None,
+                        problems,
+                    };
+                }
+            }
+        };
+        Report {
+            optimal: Some(optimal),
+            problems,
+        }
+    }
+}
+
+/// Read a collection of puzzles.
+pub fn parse(text: &str) -> Result<Vec<Puzzle>, ParseError> {
+    let mut chars = text.chars().peekable();
+    let value = Json::parse(&mut chars)?;
+    skip_space(&mut chars);
+    if chars.next().is_some() {
+        return Err(ParseError::InvalidPuzzle);
+    }
+    match value {
+        Json::Array(items) => items.iter().map(puzzle).collect(),
+        _ => Err(ParseError::InvalidPuzzle),
+    }
+}
+
+fn puzzle(value: &Json) -> Result<Puzzle, ParseError> {
+    let fields = match value {
+        Json::Object(fields) => fields,
+        _ => return Err(ParseError::InvalidPuzzle),
+    };
+    let name = match fields.get("name") {
+        Some(Json::String(name)) => Some(name.clone()),
+        Some(_) => return Err(ParseError::InvalidPuzzle),
+        None => None,
+    };
+    let (board, player) = match fields.get("position") {
+        Some(Json::String(position)) => Board::from_fen(position)?,
+        _ => return Err(ParseError::InvalidPuzzle),
+    };
+    let solution = match fields.get("solution") {
+        Some(Json::Array(turns)) => turns
+            .iter()
+            .map(|turn| match turn {
+                Json::String(turn) => turn.parse(),
+                _ => Err(ParseError::InvalidPuzzle),
+            })
+            .collect::<Result<_, _>>()?,
+        _ => return Err(ParseError::InvalidPuzzle),
+    };
+    Ok(Puzzle {
+        name,
+        board,
+        player: player.ok_or(ParseError::InvalidPuzzle)?,
+        solution,
+    })
+}
+
+/// As much of JSON as puzzle collections need. Puzzles do not use
+/// numbers, `true`, `false`, or `null`, so those are not kept.
+#[derive(Debug)]
+enum Json {
+    Other,
+    String(String),
+    Array(Vec<Json>),
+    Object(HashMap<String, Json>),
+}
+
+fn skip_space(chars: &mut Peekable<Chars>) {
+    while chars.peek().is_some_and(|x| x.is_whitespace()) {
+        chars.next();
+    }
+}
+
+impl Json {
+    fn parse(chars: &mut Peekable<Chars>) -> Result<Self, ParseError> {
+        skip_space(chars);
+        match chars.peek().ok_or(ParseError::InvalidPuzzle)? {
+            '"' => Self::string(chars).map(Json::String),
+            '[' => {
+                chars.next();
+                let mut items = vec![];
+                skip_space(chars);
+                if chars.peek() == Some(&']') {
+                    chars.next();
+                    return Ok(Json::Array(items));
+                }
+                loop {
+                    items.push(Self::parse(chars)?);
+                    skip_space(chars);
+                    match chars.next() {
+                        Some(',') => continue,
+                        Some(']') => return Ok(Json::Array(items)),
+                        _ => return Err(ParseError::InvalidPuzzle),
+                    }
+                }
+            }
+            '{' => {
+                chars.next();
+                let mut fields = HashMap::new();
+                skip_space(chars);
+                if chars.peek() == Some(&'}') {
+                    chars.next();
+                    return Ok(Json::Object(fields));
+                }
+                loop {
+                    skip_space(chars);
+                    let key = Self::string(chars)?;
+                    skip_space(chars);
+                    if chars.next() != Some(':') {
+                        return Err(ParseError::InvalidPuzzle);
+                    }
+                    fields.insert(key, Self::parse(chars)?);
+                    skip_space(chars);
+                    match chars.next() {
+                        Some(',') => continue,
+                        Some('}') => return Ok(Json::Object(fields)),
+                        _ => return Err(ParseError::InvalidPuzzle),
+                    }
+                }
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(c) = chars
+                    .peek()
+                    .filter(|x| x.is_alphanumeric() || "+-.".contains(**x))
+                {
+                    word.push(*c);
+                    chars.next();
+                }
+                match word.as_str() {
+                    "null" | "true" | "false" => Ok(Json::Other),
+                    _ if word.parse::<f64>().is_ok() => Ok(Json::Other),
+                    _ => Err(ParseError::InvalidPuzzle),
+                }
+            }
+        }
+    }
+
+    fn string(chars: &mut Peekable<Chars>) -> Result<String, ParseError> {
+        if chars.next() != Some('"') {
+            return Err(ParseError::InvalidPuzzle);
+        }
+        let mut text = String::new();
+        loop {
+            match chars.next().ok_or(ParseError::InvalidPuzzle)? {
+                '"' => return Ok(text),
+                '\\' => text.push(match chars.next().ok_or(ParseError::InvalidPuzzle)? {
+                    'n' => '\n',
+                    't' => '\t',
+                    'r' => '\r',
+                    'b' => '\u{8}',
+                    'f' => '\u{c}',
+                    'u' => {
+                        let code: String = chars.by_ref().take(4).collect();
+                        u32::from_str_radix(&code, 16)
+                            .ok()
+                            .and_then(std::char::from_u32)
+                            .ok_or(ParseError::InvalidPuzzle)?
+                    }
+                    c @ '"' | c @ '\\' | c @ '/' => c,
+                    _ => return Err(ParseError::InvalidPuzzle),
+                }),
+                c => text.push(c),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, Point, WinCondition};
+
+    fn collection(puzzles: &[(&str, &Board, &[&str])]) -> String {
+        let puzzles: Vec<String> = puzzles
+            .iter()
+            .map(|(name, board, solution)| {
+                let turns: Vec<String> = solution.iter().map(|x| format!("\"{}\"", x)).collect();
+                format!(
+                    "{{\"name\": \"{}\", \"position\": \"{}\", \"solution\": [{}]}}",
+                    name,
+                    board.to_fen(Some(Player::Head)),
+                    turns.join(", ")
+                )
+            })
+            .collect();
+        format!("[\n    {}\n]\n", puzzles.join(",\n    "))
+    }
+
+    #[test]
+    fn test_check() {
+        let config = Config::default().with_win_condition(WinCondition::FirstNPiecesHome(1));
+        let setup = [
+            (Point::new(9, 13), Player::Head),
+            (Point::new(10, 14), Player::Tail),
+        ];
+        let board = Board::from_setup(&setup, config).unwrap();
+        let text = collection(&[
+            (
+                "Sound",
+                &board,
+                &["9/13>11/15", "11/15-12/14", "12/14-13/13", "13/13-14/12"],
+            ),
+            (
+                "Long",
+                &board,
+                &[
+                    "9/13-10/12",
+                    "10/12-11/13",
+                    "11/13-12/12",
+                    "12/12-13/13",
+                    "13/13-14/12",
+                ],
+            ),
+            ("Short", &board, &["9/13>11/15", "11/15-12/14"]),
+            ("Broken", &board, &["9/13-10/14"]),
+        ]);
+        let puzzles = parse(&text).unwrap();
+        assert_eq!(puzzles.len(), 4);
+        assert_eq!(puzzles[0].name.as_deref(), Some("Sound"));
+        assert_eq!(puzzles[0].player, Player::Head);
+        assert_eq!(puzzles[0].board, board);
+
+        let report = puzzles[0].check(10);
+        assert_eq!(report.optimal, Some(4));
+        assert!(report.is_sound());
+
+        let report = puzzles[1].check(10);
+        assert_eq!(report.optimal, Some(4));
+        match report.problems.as_slice() {
+            [Problem::Shorter(line)] => assert_eq!(line.len(), 4),
+            problems => panic!("unexpected problems: {:?}", problems),
+        }
+
+        let report = puzzles[2].check(10);
+        assert_eq!(report.optimal, Some(4));
+        assert_eq!(report.problems, vec![Problem::Unfinished]);
+
+        let report = puzzles[3].check(3);
+        assert_eq!(report.optimal, None);
+        assert!(matches!(
+            report.problems.as_slice(),
+            [Problem::IllegalTurn(0, _), Problem::NoSolution]
+        ));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse("[]"), Ok(vec![]));
+        assert_eq!(parse("{}"), Err(ParseError::InvalidPuzzle));
+        assert_eq!(
+            parse("[{\"solution\": []}]"),
+            Err(ParseError::InvalidPuzzle)
+        );
+        assert_eq!(parse("[1, true, null"), Err(ParseError::InvalidPuzzle));
+        assert_eq!(
+            parse("[{\"position\": \"1/11\", \"solution\": []}]"),
+            Err(ParseError::InvalidField)
+        );
+        let fen = Board::default().to_fen(None);
+        assert_eq!(
+            parse(&format!(
+                "[{{\"position\": \"{}\", \"solution\": []}}]",
+                fen
+            )),
+            Err(ParseError::InvalidPuzzle)
+        );
+        let fen = Board::default().to_fen(Some(Player::Head));
+        assert_eq!(
+            parse(&format!(
+                "[{{\"position\": \"{}\", \"solution\": [\"4/10\"]}}] ",
+                fen
+            )),
+            Err(ParseError::InvalidTurn)
+        );
+    }
+}
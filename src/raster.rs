@@ -0,0 +1,130 @@
+//! PNG snapshots of positions, for bots, tournaments, and bug reports that
+//! have no GUI to show the board. Requires the `image` feature.
+//!
+//! The layout is the same as in `tikz::export`: one circle per spot in a
+//! square picture, filled in the color of the piece on it.
+
+use crate::{Board, Cell, Player, Point};
+use image::{Rgb, RgbImage};
+use std::io;
+use std::path::Path;
+
+/// Share of a spot's radius that is drawn, so neighboring circles don't touch.
+const FILL: f64 = 0.85;
+
+const BACKGROUND: Rgb<u8> = Rgb([240, 240, 240]);
+const OUTLINE: Rgb<u8> = Rgb([0, 0, 0]);
+
+/// Settings for `Board::render_png`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PngOptions {
+    /// Width and height of the picture in pixels.
+    pub size: u32,
+    /// A spot to draw with a thicker outline, such as the last one moved to.
+    pub highlight: Option<Point>,
+}
+
+impl Default for PngOptions {
+    fn default() -> Self {
+        Self {
+            size: 600,
+            highlight: None,
+        }
+    }
+}
+
+fn color(cell: Cell) -> Rgb<u8> {
+    match cell.player() {
+        Some(Player::Head) => Rgb([255, 0, 0]),
+        Some(Player::Tail) => Rgb([0, 0, 255]),
+        Some(Player::LeftHand) => Rgb([0, 153, 0]),
+        Some(Player::RightHand) => Rgb([255, 128, 0]),
+        Some(Player::LeftFoot) => Rgb([128, 0, 128]),
+        Some(Player::RightFoot) => Rgb([255, 255, 0]),
+        None => Rgb([255, 255, 255]),
+    }
+}
+
+impl Board {
+    /// Draw the board as an image in memory.
+    pub fn to_image(&self, options: PngOptions) -> RgbImage {
+        let size = f64::from(options.size);
+        let radius = self.cell_radius() * size * FILL;
+        let mut image = RgbImage::from_pixel(options.size, options.size, BACKGROUND);
+        for (point, cell) in self.cells() {
+            let (x, y) = match self.cell_center(point) {
+                Some((x, y)) => (x * size, y * size),
+                None => continue,
+            };
+            let outline = if options.highlight == Some(point) {
+                (radius / 4.0).max(2.0)
+            } else {
+                1.0
+            };
+            draw_circle(&mut image, (x, y), radius, outline, color(cell));
+        }
+        image
+    }
+
+    /// Write the board to a PNG file, replacing any existing one.
+    pub fn render_png<P: AsRef<Path>>(&self, path: P, options: PngOptions) -> io::Result<()> {
+        self.to_image(options)
+            .save_with_format(path, image::ImageFormat::Png)
+            .map_err(io::Error::other)
+    }
+}
+
+/// Fill a circle and give it a black outline `outline` pixels wide.
+fn draw_circle(image: &mut RgbImage, (x, y): (f64, f64), radius: f64, outline: f64, fill: Rgb<u8>) {
+    let left = (x - radius).floor().max(0.0) as u32;
+    let top = (y - radius).floor().max(0.0) as u32;
+    let right = ((x + radius).ceil() as u32).min(image.width());
+    let bottom = ((y + radius).ceil() as u32).min(image.height());
+    for py in top..bottom {
+        for px in left..right {
+            // Measure from the middle of the pixel.
+            let distance = (f64::from(px) + 0.5 - x).hypot(f64::from(py) + 0.5 - y);
+            if distance <= radius - outline {
+                image.put_pixel(px, py, fill);
+            } else if distance <= radius {
+                image.put_pixel(px, py, OUTLINE);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_to_image() {
+        let board = Board::new(Config::default().with_player_lines(1));
+        let image = board.to_image(PngOptions {
+            size: 200,
+            highlight: None,
+        });
+        assert_eq!(image.dimensions(), (200, 200));
+        assert_eq!(*image.get_pixel(0, 0), BACKGROUND);
+
+        let pixel = |point| {
+            let (x, y) = board.cell_center(point).unwrap();
+            *image.get_pixel((x * 200.0) as u32, (y * 200.0) as u32)
+        };
+        assert_eq!(pixel(Point::new(1, 4)), color(Player::Head.into()));
+        assert_eq!(pixel(Point::new(3, 4)), color(Cell::Empty));
+        assert_eq!(pixel(Point::new(5, 4)), color(Player::Tail.into()));
+    }
+
+    #[test]
+    fn test_render_png() {
+        let path = std::env::temp_dir().join(format!("stelsalto-{}.png", std::process::id()));
+        Board::default()
+            .render_png(&path, PngOptions::default())
+            .unwrap();
+        let image = image::open(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!((image.width(), image.height()), (600, 600));
+    }
+}
@@ -0,0 +1,584 @@
+//! Game records for archiving and exchanging finished games, in the spirit
+//! of PGN for chess.
+//!
+//! A record starts with headers, one per line, then a blank line and the
+//! moves:
+//!
+//! ```text
+//! [Event "Club night"]
+//! [Date "2026-10-14"]
+//! [Result "1 2"]
+//! [Setup "1/11/111/1111/3333e5555/... 1 fill standard 2"]
+//! [Players "1 2"]
+//! [Head "Ada"]
+//! [Tail "Bob"]
+//!
+//! 1. 4/10-5/11
+//! {A quiet start.}
+//! 2. 14/16-13/15
+//! (2. 14/10-13/9 {Also fine.})
+//! resign 2
+//! ```
+//!
+//! `Setup` is the starting position as in `Board::to_fen`, which also
+//! carries the rules, and `Players` lists the seats in turn order by
+//! symbol. A header named after a player, such as `Head`, gives the name
+//! for their seat. Every other header is kept as it is. In the moves,
+//! turn numbers are optional and whitespace between entries does not
+//! matter, so a whole game can also be written on one line.
+//!
+//! Comments go in braces, with `\` before any `}` or `\` inside them, and
+//! whitespace around them is not kept. A variation in parentheses is
+//! another way that the game could have gone, starting with a turn in place
+//! of the last one before it, and can hold comments and variations of its
+//! own. Neither changes the game, so variations are not checked when the
+//! record is replayed. A comment right after a turn is kept as the
+//! `TurnRecord::comment` of that turn, the last one if there are several.
+//!
+//! Jump chains may only land on a spot twice when the variant in `Setup`
+//! includes `+revisit`. Records from before that rule existed can have
+//! such chains, and only load once `+revisit` is added to their setup.
+
+use crate::fen;
+use crate::PLAYERS;
+use crate::{Board, Game, GameError, GameState, Outcome, ParseError, Player, SeatInfo, Turn};
+use std::fmt;
+use std::str::FromStr;
+
+/// Something that happened in a game, in the order of the record.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Entry {
+    Turn(Turn),
+    Resign(Player),
+    Timeout(Player),
+    /// A note about the game at this point.
+    Comment(String),
+    /// Entries to play instead of the last turn before this.
+    Variation(Vec<Entry>),
+}
+
+impl Entry {
+    pub(crate) fn apply(&self, game: &mut Game) -> Result<(), GameError> {
+        match self {
+            Entry::Turn(turn) => game.play_turn(turn)?,
+            Entry::Resign(player) => {
+                game.resign(*player);
+            }
+            Entry::Timeout(player) => {
+                game.forfeit_on_timeout(*player);
+            }
+            Entry::Comment(text) => {
+                game.set_turn_comment(Some(text.clone()));
+            }
+            Entry::Variation(_) => {}
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameRecord {
+    /// Headers other than `Setup`, `Players`, and the seat names, such as
+    /// `Event` or `Date`, in the order that they are written.
+    pub headers: Vec<(String, String)>,
+    pub start: Board,
+    /// Everyone who started the game, in turn order, with their names.
+    pub players: Vec<(Player, String)>,
+    pub entries: Vec<Entry>,
+}
+
+impl GameRecord {
+    /// The record of a game so far. If it is over, a `Result` header lists
+    /// the standings by symbol, or says `draw`.
+    pub fn from_game(game: &Game) -> Self {
+        let result = game.result();
+        let mut left: Vec<_> = result
+            .placings
+            .iter()
+            .filter_map(|x| match (x.outcome, x.left_after) {
+                (Outcome::Resigned, Some(at)) => Some((at, Entry::Resign(x.player))),
+                (Outcome::TimedOut, Some(at)) => Some((at, Entry::Timeout(x.player))),
+                _ => None,
+            })
+            .collect();
+        // Placings list the players who left with the latest one first.
+        left.reverse();
+
+        let mut left = left.into_iter().peekable();
+        let mut entries = vec![];
+        for turns in 0..=game.history().len() {
+            while let Some((_, entry)) = left.next_if(|(at, _)| *at == turns) {
+                entries.push(entry);
+            }
+            if let Some(record) = game.history().get(turns) {
+                entries.push(Entry::Turn(record.turn.clone()));
+                if let Some(comment) = &record.comment {
+                    entries.push(Entry::Comment(comment.clone()));
+                }
+            }
+        }
+
+        let mut headers = vec![];
+        match result.state {
+            GameState::InProgress => {}
+            GameState::Drawn(_) => headers.push(("Result".to_string(), "draw".to_string())),
+            GameState::Finished => {
+                let order: Vec<String> = game
+                    .standings()
+                    .iter()
+                    .map(|x| fen::symbol(*x).to_string())
+                    .collect();
+                headers.push(("Result".to_string(), order.join(" ")));
+            }
+        }
+
+        Self {
+            headers,
+            start: game.position_after(0).expect("the start is always there"),
+            players: game
+                .seats()
+                .iter()
+                .map(|x| {
+                    (
+                        *x,
+                        game.seat_info(*x).map_or(String::new(), |x| x.name.clone()),
+                    )
+                })
+                .collect(),
+            entries,
+        }
+    }
+
+    /// Add a header, such as `Event` or `Date`, after the existing ones.
+    pub fn with_header(mut self, key: &str, value: &str) -> Self {
+        self.headers.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// The value of a header other than `Setup`, `Players`, or a name.
+    pub fn header(&self, key: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(x, _)| x == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Replay the record from its starting position. Fails at the first
+    /// turn that is not legal.
+    pub fn to_game(&self) -> Result<Game, GameError> {
+        let mut game = self.new_game();
+        for entry in &self.entries {
+            entry.apply(&mut game)?;
+        }
+        Ok(game)
+    }
+
+    /// The game before any of the entries, with the seats named.
+    pub(crate) fn new_game(&self) -> Game {
+        let players = self.players.iter().map(|(x, _)| *x).collect();
+        let mut game = Game::new(self.start.clone(), players);
+        for (player, name) in &self.players {
+            game.set_seat_info(*player, SeatInfo::new(*player).with_name(name));
+        }
+        game
+    }
+
+    /// Read a record written by `to_string`.
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let mut lines = text.lines().map(|x| x.trim());
+        let mut headers = vec![];
+        let mut start = None;
+        let mut players: Option<Vec<(Player, String)>> = None;
+        let mut names = vec![];
+        for line in lines.by_ref().skip_while(|x| x.is_empty()) {
+            if line.is_empty() {
+                break;
+            }
+            let (key, value) = parse_header(line)?;
+            match key {
+                "Setup" => start = Some(Board::from_fen(&value)?.0),
+                "Players" => {
+                    let seats = value
+                        .split_whitespace()
+                        .map(parse_player)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    players = Some(seats.into_iter().map(|x| (x, format!("{:?}", x))).collect());
+                }
+                _ => match PLAYERS.iter().find(|x| format!("{:?}", x) == key) {
+                    Some(player) => names.push((*player, value)),
+                    None => headers.push((key.to_string(), value)),
+                },
+            }
+        }
+        let (start, mut players) = match (start, players) {
+            (Some(start), Some(players)) => (start, players),
+            _ => return Err(ParseError::MissingHeader),
+        };
+        for (player, name) in names {
+            match players.iter_mut().find(|(x, _)| *x == player) {
+                Some(seat) => seat.1 = name,
+                None => return Err(ParseError::InvalidHeader),
+            }
+        }
+
+        let moves: Vec<&str> = lines.collect();
+        let mut tokens = tokenize(&moves.join("\n"))?.into_iter();
+        let entries = parse_entries(&mut tokens, false)?;
+
+        Ok(Self {
+            headers,
+            start,
+            players,
+            entries,
+        })
+    }
+}
+
+/// A piece of the moves in a record.
+enum Token {
+    Word(String),
+    Comment(String),
+    Open,
+    Close,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, ParseError> {
+    let mut tokens = vec![];
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' => {
+                let mut comment = String::new();
+                loop {
+                    match chars.next().ok_or(ParseError::InvalidAnnotation)? {
+                        '\\' => comment.push(chars.next().ok_or(ParseError::InvalidAnnotation)?),
+                        '}' => break,
+                        c => comment.push(c),
+                    }
+                }
+                tokens.push(Token::Comment(comment.trim().to_string()));
+            }
+            '}' => return Err(ParseError::InvalidAnnotation),
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            c if c.is_whitespace() => {}
+            c => {
+                let mut word = c.to_string();
+                while let Some(c) = chars.next_if(|x| !x.is_whitespace() && !"{}()".contains(*x)) {
+                    word.push(c);
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Read entries up to the end of the moves, or up to the `)` that closes
+/// the variation that they are `nested` in.
+fn parse_entries(
+    tokens: &mut impl Iterator<Item = Token>,
+    nested: bool,
+) -> Result<Vec<Entry>, ParseError> {
+    let mut entries = vec![];
+    while let Some(token) = tokens.next() {
+        match token {
+            Token::Comment(text) => entries.push(Entry::Comment(text)),
+            Token::Open => {
+                if !entries.iter().any(|x| matches!(x, Entry::Turn(_))) {
+                    return Err(ParseError::InvalidAnnotation);
+                }
+                entries.push(Entry::Variation(parse_entries(tokens, true)?));
+            }
+            Token::Close if nested => return Ok(entries),
+            Token::Close => return Err(ParseError::InvalidAnnotation),
+            Token::Word(word) => match word.as_str() {
+                "resign" | "timeout" => {
+                    let player = match tokens.next() {
+                        Some(Token::Word(symbol)) => parse_player(&symbol)?,
+                        _ => return Err(ParseError::UnknownSymbol),
+                    };
+                    entries.push(match word.as_str() {
+                        "resign" => Entry::Resign(player),
+                        _ => Entry::Timeout(player),
+                    });
+                }
+                _ if is_turn_number(&word) => {}
+                _ => entries.push(Entry::Turn(word.parse()?)),
+            },
+        }
+    }
+    match nested {
+        true => Err(ParseError::InvalidAnnotation),
+        false => Ok(entries),
+    }
+}
+
+/// How each entry is written, with turns numbered on from `number`.
+fn entry_texts(entries: &[Entry], mut number: usize) -> Vec<String> {
+    let mut texts = vec![];
+    for entry in entries {
+        texts.push(match entry {
+            Entry::Turn(turn) => {
+                number += 1;
+                format!("{}. {}", number, turn)
+            }
+            Entry::Resign(player) => format!("resign {}", fen::symbol(*player)),
+            Entry::Timeout(player) => format!("timeout {}", fen::symbol(*player)),
+            Entry::Comment(text) => {
+                let text = text.replace('\\', "\\\\").replace('}', "\\}");
+                format!("{{{}}}", text)
+            }
+            Entry::Variation(entries) => {
+                let texts = entry_texts(entries, number.saturating_sub(1));
+                format!("({})", texts.join(" "))
+            }
+        });
+    }
+    texts
+}
+
+fn parse_player(text: &str) -> Result<Player, ParseError> {
+    let mut chars = text.chars();
+    match (chars.next().and_then(fen::player), chars.next()) {
+        (Some(player), None) => Ok(player),
+        _ => Err(ParseError::UnknownSymbol),
+    }
+}
+
+fn is_turn_number(word: &str) -> bool {
+    word.strip_suffix('.')
+        .is_some_and(|x| !x.is_empty() && x.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Split `[Key "value"]` into its key and unescaped value.
+fn parse_header(line: &str) -> Result<(&str, String), ParseError> {
+    let inner = line
+        .strip_prefix('[')
+        .and_then(|x| x.strip_suffix(']'))
+        .ok_or(ParseError::InvalidHeader)?;
+    let (key, value) = inner.split_once(' ').ok_or(ParseError::InvalidHeader)?;
+    let value = value
+        .trim()
+        .strip_prefix('"')
+        .and_then(|x| x.strip_suffix('"'))
+        .ok_or(ParseError::InvalidHeader)?;
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.push(chars.next().ok_or(ParseError::InvalidHeader)?),
+            '"' => return Err(ParseError::InvalidHeader),
+            c => unescaped.push(c),
+        }
+    }
+    Ok((key, unescaped))
+}
+
+fn write_header(f: &mut fmt::Formatter, key: &str, value: &str) -> fmt::Result {
+    let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+    writeln!(f, "[{} \"{}\"]", key, value)
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (key, value) in &self.headers {
+            write_header(f, key, value)?;
+        }
+        write_header(f, "Setup", &self.start.to_fen(None))?;
+        let symbols: Vec<String> = self
+            .players
+            .iter()
+            .map(|(x, _)| fen::symbol(*x).to_string())
+            .collect();
+        write_header(f, "Players", &symbols.join(" "))?;
+        for (player, name) in &self.players {
+            write_header(f, &format!("{:?}", player), name)?;
+        }
+        writeln!(f)?;
+
+        for text in entry_texts(&self.entries, 0) {
+            writeln!(f, "{}", text)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for GameRecord {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game() -> Game {
+        let mut game = Game::new(
+            Board::default(),
+            vec![Player::Head, Player::Tail, Player::LeftHand],
+        );
+        game.set_seat_info(
+            Player::Head,
+            SeatInfo::new(Player::Head).with_name("Ada \"the Countess\""),
+        );
+        game.play_turn(&"4/10-5/11".parse().unwrap()).unwrap();
+        game.resign(Player::LeftHand);
+        game.play_turn(&"14/16-13/15".parse().unwrap()).unwrap();
+        game
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let game = game();
+        let record = GameRecord::from_game(&game).with_header("Date", "2026-10-14");
+        let text = record.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "[Date \"2026-10-14\"]");
+        assert_eq!(lines[2], "[Players \"1 2 3\"]");
+        assert_eq!(lines[3], "[Head \"Ada \\\"the Countess\\\"\"]");
+        assert_eq!(
+            &lines[6..],
+            &["", "1. 4/10-5/11", "resign 3", "2. 14/16-13/15"]
+        );
+
+        let parsed = GameRecord::parse(&text).unwrap();
+        assert_eq!(parsed, record);
+        assert_eq!(parsed.header("Date"), Some("2026-10-14"));
+        assert_eq!(parsed.to_game().unwrap(), game);
+    }
+
+    #[test]
+    fn test_round_trip_with_annotations() {
+        let game = game();
+        let mut record = GameRecord::from_game(&game);
+        record
+            .entries
+            .insert(1, Entry::Comment(String::from("Opens {up}")));
+        record.entries.push(Entry::Variation(vec![
+            Entry::Turn("14/10-13/9".parse().unwrap()),
+            Entry::Comment(String::from("Back\\slash")),
+            Entry::Turn("5/11-6/12".parse().unwrap()),
+            Entry::Variation(vec![Entry::Turn("4/12-5/13".parse().unwrap())]),
+        ]));
+        let text = record.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            &lines[5..],
+            &[
+                "",
+                "1. 4/10-5/11",
+                "{Opens {up\\}}",
+                "resign 3",
+                "2. 14/16-13/15",
+                "(2. 14/10-13/9 {Back\\\\slash} 3. 5/11-6/12 (3. 4/12-5/13))"
+            ]
+        );
+
+        let parsed = GameRecord::parse(&text).unwrap();
+        assert_eq!(parsed, record);
+        let replayed = parsed.to_game().unwrap();
+        assert_eq!(replayed.board, game.board);
+        assert_eq!(replayed.history()[0].comment.as_deref(), Some("Opens {up}"));
+    }
+
+    #[test]
+    fn test_turn_comments() {
+        let mut game = game();
+        game.set_turn_comment(Some(String::from("Mirrors Head")));
+        let record = GameRecord::from_game(&game);
+        assert_eq!(
+            record.entries.last(),
+            Some(&Entry::Comment(String::from("Mirrors Head")))
+        );
+        assert_eq!(record.to_game().unwrap(), game);
+    }
+
+    #[test]
+    fn test_parse_annotations_loosely() {
+        let text = format!(
+            "[Setup \"{}\"]\n[Players \"1 2\"]\n\n4/10-5/11 {{ Spread\nover lines }}(4/12-5/13)",
+            Board::default().to_fen(None)
+        );
+        let record = GameRecord::parse(&text).unwrap();
+        assert_eq!(
+            record.entries,
+            vec![
+                Entry::Turn("4/10-5/11".parse().unwrap()),
+                Entry::Comment(String::from("Spread\nover lines")),
+                Entry::Variation(vec![Entry::Turn("4/12-5/13".parse().unwrap())]),
+            ]
+        );
+
+        let setup = format!(
+            "[Setup \"{}\"]\n[Players \"1 2\"]\n\n",
+            Board::default().to_fen(None)
+        );
+        for moves in [
+            "1. 4/10-5/11 {unclosed",
+            "1. 4/10-5/11 (2. 14/16-13/15",
+            "1. 4/10-5/11 )",
+            "1. 4/10-5/11 }",
+            "{first} (1. 4/10-5/11)",
+        ] {
+            assert_eq!(
+                GameRecord::parse(&format!("{}{}", setup, moves)),
+                Err(ParseError::InvalidAnnotation),
+                "{}",
+                moves
+            );
+        }
+    }
+
+    #[test]
+    fn test_result_header() {
+        let mut game = game();
+        assert_eq!(GameRecord::from_game(&game).header("Result"), None);
+        game.resign(Player::Head);
+        assert_eq!(GameRecord::from_game(&game).header("Result"), Some("2"));
+    }
+
+    #[test]
+    fn test_parse_moves_on_one_line() {
+        let text = format!(
+            "[Setup \"{}\"]\n[Players \"1 2\"]\n\n1. 4/10-5/11 2. 14/16-13/15 timeout 1\n",
+            Board::default().to_fen(None)
+        );
+        let record: GameRecord = text.parse().unwrap();
+        assert_eq!(record.players[0], (Player::Head, String::from("Head")));
+        assert_eq!(record.entries.len(), 3);
+        assert_eq!(record.entries[2], Entry::Timeout(Player::Head));
+        let game = record.to_game().unwrap();
+        assert_eq!(game.state(), GameState::Finished);
+    }
+
+    #[test]
+    fn test_parse_with_errors() {
+        let setup = format!("[Setup \"{}\"]", Board::default().to_fen(None));
+        assert_eq!(
+            GameRecord::parse("[Players \"1 2\"]\n\n1. 4/10-5/11"),
+            Err(ParseError::MissingHeader)
+        );
+        assert_eq!(
+            GameRecord::parse(&format!("{}\n[Players 1 2]\n", setup)),
+            Err(ParseError::InvalidHeader)
+        );
+        assert_eq!(
+            GameRecord::parse(&format!(
+                "{}\n[Players \"1 2\"]\n[LeftHand \"Cy\"]\n",
+                setup
+            )),
+            Err(ParseError::InvalidHeader)
+        );
+        assert_eq!(
+            GameRecord::parse(&format!("{}\n[Players \"1 2\"]\n\n1. 4/10~5/11", setup)),
+            Err(ParseError::InvalidPoint)
+        );
+
+        let record = GameRecord::parse(&format!("{}\n[Players \"1 2\"]\n\n1/13-2/12", setup));
+        assert!(record.unwrap().to_game().is_err());
+    }
+}
@@ -0,0 +1,185 @@
+//! Stepping through a recorded game one turn at a time, for playback
+//! viewers and analysis tools.
+//!
+//! A `Replay` checks the whole record up front, so moving around in it
+//! afterwards cannot fail. Resignations and timeouts are folded into the
+//! position before the next turn, the same way as `Game::position_after`.
+
+use crate::record::{Entry, GameRecord};
+use crate::{Board, Game, GameError, Snapshot, TurnRecord};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Replay {
+    /// The game as it stands at the end of the record.
+    game: Game,
+    /// The position after each number of turns, starting from 0.
+    positions: Vec<Snapshot>,
+    board: Board,
+    ply: usize,
+}
+
+impl Replay {
+    /// Play through a record, starting the replay at its first position.
+    /// Fails at the first turn that is not legal.
+    pub fn new(record: &GameRecord) -> Result<Self, GameError> {
+        let mut game = record.new_game();
+        let mut positions = vec![];
+        for entry in &record.entries {
+            if let Entry::Turn(_) = entry {
+                positions.push(game.board.snapshot());
+            }
+            entry.apply(&mut game)?;
+        }
+        positions.push(game.board.snapshot());
+
+        Ok(Self {
+            board: record.start.clone(),
+            game,
+            positions,
+            ply: 0,
+        })
+    }
+
+    /// The whole game, as it was at the end of the record.
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    /// The position after the turns played so far.
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// How many turns have been played so far.
+    pub fn ply(&self) -> usize {
+        self.ply
+    }
+
+    /// How many turns there are in the record.
+    pub fn len(&self) -> usize {
+        self.positions.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The turn that led to the current position, if any.
+    pub fn last_turn(&self) -> Option<&TurnRecord> {
+        self.ply.checked_sub(1).map(|x| &self.game.history()[x])
+    }
+
+    /// Play the next turn. Returns it, or `None` at the end of the record.
+    pub fn step_forward(&mut self) -> Option<&TurnRecord> {
+        if self.ply == self.len() {
+            return None;
+        }
+        self.seek(self.ply + 1);
+        self.last_turn()
+    }
+
+    /// Take back the last turn. Returns it, or `None` at the start.
+    pub fn step_back(&mut self) -> Option<&TurnRecord> {
+        if self.ply == 0 {
+            return None;
+        }
+        self.ply -= 1;
+        self.show();
+        self.game.history().get(self.ply)
+    }
+
+    /// Jump to the position after a number of turns, where 0 is the start.
+    /// Returns false and stays put if the record is not that long.
+    pub fn seek(&mut self, ply: usize) -> bool {
+        if ply > self.len() {
+            return false;
+        }
+        self.ply = ply;
+        self.show();
+        true
+    }
+
+    /// The position after each number of turns, from the start to the end
+    /// of the record, regardless of where the replay is.
+    pub fn positions(&self) -> impl Iterator<Item = Board> + '_ {
+        self.positions.iter().map(move |x| {
+            let mut board = self.board.clone();
+            board
+                .restore(x)
+                .expect("positions come from the same board");
+            board
+        })
+    }
+
+    fn show(&mut self) {
+        self.board
+            .restore(&self.positions[self.ply])
+            .expect("positions come from the same board");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, ForfeitRule, Player};
+
+    fn record() -> GameRecord {
+        let board = Board::new(Config::default().with_forfeit_rule(ForfeitRule::Clear));
+        let mut game = Game::new(board, vec![Player::Head, Player::Tail]);
+        game.play_turn(&"4/10-5/11".parse().unwrap()).unwrap();
+        game.play_turn(&"14/16-13/15".parse().unwrap()).unwrap();
+        game.play_turn(&"4/12-5/13".parse().unwrap()).unwrap();
+        game.resign(Player::Tail);
+        GameRecord::from_game(&game)
+    }
+
+    #[test]
+    fn test_stepping() {
+        let record = record();
+        let game = record.to_game().unwrap();
+        let mut replay = Replay::new(&record).unwrap();
+        assert_eq!(replay.len(), 3);
+        assert_eq!(replay.board(), &record.start);
+        assert_eq!(replay.last_turn(), None);
+        assert_eq!(replay.step_back(), None);
+
+        let turn = replay.step_forward().unwrap().clone();
+        assert_eq!(turn, game.history()[0]);
+        assert_eq!(replay.last_turn(), Some(&turn));
+        assert_eq!(replay.board(), &game.position_after(1).unwrap());
+
+        assert!(replay.seek(3));
+        assert_eq!(replay.board(), &game.board);
+        assert_eq!(replay.step_forward(), None);
+        assert_eq!(replay.step_back(), Some(&game.history()[2]));
+        assert_eq!(replay.ply(), 2);
+        assert_eq!(replay.board(), &game.position_after(2).unwrap());
+
+        assert!(!replay.seek(4));
+        assert_eq!(replay.ply(), 2);
+        assert!(replay.seek(0));
+        assert_eq!(replay.board(), &record.start);
+    }
+
+    #[test]
+    fn test_positions() {
+        let record = record();
+        let game = record.to_game().unwrap();
+        let replay = Replay::new(&record).unwrap();
+        let positions: Vec<Board> = replay.positions().collect();
+        assert_eq!(positions.len(), 4);
+        for (turns, board) in positions.iter().enumerate() {
+            assert_eq!(board, &game.position_after(turns).unwrap());
+        }
+        assert_eq!(positions[3].pieces_of(Player::Tail).count(), 0);
+    }
+
+    #[test]
+    fn test_illegal_record() {
+        let mut record = record();
+        record
+            .entries
+            .push(Entry::Turn("1/13-2/12".parse().unwrap()));
+        assert!(Replay::new(&record).is_err());
+    }
+}
@@ -0,0 +1,467 @@
+//! Game files, so interrupted games can be resumed.
+//!
+//! A game file is plain text. The first line names the format and its
+//! version, and the rest are records, one per line:
+//!
+//! ```text
+//! stelsalto 5
+//! start <position as in Board::to_fen>
+//! config lines 2 3
+//! config goal blockers
+//! config repetition 3
+//! players 1 2 3
+//! seat 1 human - Head
+//! seat 2 bot 1500 Ada
+//! seat 3 human - LeftHand
+//! turn 4/10-5/11
+//! resign 2
+//! timeout 3
+//! ```
+//!
+//! Each `seat` record gives the controller, rating (or `-`), and name of a
+//! player in `SeatInfo`. Turns, resignations, and players who ran out of
+//! time are listed in the order that they happened, and loading replays
+//! them from the starting position.
+//!
+//! The `config` records cover the settings that `Board::to_fen` leaves
+//! out, and only the ones that differ from `Config::default` are written:
+//!
+//! * `lines <player> <lines>` for `Config::with_corner_lines`.
+//! * `start <player> <corner>` and `target <player> <corner>`.
+//! * `goal <own|blockers|occupied>`.
+//! * `repetition <times>` and `progress <turns>` for the draw limits.
+//! * `seed <seed>`.
+//! * `colors <plain|ansi>`, `labels`, and `symbol <player|empty> <symbol>`
+//!   for how the board is drawn.
+//!
+//! Files from version 1, which had no seat records, version 2, which had
+//! no timeouts, and version 4, which kept only the settings in the start
+//! position, still load. Jump chains could land on a spot twice before
+//! version 4, so older files load with `Config::with_revisits`.
+
+use crate::fen;
+use crate::{
+    Board, Cell, ColorMode, Config, Controller, Game, GoalRule, Outcome, Player, SeatInfo,
+    SymbolTheme, Turn, PLAYERS,
+};
+use std::error::Error;
+use std::io;
+use std::path::Path;
+
+const HEADER: &str = "stelsalto";
+const VERSION: u32 = 5;
+
+fn invalid(error: impl Into<Box<dyn Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn parse_player(text: &str) -> io::Result<Player> {
+    let mut chars = text.chars();
+    match (chars.next().and_then(fen::player), chars.next()) {
+        (Some(player), None) => Ok(player),
+        _ => Err(invalid(format!("unknown player: {}", text))),
+    }
+}
+
+fn parse_seat(text: &str) -> io::Result<(Player, SeatInfo)> {
+    let words: Vec<&str> = text.splitn(4, ' ').collect();
+    if words.len() < 3 {
+        return Err(invalid(format!("incomplete seat: {}", text)));
+    }
+    let player = parse_player(words[0])?;
+    let controller = match words[1] {
+        "human" => Controller::Human,
+        "bot" => Controller::Bot,
+        other => return Err(invalid(format!("unknown controller: {}", other))),
+    };
+    let rating = match words[2] {
+        "-" => None,
+        rating => Some(rating.parse().map_err(invalid)?),
+    };
+    Ok((
+        player,
+        SeatInfo {
+            name: words.get(3).unwrap_or(&"").to_string(),
+            rating,
+            controller,
+        },
+    ))
+}
+
+fn parse_number<T: std::str::FromStr>(text: &str) -> io::Result<T> {
+    text.parse()
+        .map_err(|_| invalid(format!("not a number: {}", text)))
+}
+
+/// The `config` records for the settings that differ from the default.
+fn config_records(config: &Config) -> Vec<String> {
+    let mut records = vec![];
+    for player in PLAYERS.iter() {
+        if let Some(lines) = config.corner_lines.get(player) {
+            records.push(format!("lines {} {}", fen::symbol(*player), lines));
+        }
+    }
+    for (kind, corners) in [("start", &config.starts), ("target", &config.targets)] {
+        for player in PLAYERS.iter() {
+            if let Some(corner) = corners.get(player) {
+                records.push(format!(
+                    "{} {} {}",
+                    kind,
+                    fen::symbol(*player),
+                    fen::symbol(*corner)
+                ));
+            }
+        }
+    }
+    match config.goal_rule {
+        GoalRule::OwnPieces => {}
+        GoalRule::HomeBlockers => records.push(String::from("goal blockers")),
+        GoalRule::AnyOccupant => records.push(String::from("goal occupied")),
+    }
+    if let Some(times) = config.repetition_limit {
+        records.push(format!("repetition {}", times));
+    }
+    if let Some(turns) = config.progress_limit {
+        records.push(format!("progress {}", turns));
+    }
+    if let Some(seed) = config.seed {
+        records.push(format!("seed {}", seed));
+    }
+    if config.color_mode == ColorMode::Ansi {
+        records.push(String::from("colors ansi"));
+    }
+    if config.labels {
+        records.push(String::from("labels"));
+    }
+    let defaults = SymbolTheme::Ascii.symbols();
+    let cells = PLAYERS
+        .iter()
+        .map(|x| (Cell::Occupied(*x), fen::symbol(*x).to_string()))
+        .chain(std::iter::once((Cell::Empty, String::from("empty"))));
+    for (cell, name) in cells {
+        let symbol = config.symbol(cell);
+        if defaults.get(&cell).map(|x| x.as_str()) != Some(symbol) {
+            records.push(format!("symbol {} {}", name, symbol));
+        }
+    }
+    records
+        .into_iter()
+        .map(|x| format!("config {}", x))
+        .collect()
+}
+
+/// Apply one `config` record, without the leading `config`.
+fn apply_config(config: &mut Config, text: &str) -> io::Result<()> {
+    let words: Vec<&str> = text.split_whitespace().collect();
+    match words.as_slice() {
+        ["lines", player, lines] => {
+            config
+                .corner_lines
+                .insert(parse_player(player)?, parse_number(lines)?);
+        }
+        ["start", player, corner] => {
+            config
+                .starts
+                .insert(parse_player(player)?, parse_player(corner)?);
+        }
+        ["target", player, corner] => {
+            config
+                .targets
+                .insert(parse_player(player)?, parse_player(corner)?);
+        }
+        ["goal", rule] => {
+            config.goal_rule = match *rule {
+                "own" => GoalRule::OwnPieces,
+                "blockers" => GoalRule::HomeBlockers,
+                "occupied" => GoalRule::AnyOccupant,
+                _ => return Err(invalid(format!("unknown goal rule: {}", rule))),
+            }
+        }
+        ["repetition", times] => config.repetition_limit = Some(parse_number(times)?),
+        ["progress", turns] => config.progress_limit = Some(parse_number(turns)?),
+        ["seed", seed] => config.seed = Some(parse_number(seed)?),
+        ["colors", mode] => {
+            config.color_mode = match *mode {
+                "plain" => ColorMode::Plain,
+                "ansi" => ColorMode::Ansi,
+                _ => return Err(invalid(format!("unknown color mode: {}", mode))),
+            }
+        }
+        ["labels"] => config.labels = true,
+        ["symbol", cell, symbol] => {
+            let cell = match *cell {
+                "empty" => Cell::Empty,
+                player => Cell::Occupied(parse_player(player)?),
+            };
+            config.symbols.insert(cell, symbol.to_string());
+        }
+        _ => return Err(invalid(format!("unknown setting: {}", text))),
+    }
+    Ok(())
+}
+
+impl Game {
+    fn to_record(&self) -> String {
+        let players: Vec<String> = self
+            .seats
+            .iter()
+            .map(|x| fen::symbol(*x).to_string())
+            .collect();
+        let mut lines = vec![
+            format!("{} {}", HEADER, VERSION),
+            format!("start {}", self.start.to_fen(self.seats.first().copied())),
+        ];
+        lines.extend(config_records(self.start.config()));
+        lines.push(format!("players {}", players.join(" ")));
+        for (player, info) in self.seats.iter().zip(&self.seat_info) {
+            let controller = match info.controller {
+                Controller::Human => "human",
+                Controller::Bot => "bot",
+            };
+            let rating = info.rating.map_or(String::from("-"), |x| x.to_string());
+            lines.push(format!(
+                "seat {} {} {} {}",
+                fen::symbol(*player),
+                controller,
+                rating,
+                info.name
+            ));
+        }
+        let mut resignations = self.resignations.iter().peekable();
+        for turns in 0..=self.history.len() {
+            while let Some((_, player, outcome)) = resignations.next_if(|x| x.0 == turns) {
+                let kind = match outcome {
+                    Outcome::TimedOut => "timeout",
+                    _ => "resign",
+                };
+                lines.push(format!("{} {}", kind, fen::symbol(*player)));
+            }
+            if let Some(record) = self.history.get(turns) {
+                lines.push(format!("turn {}", record.turn));
+            }
+        }
+        lines.join("\n") + "\n"
+    }
+
+    fn from_record(text: &str) -> io::Result<Self> {
+        let mut lines = text.lines().filter(|x| !x.trim().is_empty());
+        let version = match lines
+            .next()
+            .map(|x| x.split_whitespace().collect::<Vec<_>>())
+        {
+            Some(ref words) if words.len() == 2 && words[0] == HEADER => {
+                match words[1].parse::<u32>() {
+                    Ok(version @ 1..=VERSION) => version,
+                    _ => return Err(invalid(format!("unsupported version: {}", words[1]))),
+                }
+            }
+            _ => return Err(invalid("not a game file")),
+        };
+
+        let mut start = None;
+        let mut game: Option<Game> = None;
+        for line in lines {
+            let line = line.trim();
+            let (kind, rest) = match line.find(' ') {
+                Some(index) => (&line[..index], line[index + 1..].trim()),
+                None => (line, ""),
+            };
+            match (kind, &mut game) {
+                ("start", None) => {
+                    let mut board = Board::from_fen(rest).map_err(invalid)?.0;
+                    if version < 4 {
+                        board.config.revisits = true;
+                    }
+                    start = Some(board);
+                }
+                ("config", None) => {
+                    let board = start
+                        .as_mut()
+                        .ok_or_else(|| invalid("config before start"))?;
+                    apply_config(&mut board.config, rest)?;
+                }
+                ("players", None) => {
+                    let board = start
+                        .take()
+                        .ok_or_else(|| invalid("players before start"))?;
+                    let players = rest
+                        .split_whitespace()
+                        .map(parse_player)
+                        .collect::<io::Result<Vec<Player>>>()?;
+                    game = Some(Game::new(board, players));
+                }
+                ("seat", Some(game)) => {
+                    let (player, info) = parse_seat(rest)?;
+                    if !game.set_seat_info(player, info) {
+                        return Err(invalid(format!(
+                            "seat for a player not in the game: {}",
+                            line
+                        )));
+                    }
+                }
+                ("turn", Some(game)) => {
+                    let turn: Turn = rest.parse().map_err(invalid)?;
+                    game.play_turn(&turn).map_err(invalid)?;
+                }
+                ("resign", Some(game)) => {
+                    game.resign(parse_player(rest)?);
+                }
+                ("timeout", Some(game)) => {
+                    game.forfeit_on_timeout(parse_player(rest)?);
+                }
+                _ => return Err(invalid(format!("unexpected line: {}", line))),
+            }
+        }
+        game.ok_or_else(|| invalid("missing players"))
+    }
+
+    /// Write the game to a file, replacing any existing one.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        std::fs::write(path, self.to_record())
+    }
+
+    /// Read a game written by `save`, in the state that it was saved in.
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Self::from_record(&std::fs::read_to_string(path)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ForfeitRule, Point, StartLayout};
+
+    fn game() -> Game {
+        let config = Config::default().with_layout(StartLayout::ThreePlayers);
+        let players = config.layout().players();
+        let mut game = Game::new(Board::new(config), players);
+        game.set_seat_info(
+            Player::RightFoot,
+            SeatInfo::new(Player::RightFoot)
+                .with_name("Ada Lovelace")
+                .with_rating(1500)
+                .with_controller(Controller::Bot),
+        );
+        game.play_turn(&"4/10-5/11".parse().unwrap()).unwrap();
+        game.resign(Player::LeftFoot);
+        game.play_turn(&"10/22-9/21".parse().unwrap()).unwrap();
+        game.forfeit_on_timeout(Player::Head);
+        game
+    }
+
+    #[test]
+    fn test_record_round_trip() {
+        let game = game();
+        let text = game.to_record();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0], "stelsalto 5");
+        assert_eq!(lines[2], "players 1 4 6");
+        assert_eq!(
+            &lines[3..6],
+            &[
+                "seat 1 human - Head",
+                "seat 4 bot 1500 Ada Lovelace",
+                "seat 6 human - LeftFoot",
+            ]
+        );
+        assert_eq!(
+            &lines[6..],
+            &["turn 4/10-5/11", "resign 6", "turn 10/22-9/21", "timeout 1"]
+        );
+        assert_eq!(Game::from_record(&text).unwrap(), game);
+    }
+
+    #[test]
+    fn test_record_round_trip_with_cleared_pieces() {
+        let config = Config::default()
+            .with_layout(StartLayout::ThreePlayers)
+            .with_forfeit_rule(ForfeitRule::Clear);
+        let players = config.layout().players();
+        let mut game = Game::new(Board::new(config), players);
+        game.play_turn(&"4/10-5/11".parse().unwrap()).unwrap();
+        game.resign(Player::RightFoot);
+        assert_eq!(game.board.pieces_of(Player::RightFoot).count(), 0);
+        let loaded = Game::from_record(&game.to_record()).unwrap();
+        assert_eq!(loaded, game);
+    }
+
+    #[test]
+    fn test_record_round_trip_with_config() {
+        let config = Config::quick()
+            .with_layout(StartLayout::TwoPlayers)
+            .with_corner_lines(Player::Tail, 2)
+            .with_start(Player::Head, Player::Tail)
+            .with_start(Player::Tail, Player::Head)
+            .with_goal_rule(GoalRule::HomeBlockers)
+            .with_repetition_limit(3)
+            .with_progress_limit(100)
+            .with_seed(7)
+            .with_color_mode(ColorMode::Ansi)
+            .with_labels(true)
+            .with_symbol_theme(SymbolTheme::Unicode);
+        let mut game = Game::new(Board::new(config.clone()), vec![Player::Head, Player::Tail]);
+        game.play_turn(&"15/15-14/16".parse().unwrap()).unwrap();
+        let text = game.to_record();
+        assert!(text.contains("\nconfig lines 2 2\nconfig lines 3 3\n"));
+        assert!(text.contains("\nconfig repetition 3\nconfig progress 100\n"));
+        let loaded = Game::from_record(&text).unwrap();
+        assert_eq!(loaded.board.config(), &config);
+        assert_eq!(loaded, game);
+    }
+
+    #[test]
+    fn test_from_record_version_1() {
+        let text = game().to_record().replace("stelsalto 5", "stelsalto 1");
+        let text: Vec<&str> = text.lines().filter(|x| !x.starts_with("seat")).collect();
+        let game = Game::from_record(&text.join("\n")).unwrap();
+        assert_eq!(
+            game.seat_info(Player::RightFoot),
+            Some(&SeatInfo::new(Player::RightFoot))
+        );
+        assert_eq!(game.history().len(), 2);
+    }
+
+    #[test]
+    fn test_from_record_version_3_with_revisit() {
+        let setup = [
+            (Point::new(9, 13), Player::Head),
+            (Point::new(10, 14), Player::Tail),
+        ];
+        let board = Board::from_setup(&setup, Config::default()).unwrap();
+        let game = Game::new(board, vec![Player::Head, Player::Tail]);
+        let text = game.to_record() + "turn 9/13>11/15>9/13\n";
+        assert!(Game::from_record(&text).is_err());
+        let text = text.replace("stelsalto 5", "stelsalto 3");
+        let loaded = Game::from_record(&text).unwrap();
+        assert!(loaded.board.config().revisits());
+        assert_eq!(loaded.history().len(), 1);
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let path = std::env::temp_dir().join(format!("stelsalto-{}.game", std::process::id()));
+        let game = game();
+        game.save(&path).unwrap();
+        let loaded = Game::load(&path);
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded.unwrap(), game);
+    }
+
+    #[test]
+    fn test_from_record_with_errors() {
+        assert!(Game::from_record("").is_err());
+        assert!(Game::from_record("stelsalto 5\n").is_err());
+        let text = game()
+            .to_record()
+            .replace("players", "config goal nobody\nplayers");
+        assert!(Game::from_record(&text).is_err());
+        let text = game()
+            .to_record()
+            .replace("turn 10/22-9/21", "turn 10/22-9/23");
+        assert!(Game::from_record(&text).is_err());
+        let text = game().to_record().replace("seat 4 bot", "seat 4 robot");
+        assert!(Game::from_record(&text).is_err());
+        let text = game().to_record().replace("seat 4", "seat 5");
+        assert!(Game::from_record(&text).is_err());
+    }
+}
@@ -0,0 +1,380 @@
+//! Simultaneous exhibitions, where one computer player takes on several
+//! opponents at once, each on a board of their own, built with the `net`
+//! feature.
+//!
+//! Every board is a `net::Host` with the exhibitor in its first seat, and
+//! opponents speak the same protocol as for a single game. `join` seats a
+//! client on the first board with a free seat, and `rejoin` takes them
+//! back to the board that the token is for. After that, what a client
+//! sends goes to their board, and they only hear about that board, so
+//! `net::Client` works unchanged. The exhibitor declines takebacks.
+//!
+//! The exhibitor goes around the boards in order with `Simul::play_next`,
+//! moving on each one where it is their turn. The think time is shared
+//! between every board that is waiting for the exhibitor, so opponents do
+//! not wait much longer as more of them move, and with a clock, it never
+//! takes more than a tenth of the time left on that board. Opponents can
+//! be local, by calling `Simul::handle` directly, or connect over TCP with
+//! `serve`.
+
+use crate::ai::{AlphaBeta, Bot, ThinkBudget};
+use crate::clock::TimeControl;
+use crate::net::{self, ClientId, Event, Host, Message};
+use crate::{Config, GameState, Player};
+use std::collections::HashMap;
+use std::io;
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// The client that the exhibitor plays as on every board.
+const EXHIBITOR: ClientId = ClientId::MAX;
+
+/// Longest that the exhibitor thinks per round of the boards by default.
+const THINK_TIME: Duration = Duration::from_secs(2);
+
+/// Part of the time left on the clock that one turn may take at most.
+const CLOCK_SHARE: u32 = 10;
+
+#[derive(Debug)]
+pub struct Simul {
+    boards: Vec<Host>,
+    /// The exhibitor's seat on every board.
+    seat: Player,
+    engine: AlphaBeta,
+    think_time: Duration,
+    /// The board to look at first for the exhibitor's next turn.
+    next: usize,
+    /// The board that each connected client plays on.
+    clients: HashMap<ClientId, usize>,
+    /// The board that each seat's token is for, for `rejoin`.
+    tokens: HashMap<String, usize>,
+}
+
+impl Simul {
+    /// Set up `boards` boards for `config`, with the exhibitor seated as
+    /// `name` on each.
+    pub fn new(config: Config, boards: usize, name: &str) -> Self {
+        let players = config.layout().players();
+        let boards = (0..boards)
+            .map(|_| {
+                let mut host = Host::new(crate::Board::new(config.clone()), players.clone());
+                host.handle(EXHIBITOR, &format!("join {}", name));
+                host
+            })
+            .collect();
+        Self {
+            boards,
+            seat: players[0],
+            engine: AlphaBeta::default(),
+            think_time: THINK_TIME,
+            next: 0,
+            clients: HashMap::new(),
+            tokens: HashMap::new(),
+        }
+    }
+
+    /// Think for up to this long per round of the boards.
+    pub fn with_think_time(mut self, time: Duration) -> Self {
+        self.think_time = time;
+        self
+    }
+
+    /// Put every board on a clock.
+    pub fn with_time_control(mut self, control: TimeControl) -> Self {
+        self.boards = self
+            .boards
+            .into_iter()
+            .map(|x| x.with_time_control(control))
+            .collect();
+        self
+    }
+
+    pub fn boards(&self) -> &[Host] {
+        &self.boards
+    }
+
+    /// The board that a client plays on.
+    pub fn board_of(&self, client: ClientId) -> Option<usize> {
+        self.clients.get(&client).copied()
+    }
+
+    /// Whether every game is over.
+    pub fn is_over(&self) -> bool {
+        self.boards
+            .iter()
+            .all(|x| x.game().state() != GameState::InProgress)
+    }
+
+    /// Carry out one command from a client and give back what to send.
+    pub fn handle(&mut self, client: ClientId, line: &str) -> Vec<Message> {
+        let line = line.trim();
+        let index = match self.board_of(client) {
+            Some(index) => index,
+            None => match self.seat_client(client, line) {
+                Ok(index) => index,
+                Err(e) => return vec![Message::to(client, format!("error {}", e))],
+            },
+        };
+        let mut messages = self.boards[index].handle(client, line);
+        if self.boards[index].player_of(client).is_none() {
+            // The client did not get a seat after all.
+            self.clients.remove(&client);
+        }
+        for message in &messages {
+            if let Some(token) = message.text.strip_prefix("welcome ") {
+                if let Some((_, token)) = token.split_once(' ') {
+                    self.tokens.insert(token.to_string(), index);
+                }
+            }
+        }
+        if line == "takeback" {
+            messages.extend(self.boards[index].handle(EXHIBITOR, "decline"));
+        }
+        self.route(index, messages)
+    }
+
+    /// Pick the board for a client that is not on one yet.
+    fn seat_client(&mut self, client: ClientId, line: &str) -> Result<usize, String> {
+        let index = match line.split_once(char::is_whitespace) {
+            Some(("join", _)) => self
+                .boards
+                .iter()
+                .position(|x| !x.is_started())
+                .ok_or("Every board is taken")?,
+            Some(("rejoin", token)) => *self.tokens.get(token.trim()).ok_or("Unknown token")?,
+            _ => return Err(String::from("Join a board first")),
+        };
+        self.clients.insert(client, index);
+        Ok(index)
+    }
+
+    /// Free up a client's seat for `rejoin` when its connection is lost.
+    pub fn disconnect(&mut self, client: ClientId) -> Vec<Message> {
+        let index = match self.clients.get(&client) {
+            Some(index) => *index,
+            None => return vec![],
+        };
+        self.clients.remove(&client);
+        let messages = self.boards[index].disconnect(client);
+        self.route(index, messages)
+    }
+
+    /// Take out anyone who has run out of time, on every board.
+    pub fn check_clock(&mut self) -> Vec<Message> {
+        let mut messages = vec![];
+        for index in 0..self.boards.len() {
+            let found = self.boards[index].check_clock();
+            messages.extend(self.route(index, found));
+        }
+        messages
+    }
+
+    /// Whether a board is waiting for the exhibitor to move.
+    fn is_waiting(&self, host: &Host) -> bool {
+        host.is_started()
+            && host.game().current_player() == Some(self.seat)
+            && !host.game().clock().is_some_and(|x| x.is_paused())
+    }
+
+    /// Whether any board is waiting for the exhibitor to move.
+    pub fn has_waiting(&self) -> bool {
+        self.boards.iter().any(|x| self.is_waiting(x))
+    }
+
+    /// How long the exhibitor may think on a board.
+    fn budget(&self, index: usize) -> Duration {
+        let waiting = self.boards.iter().filter(|x| self.is_waiting(x)).count();
+        let budget = self.think_time / waiting.max(1) as u32;
+        match self.boards[index]
+            .game()
+            .clock()
+            .and_then(|x| x.remaining(self.seat))
+        {
+            Some(left) => budget.min(left / CLOCK_SHARE),
+            None => budget,
+        }
+    }
+
+    /// Play the exhibitor's turn on the next board around that is waiting
+    /// for it, giving back which board that was and what to send. Gives
+    /// `None` if no board is waiting.
+    pub fn play_next(&mut self) -> Option<(usize, Vec<Message>)> {
+        let count = self.boards.len();
+        let index = (0..count)
+            .map(|x| (self.next + x) % count)
+            .find(|x| self.is_waiting(&self.boards[*x]))?;
+        let millis = self.budget(index).as_millis().max(1) as u64;
+        self.engine.budget = ThinkBudget::Millis(millis);
+        self.next = (index + 1) % count;
+        let messages = match self.engine.choose(self.boards[index].game()) {
+            Some(turn) => self.boards[index].handle(EXHIBITOR, &format!("turn {}", turn)),
+            None => self.boards[index].handle(EXHIBITOR, "resign"),
+        };
+        Some((index, self.route(index, messages)))
+    }
+
+    /// Address a board's messages to the clients on it, leaving out the
+    /// exhibitor.
+    fn route(&self, index: usize, messages: Vec<Message>) -> Vec<Message> {
+        let mut routed = vec![];
+        for message in messages {
+            match message.to {
+                Some(EXHIBITOR) => {}
+                Some(_) => routed.push(message),
+                None => routed.extend(
+                    self.clients
+                        .iter()
+                        .filter(|(_, board)| **board == index)
+                        .map(|(client, _)| Message::to(*client, message.text.clone())),
+                ),
+            }
+        }
+        routed
+    }
+}
+
+/// Accept opponents on `listener` and run the exhibition for them. Returns
+/// once every game is over and every client has left.
+pub fn serve(listener: TcpListener, mut simul: Simul) -> io::Result<Simul> {
+    let events = net::listen(listener);
+    let mut streams: HashMap<ClientId, TcpStream> = HashMap::new();
+    loop {
+        // Anything that has already arrived is handled before the
+        // exhibitor thinks again.
+        let wait = match simul.has_waiting() {
+            true => Duration::from_millis(0),
+            false => net::CLOCK_CHECK,
+        };
+        let event = match events.recv_timeout(wait) {
+            Ok(event) => Some(event),
+            Err(mpsc::RecvTimeoutError::Timeout) => None,
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        };
+        let messages = match event {
+            None => {
+                let mut messages = simul.check_clock();
+                if let Some((_, played)) = simul.play_next() {
+                    messages.extend(played);
+                }
+                messages
+            }
+            Some(Event::Connected(client, stream)) => {
+                streams.insert(client, stream);
+                vec![]
+            }
+            Some(Event::Line(client, line)) => simul.handle(client, &line),
+            Some(Event::Closed(client)) => {
+                streams.remove(&client);
+                simul.disconnect(client)
+            }
+        };
+        net::deliver(&mut streams, messages);
+        if simul.is_over() && streams.is_empty() {
+            break;
+        }
+    }
+    Ok(simul)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StartLayout;
+
+    fn simul(boards: usize) -> Simul {
+        let config = Config::default().with_layout(StartLayout::TwoPlayers);
+        Simul::new(config, boards, "Exhibitor").with_think_time(Duration::from_millis(20))
+    }
+
+    fn texts(messages: &[Message], client: ClientId) -> Vec<&str> {
+        messages
+            .iter()
+            .filter(|x| x.to == Some(client))
+            .map(|x| x.text.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_boards() {
+        let mut simul = simul(2);
+        assert!(!simul.has_waiting());
+        assert_eq!(
+            texts(&simul.handle(0, "turn 4/10-5/11"), 0),
+            &["error Join a board first"]
+        );
+
+        let messages = simul.handle(0, "join Ada");
+        assert!(texts(&messages, 0)[0].starts_with("welcome Tail "));
+        assert_eq!(
+            texts(&messages, 0)[1..3],
+            ["joined Head Exhibitor", "joined Tail Ada"]
+        );
+        assert_eq!(texts(&messages, 0)[3], "start");
+        assert_eq!(simul.board_of(0), Some(0));
+        let token = texts(&messages, 0)[0]
+            .split(' ')
+            .nth(2)
+            .unwrap()
+            .to_string();
+
+        let messages = simul.handle(1, "join Bob");
+        assert!(texts(&messages, 1)[0].starts_with("welcome Tail "));
+        assert_eq!(texts(&messages, 0), Vec::<&str>::new());
+        assert_eq!(simul.board_of(1), Some(1));
+        assert_eq!(
+            texts(&simul.handle(2, "join Cy"), 2),
+            &["error Every board is taken"]
+        );
+        assert_eq!(simul.board_of(2), None);
+
+        // The exhibitor moves on each board in turn, and only that board
+        // hears about it.
+        assert!(simul.has_waiting());
+        let (board, messages) = simul.play_next().unwrap();
+        assert_eq!(board, 0);
+        assert!(texts(&messages, 0)[0].starts_with("played Head "));
+        assert_eq!(texts(&messages, 1), Vec::<&str>::new());
+        let (board, messages) = simul.play_next().unwrap();
+        assert_eq!(board, 1);
+        assert!(texts(&messages, 1)[0].starts_with("played Head "));
+        assert_eq!(simul.play_next(), None);
+
+        let messages = simul.handle(1, "turn 14/16-13/15");
+        assert_eq!(texts(&messages, 1)[0], "played Tail 14/16-13/15");
+        assert_eq!(texts(&messages, 0), Vec::<&str>::new());
+        assert_eq!(simul.play_next().map(|x| x.0), Some(1));
+
+        // Takebacks are declined for the exhibitor.
+        simul.handle(1, "turn 14/12-13/11");
+        let messages = simul.handle(1, "takeback");
+        assert_eq!(texts(&messages, 1), &["takeback Tail", "declined Head"]);
+
+        let messages = simul.disconnect(0);
+        assert_eq!(messages, vec![]);
+        assert_eq!(simul.board_of(0), None);
+        let messages = simul.handle(3, &format!("rejoin {}", token));
+        assert!(texts(&messages, 3).contains(&"joined Tail Ada"));
+        assert_eq!(simul.board_of(3), Some(0));
+
+        assert!(!simul.is_over());
+        simul.handle(3, "resign");
+        simul.handle(1, "resign");
+        assert!(simul.is_over());
+        assert!(!simul.has_waiting());
+    }
+
+    #[test]
+    fn test_budget() {
+        let simul = simul(4);
+        assert_eq!(simul.budget(0), Duration::from_millis(20));
+
+        let mut simul = simul.with_time_control(TimeControl::Absolute(Duration::from_secs(1)));
+        for client in 0..4 {
+            simul.handle(client, "join Ada");
+        }
+        assert_eq!(simul.budget(0), Duration::from_millis(5));
+        simul.think_time = Duration::from_secs(4);
+        assert_eq!(simul.budget(0), Duration::from_millis(100));
+    }
+}
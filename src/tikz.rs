@@ -0,0 +1,67 @@
+//! Export of positions as TikZ pictures, for papers and printed handouts.
+//!
+//! The picture is `SIZE` centimeters tall, with one circle per spot,
+//! filled in the color of the piece on it.
+
+use crate::{Board, Cell, Player};
+
+/// Height of the picture in centimeters.
+const SIZE: f64 = 10.0;
+
+/// Share of a spot's radius that is drawn, so neighboring circles don't touch.
+const FILL: f64 = 0.85;
+
+fn color(cell: Cell) -> &'static str {
+    match cell.player() {
+        Some(Player::Head) => "red",
+        Some(Player::Tail) => "blue",
+        Some(Player::LeftHand) => "green!60!black",
+        Some(Player::RightHand) => "orange",
+        Some(Player::LeftFoot) => "violet",
+        Some(Player::RightFoot) => "yellow",
+        None => "white",
+    }
+}
+
+/// TikZ code for a board, ready to be pasted into a LaTeX document.
+pub fn export(board: &Board) -> String {
+    let radius = board.cell_radius() * SIZE * FILL;
+    let mut lines = vec![String::from("\\begin{tikzpicture}")];
+    for (point, cell) in board.cells() {
+        let (x, y) = board.cell_center(point).unwrap();
+        lines.push(format!(
+            "  \\draw[fill={}] ({:.3},{:.3}) circle ({:.3}); % {}",
+            color(cell),
+            x * SIZE,
+            -y * SIZE,
+            radius,
+            point,
+        ));
+    }
+    lines.push(String::from("\\end{tikzpicture}"));
+    lines.join("\n") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[test]
+    fn test_export() {
+        let board = Board::new(Config::default().with_player_lines(1));
+        let text = export(&board);
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 15);
+        assert_eq!(lines[0], "\\begin{tikzpicture}");
+        assert_eq!(
+            lines[1],
+            "  \\draw[fill=red] (5.000,-1.120) circle (0.952); % 1/4"
+        );
+        assert_eq!(
+            lines[13],
+            "  \\draw[fill=blue] (5.000,-8.880) circle (0.952); % 5/4"
+        );
+        assert_eq!(lines[14], "\\end{tikzpicture}");
+    }
+}
@@ -0,0 +1,369 @@
+//! Matches between computer players, for measuring how changes to a bot
+//! affect its strength.
+//!
+//! Every game is between two entrants on the two-player layout, and each
+//! pairing plays an even number of games so that both sides get to move
+//! first equally often. A win is worth one point and a draw half a point.
+//! Games that reach the turn limit without anyone finishing are draws.
+
+use crate::ai::Bot;
+use crate::ndjson::string;
+use crate::{Board, Config, Game, GameError, GameState, Player, StartLayout};
+
+/// How entrants are paired.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    /// Everyone plays everyone else.
+    RoundRobin,
+    /// Each round, entrants play someone with a similar score whom they
+    /// have not played yet. With an odd number, the lowest-ranked entrant
+    /// who has not sat out yet sits out the round.
+    Swiss { rounds: usize },
+}
+
+struct Entrant {
+    name: String,
+    make: Box<dyn Fn() -> Box<dyn Bot>>,
+}
+
+/// One game that was played, with entrants given by their index in
+/// `Report::entrants`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GameSummary {
+    /// The entrant who moved first, as `Player::Head`.
+    pub first: usize,
+    pub second: usize,
+    /// Who won, or `None` for a draw.
+    pub winner: Option<usize>,
+    /// Number of turns in the whole game.
+    pub turns: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Standing {
+    pub name: String,
+    pub games: usize,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    /// One for each win and a half for each draw.
+    pub score: f64,
+    /// Average number of turns that the entrant took per game.
+    pub average_turns: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    /// Names of the entrants, in the order that they were added.
+    pub entrants: Vec<String>,
+    pub games: Vec<GameSummary>,
+    /// Every entrant, from the highest score to the lowest.
+    pub standings: Vec<Standing>,
+}
+
+impl Report {
+    /// The standings as CSV, with a header row.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("name,games,wins,draws,losses,score,average_turns\n");
+        for x in &self.standings {
+            let name = if x.name.contains([',', '"']) {
+                format!("\"{}\"", x.name.replace('"', "\"\""))
+            } else {
+                x.name.clone()
+            };
+            out.push_str(&format!(
+                "{},{},{},{},{},{},{:.1}\n",
+                name, x.games, x.wins, x.draws, x.losses, x.score, x.average_turns
+            ));
+        }
+        out
+    }
+
+    /// The standings and every game as a JSON object. Entrants in the
+    /// games are given by name.
+    pub fn to_json(&self) -> String {
+        let names = &self.entrants;
+        let standings: Vec<String> = self
+            .standings
+            .iter()
+            .map(|x| {
+                format!(
+                    "{{\"name\":{},\"games\":{},\"wins\":{},\"draws\":{},\"losses\":{},\"score\":{},\"average_turns\":{:.1}}}",
+                    string(&x.name),
+                    x.games,
+                    x.wins,
+                    x.draws,
+                    x.losses,
+                    x.score,
+                    x.average_turns
+                )
+            })
+            .collect();
+        let games: Vec<String> = self
+            .games
+            .iter()
+            .map(|x| {
+                format!(
+                    "{{\"first\":{},\"second\":{},\"winner\":{},\"turns\":{}}}",
+                    string(&names[x.first]),
+                    string(&names[x.second]),
+                    x.winner.map_or(String::from("null"), |x| string(&names[x])),
+                    x.turns
+                )
+            })
+            .collect();
+        format!(
+            "{{\"standings\":[{}],\"games\":[{}]}}",
+            standings.join(","),
+            games.join(",")
+        )
+    }
+}
+
+pub struct Tournament {
+    config: Config,
+    entrants: Vec<Entrant>,
+    format: Format,
+    games: usize,
+    max_turns: usize,
+}
+
+impl Tournament {
+    /// Games are played on `config`, but always with the two-player layout.
+    pub fn new(config: Config) -> Self {
+        Self {
+            config: config.with_layout(StartLayout::TwoPlayers),
+            entrants: vec![],
+            format: Format::RoundRobin,
+            games: 2,
+            max_turns: 300,
+        }
+    }
+
+    /// Add an entrant. `make` is called for a fresh bot before every game,
+    /// so that nothing carries over from one game to the next.
+    pub fn with_entrant(mut self, name: &str, make: impl Fn() -> Box<dyn Bot> + 'static) -> Self {
+        self.entrants.push(Entrant {
+            name: name.to_string(),
+            make: Box::new(make),
+        });
+        self
+    }
+
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// How many games each pairing plays. Odd numbers are rounded up, so
+    /// that both entrants move first equally often.
+    pub fn with_games(mut self, games: usize) -> Self {
+        self.games = games + games % 2;
+        self
+    }
+
+    pub fn with_max_turns(mut self, turns: usize) -> Self {
+        self.max_turns = turns;
+        self
+    }
+
+    /// Play every game. `observer` hears about each one as it finishes.
+    pub fn run(&self, mut observer: impl FnMut(&GameSummary)) -> Result<Report, GameError> {
+        let mut games = vec![];
+        match self.format {
+            Format::RoundRobin => {
+                for a in 0..self.entrants.len() {
+                    for b in a + 1..self.entrants.len() {
+                        self.play_pairing(a, b, &mut games, &mut observer)?;
+                    }
+                }
+            }
+            Format::Swiss { rounds } => {
+                let mut sat_out = vec![];
+                for _ in 0..rounds {
+                    let standings = self.standings(&games);
+                    let mut order: Vec<usize> = (0..self.entrants.len()).collect();
+                    order.sort_by(|a, b| standings[*b].score.total_cmp(&standings[*a].score));
+                    if order.len() % 2 == 1 {
+                        if let Some(index) = order.iter().rposition(|x| !sat_out.contains(x)) {
+                            sat_out.push(order.remove(index));
+                        } else {
+                            order.pop();
+                        }
+                    }
+                    while !order.is_empty() {
+                        let a = order.remove(0);
+                        let played = |b: usize| {
+                            games.iter().any(|x: &GameSummary| {
+                                (x.first == a && x.second == b) || (x.first == b && x.second == a)
+                            })
+                        };
+                        let index = order.iter().position(|x| !played(*x)).unwrap_or(0);
+                        let b = order.remove(index);
+                        self.play_pairing(a, b, &mut games, &mut observer)?;
+                    }
+                }
+            }
+        }
+
+        let mut standings = self.standings(&games);
+        standings.sort_by(|a, b| b.score.total_cmp(&a.score));
+        Ok(Report {
+            entrants: self.entrants.iter().map(|x| x.name.clone()).collect(),
+            games,
+            standings,
+        })
+    }
+
+    fn play_pairing(
+        &self,
+        a: usize,
+        b: usize,
+        games: &mut Vec<GameSummary>,
+        observer: &mut impl FnMut(&GameSummary),
+    ) -> Result<(), GameError> {
+        for index in 0..self.games {
+            let (first, second) = if index % 2 == 0 { (a, b) } else { (b, a) };
+            let game = self.play(first, second, games.len())?;
+            observer(&game);
+            games.push(game);
+        }
+        Ok(())
+    }
+
+    /// With a seed, each game gets its own, so that the same two entrants
+    /// do not play the same game every time.
+    fn play(&self, first: usize, second: usize, number: usize) -> Result<GameSummary, GameError> {
+        let mut config = self.config.clone();
+        if let Some(seed) = config.seed() {
+            config = config.with_seed(seed.wrapping_mul(31).wrapping_add(number as u64));
+        }
+        let mut game = Game::new(Board::new(config), vec![Player::Head, Player::Tail]);
+        let mut bots = vec![
+            (self.entrants[first].make)(),
+            (self.entrants[second].make)(),
+        ];
+        game.play_bots(&mut bots, self.max_turns, |_, _| {})?;
+        let result = game.result();
+        let winner = match result.state {
+            GameState::Finished => match result.winner() {
+                Some(Player::Head) => Some(first),
+                Some(_) => Some(second),
+                None => None,
+            },
+            _ => None,
+        };
+        Ok(GameSummary {
+            first,
+            second,
+            winner,
+            turns: result.turns,
+        })
+    }
+
+    /// Standings in the order that entrants were added.
+    fn standings(&self, games: &[GameSummary]) -> Vec<Standing> {
+        self.entrants
+            .iter()
+            .enumerate()
+            .map(|(index, entrant)| {
+                let played: Vec<&GameSummary> = games
+                    .iter()
+                    .filter(|x| x.first == index || x.second == index)
+                    .collect();
+                let wins = played.iter().filter(|x| x.winner == Some(index)).count();
+                let draws = played.iter().filter(|x| x.winner.is_none()).count();
+                // Head moves first, so it takes the extra turn of an odd game.
+                let turns: usize = played
+                    .iter()
+                    .map(|x| {
+                        if x.first == index {
+                            x.turns.div_ceil(2)
+                        } else {
+                            x.turns / 2
+                        }
+                    })
+                    .sum();
+                Standing {
+                    name: entrant.name.clone(),
+                    games: played.len(),
+                    wins,
+                    draws,
+                    losses: played.len() - wins - draws,
+                    score: wins as f64 + draws as f64 / 2.0,
+                    average_turns: match played.len() {
+                        0 => 0.0,
+                        n => turns as f64 / n as f64,
+                    },
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::{GreedyBot, RandomBot};
+
+    fn tournament() -> Tournament {
+        Tournament::new(Config::default().with_progress_limit(100))
+            .with_entrant("greedy", || Box::new(GreedyBot))
+            .with_entrant("random", || Box::new(RandomBot))
+            .with_entrant("greedy, again", || Box::new(GreedyBot))
+            .with_max_turns(100)
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let tournament = tournament().with_games(1);
+        let mut seen = 0;
+        let report = tournament.run(|_| seen += 1).unwrap();
+        assert_eq!(seen, 6);
+        assert_eq!(report.games.len(), 6);
+        for standing in &report.standings {
+            assert_eq!(standing.games, 4);
+            assert_eq!(
+                standing.wins + standing.draws + standing.losses,
+                standing.games
+            );
+        }
+        assert!(report
+            .standings
+            .windows(2)
+            .all(|x| x[0].score >= x[1].score));
+        // Each pairing alternates who moves first.
+        assert_eq!((report.games[0].first, report.games[0].second), (0, 1));
+        assert_eq!((report.games[1].first, report.games[1].second), (1, 0));
+
+        let csv = report.to_csv();
+        assert!(csv.starts_with("name,games,wins,draws,losses,score,average_turns\n"));
+        assert!(csv.contains("\n\"greedy, again\",4,"));
+        let json = report.to_json();
+        assert!(json.starts_with("{\"standings\":[{\"name\":"));
+        assert!(json.contains("\"first\":\"greedy\",\"second\":\"random\""));
+    }
+
+    #[test]
+    fn test_swiss() {
+        let report = tournament()
+            .with_format(Format::Swiss { rounds: 3 })
+            .run(|_| {})
+            .unwrap();
+        // Three entrants means one pairing of two games per round, and
+        // everyone sits out once.
+        assert_eq!(report.games.len(), 6);
+        for standing in &report.standings {
+            assert_eq!(standing.games, 4);
+        }
+    }
+
+    #[test]
+    fn test_seeded() {
+        let tournament = Tournament::new(Config::default().with_seed(3))
+            .with_entrant("random", || Box::new(RandomBot))
+            .with_entrant("random, again", || Box::new(RandomBot))
+            .with_max_turns(20);
+        assert_eq!(tournament.run(|_| {}), tournament.run(|_| {}));
+    }
+}
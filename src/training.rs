@@ -0,0 +1,178 @@
+//! Data from games between computer players, for training evaluation
+//! functions outside of this crate.
+//!
+//! `write_samples` writes one JSON object per line for every turn that was
+//! played, with these fields:
+//!
+//! * `fen`: the position before the turn, from `Game::to_fen`.
+//! * `player`: who took the turn, such as `"Head"`.
+//! * `position`: the position before the turn from `encode`, as an array
+//!   of planes with one 0 or 1 for each spot.
+//! * `turn`: the turn that was chosen, such as `"4/10-5/11"`.
+//! * `outcome`: how the game went for `player`, from 1 for winning to 0
+//!   for coming last. Draws and games that were cut short are 0.5.
+//!
+//! A trained model can be plugged back in as an `ai::Evaluator` by calling
+//! it on `encode(board, player)`, since any `Fn(&Board, Player) -> i32` is
+//! an evaluator.
+
+use crate::ai::Bot;
+use crate::ndjson::string;
+use crate::{Board, Cell, Game, GameError, GameState, Player, Turn};
+use std::io::{self, Write};
+
+/// One turn from a game, and how the game went for whoever took it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sample {
+    pub fen: String,
+    pub player: Player,
+    pub position: Vec<Vec<u8>>,
+    pub turn: Turn,
+    pub outcome: f64,
+}
+
+/// The position from the point of view of `player`, as one plane for each
+/// player in the layout. The first plane is for `player` and the rest
+/// follow the turn order of the layout. Each plane has a 1 for every spot
+/// with that player's piece and a 0 for the rest, in the order of
+/// `Board::cells`.
+pub fn encode(board: &Board, player: Player) -> Vec<Vec<u8>> {
+    let mut players = board.config().layout().players();
+    match players.iter().position(|x| *x == player) {
+        Some(index) => players.rotate_left(index),
+        None => players.insert(0, player),
+    }
+    players
+        .iter()
+        .map(|x| {
+            board
+                .cells()
+                .map(|(_, cell)| u8::from(cell == Cell::Occupied(*x)))
+                .collect()
+        })
+        .collect()
+}
+
+/// Let bots play a game, one for each seat, and record every turn that
+/// they take. Stops like `Game::play_bots`.
+pub fn self_play(
+    game: &mut Game,
+    bots: &mut [Box<dyn Bot>],
+    max_turns: usize,
+) -> Result<Vec<Sample>, GameError> {
+    let mut samples = vec![];
+    for _ in 0..max_turns {
+        let player = match game.current_player() {
+            Some(player) => player,
+            None => break,
+        };
+        let seat = match game.seats().iter().position(|x| *x == player) {
+            Some(seat) => seat,
+            None => break,
+        };
+        let turn = match bots.get_mut(seat).and_then(|x| x.choose(game)) {
+            Some(turn) => turn,
+            None => break,
+        };
+        let fen = game.to_fen();
+        let position = encode(&game.board, player);
+        game.play_turn(&turn)?;
+        samples.push(Sample {
+            fen,
+            player,
+            position,
+            turn,
+            outcome: 0.5,
+        });
+    }
+
+    let result = game.result();
+    if result.state == GameState::Finished && result.placings.len() > 1 {
+        let last = (result.placings.len() - 1) as f64;
+        for sample in &mut samples {
+            if let Some(place) = result
+                .placings
+                .iter()
+                .position(|x| x.player == sample.player)
+            {
+                sample.outcome = (last - place as f64) / last;
+            }
+        }
+    }
+    Ok(samples)
+}
+
+/// Write samples in the format described above, one per line.
+pub fn write_samples(samples: &[Sample], mut out: impl Write) -> io::Result<()> {
+    for sample in samples {
+        let planes: Vec<String> = sample
+            .position
+            .iter()
+            .map(|plane| {
+                let spots: Vec<String> = plane.iter().map(|x| x.to_string()).collect();
+                format!("[{}]", spots.join(","))
+            })
+            .collect();
+        writeln!(
+            out,
+            "{{\"fen\":{},\"player\":\"{:?}\",\"position\":[{}],\"turn\":\"{}\",\"outcome\":{}}}",
+            string(&sample.fen),
+            sample.player,
+            planes.join(","),
+            sample.turn,
+            sample.outcome
+        )?;
+    }
+    out.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::GreedyBot;
+    use crate::{Config, StartLayout};
+
+    #[test]
+    fn test_encode() {
+        let board = Board::new(Config::default().with_layout(StartLayout::ThreePlayers));
+        let planes = encode(&board, Player::RightFoot);
+        assert_eq!(planes.len(), 3);
+        let count = |plane: &[u8]| plane.iter().filter(|x| **x == 1).count();
+        assert_eq!(count(&planes[0]), 10);
+        assert_eq!(planes[0].len(), board.cells().count());
+        let first = board
+            .cells()
+            .position(|(_, x)| x == Cell::Occupied(Player::Head));
+        assert_eq!(planes[2].iter().position(|x| *x == 1), first);
+        assert_eq!(encode(&board, Player::Tail)[0], vec![0; 121]);
+    }
+
+    #[test]
+    fn test_self_play() {
+        let config = Config::default()
+            .with_player_lines(1)
+            .with_layout(StartLayout::TwoPlayers);
+        let mut game = Game::new(Board::new(config), vec![Player::Head, Player::Tail]);
+        let mut bots: Vec<Box<dyn Bot>> = vec![Box::new(GreedyBot), Box::new(GreedyBot)];
+        let samples = self_play(&mut game, &mut bots, 100).unwrap();
+        assert_eq!(game.state(), GameState::Finished);
+        assert_eq!(samples.len(), game.history().len());
+        let winner = game.result().winner().unwrap();
+        for sample in &samples {
+            let expected = if sample.player == winner { 1.0 } else { 0.0 };
+            assert_eq!(sample.outcome, expected);
+        }
+
+        let mut out = vec![];
+        write_samples(&samples[..1], &mut out).unwrap();
+        let line = String::from_utf8(out).unwrap();
+        assert!(line.starts_with(&format!(
+            "{{\"fen\":\"{}\",\"player\":\"Head\",\"position\":[[",
+            samples[0].fen
+        )));
+        assert!(line.ends_with(&format!(
+            "]],\"turn\":\"{}\",\"outcome\":{}}}\n",
+            samples[0].turn, samples[0].outcome
+        )));
+    }
+}
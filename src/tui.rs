@@ -0,0 +1,351 @@
+//! Terminal interface where players pick pieces with a cursor instead of
+//! typing coordinates. Requires the `tui` feature.
+//!
+//! The arrow keys move the cursor. Enter or space selects a piece, which
+//! highlights where it can go, then selects one of those destinations,
+//! which shows the whole jump chain. Pressing Enter once more plays it.
+//! Escape cancels the selection, `u` takes back the last turn, and `q`
+//! leaves the game.
+
+use crate::{Board, Cell, Game, Point, Turn};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::style::{Attribute, Color, Print, ResetColor, SetAttribute, SetBackgroundColor};
+use crossterm::{cursor, queue, terminal};
+use std::io::{self, Write};
+
+const HELP: &str = "Arrows: move, Enter: select, Esc: cancel, u: undo, q: quit";
+
+/// Input for the interface, separate from the terminal's key codes.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Key {
+    Up,
+    Down,
+    Left,
+    Right,
+    Select,
+    Cancel,
+    Undo,
+    Quit,
+}
+
+impl Key {
+    fn from_code(code: KeyCode) -> Option<Self> {
+        match code {
+            KeyCode::Up => Some(Key::Up),
+            KeyCode::Down => Some(Key::Down),
+            KeyCode::Left => Some(Key::Left),
+            KeyCode::Right => Some(Key::Right),
+            KeyCode::Enter | KeyCode::Char(' ') => Some(Key::Select),
+            KeyCode::Esc | KeyCode::Backspace => Some(Key::Cancel),
+            KeyCode::Char('u') => Some(Key::Undo),
+            KeyCode::Char('q') => Some(Key::Quit),
+            _ => None,
+        }
+    }
+}
+
+/// What the current player has picked so far.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Selection {
+    Nothing,
+    /// A piece and every turn it can take.
+    Piece(Point, Vec<Turn>),
+    /// A turn waiting to be confirmed.
+    Turn(Turn),
+}
+
+/// State of the interface, which can be driven without a terminal.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Tui {
+    game: Game,
+    cursor: Point,
+    selection: Selection,
+    message: String,
+}
+
+impl Tui {
+    pub fn new(game: Game) -> Self {
+        let cursor = game
+            .current_player()
+            .and_then(|x| game.board.pieces_of(x).next())
+            .unwrap_or_else(|| game.board.center());
+        Self {
+            game,
+            cursor,
+            selection: Selection::Nothing,
+            message: String::new(),
+        }
+    }
+
+    pub fn game(&self) -> &Game {
+        &self.game
+    }
+
+    pub fn cursor(&self) -> Point {
+        self.cursor
+    }
+
+    /// Spots that the selected piece can move to.
+    pub fn destinations(&self) -> Vec<Point> {
+        match &self.selection {
+            Selection::Piece(_, turns) => turns.iter().map(|x| x.target()).collect(),
+            _ => vec![],
+        }
+    }
+
+    /// The turn waiting to be confirmed, if any.
+    pub fn pending(&self) -> Option<&Turn> {
+        match &self.selection {
+            Selection::Turn(turn) => Some(turn),
+            _ => None,
+        }
+    }
+
+    /// React to a key. Returns false once the player wants to leave.
+    pub fn handle(&mut self, key: Key) -> bool {
+        self.message.clear();
+        match key {
+            Key::Up => self.cursor = nearest_in_row(&self.game.board, self.cursor, -1),
+            Key::Down => self.cursor = nearest_in_row(&self.game.board, self.cursor, 1),
+            Key::Left => self.step_along_row(-2),
+            Key::Right => self.step_along_row(2),
+            Key::Select => self.select(),
+            Key::Cancel => {
+                self.selection = match std::mem::replace(&mut self.selection, Selection::Nothing) {
+                    Selection::Turn(turn) => self.piece_selection(turn.source()),
+                    _ => Selection::Nothing,
+                }
+            }
+            Key::Undo => {
+                self.selection = Selection::Nothing;
+                match self.game.undo() {
+                    Some(record) => {
+                        self.message = format!("Took back {:?}'s {}", record.player, record.turn)
+                    }
+                    None => self.message = String::from("There is nothing to undo"),
+                }
+            }
+            Key::Quit => return false,
+        }
+        true
+    }
+
+    fn step_along_row(&mut self, columns: i32) {
+        let next = Point::new(self.cursor.row, self.cursor.column + columns);
+        if self.game.board.get_piece(next).is_some() {
+            self.cursor = next;
+        }
+    }
+
+    fn piece_selection(&self, from: Point) -> Selection {
+        Selection::Piece(from, self.game.board.destinations(from))
+    }
+
+    fn select(&mut self) {
+        let player = match self.game.current_player() {
+            Some(player) => player,
+            None => return,
+        };
+        let on_own_piece = self.game.board.get_piece(self.cursor) == Some(Cell::Occupied(player));
+        match &self.selection {
+            Selection::Turn(turn) if turn.target() == self.cursor => {
+                let turn = turn.clone();
+                match self.game.play_turn(&turn) {
+                    Ok(()) => {
+                        self.message = format!("{:?} played {}", player, turn);
+                        if self.game.finished().last() == Some(&player) {
+                            self.message.push_str(", and has finished!");
+                        }
+                    }
+                    Err(e) => self.message = e.to_string(),
+                }
+                self.selection = Selection::Nothing;
+            }
+            Selection::Piece(_, turns) if !on_own_piece => {
+                match turns.iter().find(|x| x.target() == self.cursor) {
+                    Some(turn) => {
+                        self.selection = Selection::Turn(turn.clone());
+                        self.message = String::from("Press Enter again to play this turn");
+                    }
+                    None => self.message = String::from("That piece cannot move there"),
+                }
+            }
+            _ if on_own_piece => {
+                self.selection = self.piece_selection(self.cursor);
+                if self.destinations().is_empty() {
+                    self.message = String::from("That piece cannot move");
+                    self.selection = Selection::Nothing;
+                }
+            }
+            _ => self.message = format!("Pick one of {:?}'s pieces", player),
+        }
+    }
+
+    /// Draw the board, with highlights, and the status lines below it.
+    fn draw(&self, out: &mut impl Write) -> io::Result<()> {
+        let board = &self.game.board;
+        let path = match &self.selection {
+            Selection::Piece(from, _) => vec![*from],
+            Selection::Turn(turn) => turn.points(),
+            Selection::Nothing => vec![],
+        };
+        let destinations = self.destinations();
+
+        queue!(out, terminal::Clear(terminal::ClearType::All))?;
+        for (point, piece) in board.cells() {
+            // The diagram from `Board::serialize` puts each spot's
+            // symbol at the character offset of its column.
+            queue!(
+                out,
+                cursor::MoveTo(point.column as u16, point.row as u16 - 1)
+            )?;
+            if path.contains(&point) {
+                queue!(out, SetBackgroundColor(Color::DarkYellow))?;
+            } else if destinations.contains(&point) {
+                queue!(out, SetBackgroundColor(Color::DarkGreen))?;
+            }
+            if point == self.cursor {
+                queue!(out, SetAttribute(Attribute::Reverse))?;
+            }
+            queue!(
+                out,
+                Print(board.config().symbol(piece)),
+                SetAttribute(Attribute::Reset),
+                ResetColor
+            )?;
+        }
+
+        let status = match self.game.current_player() {
+            Some(player) => format!(
+                "{:?} ({}) to move, cursor at {}",
+                player,
+                board.config().symbol(player),
+                self.cursor
+            ),
+            None => String::from("The game is over! Press q to leave."),
+        };
+        let below = board.row_count() as u16 + 1;
+        for (offset, line) in [status.as_str(), self.message.as_str(), HELP]
+            .iter()
+            .enumerate()
+        {
+            queue!(out, cursor::MoveTo(0, below + offset as u16), Print(line))?;
+        }
+        out.flush()
+    }
+}
+
+/// The spot in the next row up or down that is closest to a point,
+/// or the point itself if there is no such row.
+fn nearest_in_row(board: &Board, from: Point, rows: i32) -> Point {
+    let row = from.row + rows;
+    match board.column_range(row) {
+        Some((first, last)) => {
+            // Spots in a row are every other column, so the nearest one
+            // is within a column of the clamped position.
+            let column = from.column.max(first).min(last);
+            let column = if (column - first) % 2 == 0 {
+                column
+            } else {
+                column - 1
+            };
+            Point::new(row, column)
+        }
+        None => from,
+    }
+}
+
+/// Run the interface on the terminal until the player quits, then give
+/// back the game as it was left.
+pub fn run(game: Game) -> io::Result<Game> {
+    let mut tui = Tui::new(game);
+    let mut out = io::stdout();
+    terminal::enable_raw_mode()?;
+    queue!(out, terminal::EnterAlternateScreen, cursor::Hide)?;
+
+    let result = (|| loop {
+        tui.draw(&mut out)?;
+        if let Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            if let Some(key) = Key::from_code(key.code) {
+                if !tui.handle(key) {
+                    return Ok(());
+                }
+            }
+        }
+    })();
+
+    queue!(out, cursor::Show, terminal::LeaveAlternateScreen)?;
+    out.flush()?;
+    terminal::disable_raw_mode()?;
+    result.map(|()| tui.game)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    fn tui() -> Tui {
+        Tui::new(Game::new(
+            Board::default(),
+            vec![Player::Head, Player::Tail],
+        ))
+    }
+
+    #[test]
+    fn test_cursor_movement() {
+        let mut tui = tui();
+        assert_eq!(tui.cursor(), Point::new(1, 13));
+        tui.handle(Key::Left);
+        assert_eq!(tui.cursor(), Point::new(1, 13));
+        tui.handle(Key::Down);
+        assert_eq!(tui.cursor(), Point::new(2, 12));
+        tui.handle(Key::Right);
+        assert_eq!(tui.cursor(), Point::new(2, 14));
+        tui.handle(Key::Up);
+        assert_eq!(tui.cursor(), Point::new(1, 13));
+        tui.handle(Key::Up);
+        assert_eq!(tui.cursor(), Point::new(1, 13));
+    }
+
+    #[test]
+    fn test_select_and_play() {
+        let mut tui = tui();
+        tui.cursor = Point::new(4, 10);
+        tui.handle(Key::Select);
+        assert!(tui.destinations().contains(&Point::new(5, 11)));
+        tui.cursor = Point::new(5, 11);
+        tui.handle(Key::Select);
+        assert_eq!(tui.pending(), Some(&"4/10-5/11".parse().unwrap()));
+        tui.handle(Key::Select);
+        assert_eq!(tui.game().history().len(), 1);
+        assert_eq!(tui.game().current_player(), Some(Player::Tail));
+
+        tui.handle(Key::Undo);
+        assert_eq!(tui.game().history().len(), 0);
+    }
+
+    #[test]
+    fn test_cancel_and_wrong_piece() {
+        let mut tui = tui();
+        tui.cursor = Point::new(14, 16);
+        tui.handle(Key::Select);
+        assert!(tui.destinations().is_empty());
+        assert_eq!(tui.message, "Pick one of Head's pieces");
+
+        tui.cursor = Point::new(3, 11);
+        tui.handle(Key::Select);
+        tui.cursor = Point::new(5, 9);
+        tui.handle(Key::Select);
+        assert_eq!(tui.pending(), Some(&"3/11>5/9".parse().unwrap()));
+        tui.handle(Key::Cancel);
+        assert_eq!(tui.pending(), None);
+        assert!(!tui.destinations().is_empty());
+        tui.handle(Key::Cancel);
+        assert!(tui.destinations().is_empty());
+        assert!(!tui.handle(Key::Quit));
+    }
+}
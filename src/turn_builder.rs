@@ -0,0 +1,273 @@
+//! Putting a turn together one hop at a time, the way people enter jump
+//! chains: pick a piece, then each spot that it lands on.
+//!
+//! A `TurnBuilder` checks every hop against the position as soon as it is
+//! added, with the earlier hops already played on a scratch board, so that
+//! a frontend can reject a bad click right away and show where the piece
+//! can go next. Nothing happens to the real game until `commit`.
+
+use crate::{Board, Direction, Game, GameError, Player, Point, Turn, Verdict};
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TurnBuilder {
+    /// The position before the turn.
+    before: Board,
+    /// The position after the hops so far.
+    board: Board,
+    player: Player,
+    points: Vec<Point>,
+    /// Whether the first hop was a step, which ends the turn.
+    stepped: bool,
+}
+
+impl TurnBuilder {
+    /// Start a turn for the player's piece at `from`.
+    pub fn new(board: &Board, player: Player, from: Point) -> Result<Self, GameError> {
+        let found = board.get_piece(from).ok_or(GameError::OutOfBounds(from))?;
+        if found.player() != Some(player) {
+            return Err(GameError::WrongPlayer {
+                point: from,
+                expected: player,
+                found,
+            });
+        }
+        Ok(Self {
+            before: board.clone(),
+            board: board.clone(),
+            player,
+            points: vec![from],
+            stepped: false,
+        })
+    }
+
+    /// Start a turn for whoever is to move in a game.
+    pub fn for_game(game: &Game, from: Point) -> Result<Self, GameError> {
+        let player = game.current_player().ok_or(GameError::GameFinished)?;
+        Self::new(&game.board, player, from)
+    }
+
+    pub fn player(&self) -> Player {
+        self.player
+    }
+
+    /// Every point visited so far, starting where the piece began.
+    pub fn points(&self) -> &[Point] {
+        &self.points
+    }
+
+    /// The position with the hops so far played.
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Move the piece on to `to`. If the hop is illegal, the builder is
+    /// left as it was and the error says why, numbering the hop among the
+    /// others like `Board::take_turn` would.
+    pub fn hop(&mut self, to: Point) -> Result<Verdict, GameError> {
+        let from = self.points[self.points.len() - 1];
+        let segment = self.points.len() - 1;
+        if self.stepped {
+            return Err(GameError::Exhausted { segment: 0 });
+        }
+        if segment > 0 && from.hex_distance(to) == 1 {
+            return Err(GameError::Exhausted { segment });
+        }
+        if self.revisits(to) {
+            return Err(GameError::Revisited { segment, point: to });
+        }
+        let verdict =
+            self.board
+                .apply_hop(from, to, self.player)
+                .map_err(|error| match segment {
+                    0 => error,
+                    _ => GameError::InChain {
+                        segment,
+                        error: Box::new(error),
+                    },
+                })?;
+        self.stepped = verdict == Verdict::Step;
+        self.points.push(to);
+        Ok(verdict)
+    }
+
+    /// Take back the last hop, returning where the piece had landed.
+    pub fn undo(&mut self) -> Option<Point> {
+        if self.points.len() < 2 {
+            return None;
+        }
+        let last = self.points.pop();
+        self.board = self.before.clone();
+        self.stepped = false;
+        for pair in self.points.clone().windows(2) {
+            let verdict = self
+                .board
+                .apply_hop(pair[0], pair[1], self.player)
+                .expect("the hop was legal before");
+            self.stepped = verdict == Verdict::Step;
+        }
+        last
+    }
+
+    /// Every spot that the piece could hop to next. Before the first hop,
+    /// this includes steps to empty neighbors.
+    pub fn next_hops(&self) -> Vec<Point> {
+        if self.stepped {
+            return vec![];
+        }
+        let from = self.points[self.points.len() - 1];
+        let mut hops = vec![];
+        for direction in Direction::ALL.iter() {
+            let mut targets = vec![from.jump(*direction)];
+            if self.points.len() == 1 {
+                targets.insert(0, from.step(*direction));
+            }
+            for to in targets {
+                if !self.revisits(to) && self.board.clone().apply_hop(from, to, self.player).is_ok()
+                {
+                    hops.push(to);
+                }
+            }
+        }
+        hops
+    }
+
+    /// Whether landing on `to` would visit a spot for the second time,
+    /// when the config does not allow that.
+    fn revisits(&self, to: Point) -> bool {
+        !self.before.config().revisits() && self.points.contains(&to)
+    }
+
+    /// Whether another hop could legally be added.
+    pub fn can_extend(&self) -> bool {
+        !self.next_hops().is_empty()
+    }
+
+    /// The turn made of the hops so far, or `None` if there are none yet.
+    pub fn turn(&self) -> Option<Turn> {
+        match self.points.len() {
+            0 | 1 => None,
+            _ => Turn::new(self.points.clone()).ok(),
+        }
+    }
+
+    /// Play the turn in a game as a whole. If it cannot be played, such as
+    /// because the game has moved on since the builder was started, the
+    /// game is left as it was.
+    pub fn commit(&self, game: &mut Game) -> Result<Turn, GameError> {
+        let turn = self.turn().ok_or(GameError::IncompleteTurn)?;
+        game.play_turn_as(self.player, &turn)?;
+        Ok(turn)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cell, Config};
+
+    #[test]
+    fn test_jump_chain() {
+        let mut game = Game::new(Board::default(), vec![Player::Head, Player::Tail]);
+        let mut builder = TurnBuilder::for_game(&game, Point::new(3, 11)).unwrap();
+        assert_eq!(
+            builder.next_hops(),
+            vec![Point::new(5, 9), Point::new(5, 13)]
+        );
+        assert_eq!(builder.commit(&mut game), Err(GameError::IncompleteTurn));
+
+        assert_eq!(
+            builder.hop(Point::new(5, 13)),
+            Ok(Verdict::Jump {
+                over: Point::new(4, 12),
+                owner: Player::Head,
+                captures: false,
+            })
+        );
+        assert_eq!(
+            builder.board().get_piece(Point::new(5, 13)),
+            Some(Cell::Occupied(Player::Head))
+        );
+        assert_eq!(
+            builder.hop(Point::new(5, 9)),
+            Err(GameError::InChain {
+                segment: 1,
+                error: Box::new(GameError::EmptyMidpoint {
+                    from: Point::new(5, 13),
+                    to: Point::new(5, 9),
+                    middle: Point::new(5, 11),
+                }),
+            })
+        );
+        assert_eq!(
+            builder.hop(Point::new(6, 14)),
+            Err(GameError::Exhausted { segment: 1 })
+        );
+        assert_eq!(builder.points(), &[Point::new(3, 11), Point::new(5, 13)]);
+        // The only jump left would go straight back to where it started.
+        assert!(!builder.can_extend());
+
+        assert_eq!(builder.undo(), Some(Point::new(5, 13)));
+        assert_eq!(builder.undo(), None);
+        assert_eq!(builder.board(), &game.board);
+        builder.hop(Point::new(5, 9)).unwrap();
+
+        let before = game.board.clone();
+        assert_eq!(builder.commit(&mut game), Ok("3/11>5/9".parse().unwrap()));
+        assert_ne!(game.board, before);
+        assert_eq!(game.current_player(), Some(Player::Tail));
+        // The game has moved on, so the same turn cannot be played again.
+        assert!(builder.commit(&mut game).is_err());
+    }
+
+    #[test]
+    fn test_step() {
+        let board = Board::default();
+        let mut builder = TurnBuilder::new(&board, Player::Head, Point::new(4, 10)).unwrap();
+        assert_eq!(
+            builder.next_hops(),
+            vec![Point::new(5, 9), Point::new(5, 11)]
+        );
+        assert_eq!(builder.hop(Point::new(5, 11)), Ok(Verdict::Step));
+        assert!(!builder.can_extend());
+        assert_eq!(
+            builder.hop(Point::new(6, 12)),
+            Err(GameError::Exhausted { segment: 0 })
+        );
+        assert_eq!(builder.turn(), Some("4/10-5/11".parse().unwrap()));
+
+        assert_eq!(
+            TurnBuilder::new(&board, Player::Tail, Point::new(4, 10)),
+            Err(GameError::WrongPlayer {
+                point: Point::new(4, 10),
+                expected: Player::Tail,
+                found: Cell::Occupied(Player::Head),
+            })
+        );
+    }
+
+    #[test]
+    fn test_revisit() {
+        let setup = [
+            (Point::new(9, 13), Player::Head),
+            (Point::new(10, 14), Player::Tail),
+        ];
+        let board = Board::from_setup(&setup, Config::default()).unwrap();
+        let mut builder = TurnBuilder::new(&board, Player::Head, Point::new(9, 13)).unwrap();
+        builder.hop(Point::new(11, 15)).unwrap();
+        assert!(!builder.can_extend());
+        assert_eq!(
+            builder.hop(Point::new(9, 13)),
+            Err(GameError::Revisited {
+                segment: 1,
+                point: Point::new(9, 13),
+            })
+        );
+
+        let config = Config::default().with_revisits(true);
+        let board = Board::from_setup(&setup, config).unwrap();
+        let mut builder = TurnBuilder::new(&board, Player::Head, Point::new(9, 13)).unwrap();
+        builder.hop(Point::new(11, 15)).unwrap();
+        assert_eq!(builder.next_hops(), vec![Point::new(9, 13)]);
+        assert!(builder.hop(Point::new(9, 13)).is_ok());
+    }
+}
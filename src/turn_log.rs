@@ -0,0 +1,214 @@
+//! Readable turn logs for console summaries and text exports, such as
+//! `12. Head: 3/11 jumps over 4/12 to 5/13`.
+
+use crate::{Game, Player, Point, Turn};
+use std::time::Duration;
+
+/// One line of a turn log.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LogEntry {
+    pub player: Player,
+    /// Name to show instead of the player's corner, as in `SeatInfo`.
+    pub name: Option<String>,
+    pub turn: Turn,
+    /// Spots of the pieces that the turn captured.
+    pub captured: Vec<Point>,
+    /// Time into the game when the turn was played.
+    pub elapsed: Option<Duration>,
+    /// Time that the player took to decide on the turn.
+    pub spent: Option<Duration>,
+    /// Time that the player had left on their clock after the turn.
+    pub remaining: Option<Duration>,
+    /// What an engine made of the position after the turn.
+    pub evaluation: Option<i32>,
+    pub comment: Option<String>,
+}
+
+/// Entries for every turn in a game's history, named after the seats.
+/// The time into the game is the `TurnRecord::timestamp`.
+pub fn from_game(game: &Game) -> Vec<LogEntry> {
+    game.history()
+        .iter()
+        .map(|record| LogEntry {
+            player: record.player,
+            name: game.seat_info(record.player).map(|x| x.name.clone()),
+            turn: record.turn.clone(),
+            captured: record.captured.iter().map(|(point, _)| *point).collect(),
+            elapsed: record.timestamp,
+            spent: record.spent,
+            remaining: record.clock_remaining,
+            evaluation: record.evaluation,
+            comment: record.comment.clone(),
+        })
+        .collect()
+}
+
+/// Describe a turn in words, naming every spot that gets jumped over.
+pub fn describe(turn: &Turn) -> String {
+    match turn {
+        Turn::Step { from, to } => format!("{} steps to {}", from, to),
+        Turn::Jumps(points) => {
+            let hops: Vec<String> = turn
+                .jumped()
+                .iter()
+                .zip(&points[1..])
+                .map(|(middle, to)| format!("over {} to {}", middle, to))
+                .collect();
+            format!("{} jumps {}", points[0], hops.join(", then "))
+        }
+    }
+}
+
+fn format_elapsed(elapsed: Duration) -> String {
+    let seconds = elapsed.as_secs();
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Render entries as numbered lines, starting from turn 1.
+pub fn format(entries: &[LogEntry]) -> Vec<String> {
+    entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let times: Vec<String> = vec![
+                entry.elapsed.map(format_elapsed),
+                entry.spent.map(|x| format!("took {}", format_elapsed(x))),
+                entry
+                    .remaining
+                    .map(|x| format!("{} left", format_elapsed(x))),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+            let time = if times.is_empty() {
+                String::new()
+            } else {
+                format!(" [{}]", times.join(", "))
+            };
+            let player = match &entry.name {
+                Some(name) => name.clone(),
+                None => format!("{:?}", entry.player),
+            };
+            let captured = if entry.captured.is_empty() {
+                String::new()
+            } else {
+                let points: Vec<String> = entry.captured.iter().map(|x| x.to_string()).collect();
+                format!(", capturing {}", points.join(" and "))
+            };
+            let evaluation = match entry.evaluation {
+                Some(score) => format!(" ({:+})", score),
+                None => String::new(),
+            };
+            let comment = match &entry.comment {
+                Some(comment) => format!(" {{{}}}", comment),
+                None => String::new(),
+            };
+            format!(
+                "{}.{} {}: {}{}{}{}",
+                index + 1,
+                time,
+                player,
+                describe(&entry.turn),
+                captured,
+                evaluation,
+                comment
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe() {
+        assert_eq!(
+            describe(&"4/10-5/11".parse().unwrap()),
+            "4/10 steps to 5/11"
+        );
+        assert_eq!(
+            describe(&"3/11>5/13".parse().unwrap()),
+            "3/11 jumps over 4/12 to 5/13"
+        );
+        assert_eq!(
+            describe(&"3/11>5/13>5/9".parse().unwrap()),
+            "3/11 jumps over 4/12 to 5/13, then over 5/11 to 5/9"
+        );
+    }
+
+    #[test]
+    fn test_from_game() {
+        let mut game = Game::new(crate::Board::default(), vec![Player::Head, Player::Tail]);
+        game.set_seat_info(
+            Player::Tail,
+            crate::SeatInfo::new(Player::Tail).with_name("Ada"),
+        );
+        game.set_time_control(crate::clock::TimeControl::Absolute(Duration::from_secs(60)));
+        game.play_timed_turn(&"4/10-5/11".parse().unwrap(), Duration::from_secs(5))
+            .unwrap();
+        game.set_turn_comment(Some(String::from("opening")));
+        game.play_timed_turn(&"14/16-13/15".parse().unwrap(), Duration::from_secs(7))
+            .unwrap();
+        game.set_turn_evaluation(Some(2));
+        assert_eq!(
+            format(&from_game(&game)),
+            vec![
+                "1. [0:00:05, took 0:00:05, 0:00:55 left] Head: 4/10 steps to 5/11 {opening}",
+                "2. [0:00:12, took 0:00:07, 0:00:53 left] Ada: 14/16 steps to 13/15 (+2)",
+            ],
+        );
+    }
+
+    #[test]
+    fn test_format() {
+        let entries = vec![
+            LogEntry {
+                player: Player::Head,
+                name: None,
+                turn: "4/10-5/11".parse().unwrap(),
+                captured: vec![],
+                elapsed: None,
+                spent: None,
+                remaining: None,
+                evaluation: None,
+                comment: None,
+            },
+            LogEntry {
+                player: Player::Tail,
+                name: Some(String::from("Ada")),
+                turn: "14/16-13/15".parse().unwrap(),
+                captured: vec![],
+                elapsed: Some(Duration::from_secs(3725)),
+                spent: None,
+                remaining: None,
+                evaluation: None,
+                comment: None,
+            },
+            LogEntry {
+                player: Player::Head,
+                name: None,
+                turn: "3/11>5/13>5/9".parse().unwrap(),
+                captured: vec![Point::new(4, 12), Point::new(5, 11)],
+                elapsed: Some(Duration::from_secs(3737)),
+                spent: Some(Duration::from_secs(12)),
+                remaining: Some(Duration::from_secs(168)),
+                evaluation: Some(-4),
+                comment: Some(String::from("opens the left side")),
+            },
+        ];
+        assert_eq!(
+            format(&entries),
+            vec![
+                "1. Head: 4/10 steps to 5/11",
+                "2. [1:02:05] Ada: 14/16 steps to 13/15",
+                "3. [1:02:17, took 0:00:12, 0:02:48 left] Head: 3/11 jumps over 4/12 to 5/13, then over 5/11 to 5/9, capturing 4/12 and 5/11 (-4) {opens the left side}",
+            ],
+        );
+    }
+}
@@ -0,0 +1,249 @@
+//! Tree of variations for analysis. Any position can be branched from,
+//! so side lines can be explored without losing the main line. The first
+//! child of each node is its main continuation.
+
+use crate::{Board, GameError, Player, Snapshot, Turn};
+
+/// Handle to a position in a `VariationTree`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct NodeId(usize);
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct Node {
+    parent: Option<NodeId>,
+    /// Who moved and how to reach this position. Only the root has none.
+    step: Option<(Player, Turn)>,
+    position: Snapshot,
+    children: Vec<NodeId>,
+}
+
+/// Methods that take a `NodeId` panic if that node has been deleted,
+/// the same way that indexing a `Vec` out of bounds would.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VariationTree {
+    board: Board,
+    nodes: Vec<Option<Node>>,
+}
+
+impl VariationTree {
+    pub fn new(board: Board) -> Self {
+        let root = Node {
+            parent: None,
+            step: None,
+            position: board.snapshot(),
+            children: vec![],
+        };
+        Self {
+            board,
+            nodes: vec![Some(root)],
+        }
+    }
+
+    /// Build a tree whose main line is an existing sequence of turns.
+    pub fn from_line(board: Board, line: &[(Player, Turn)]) -> Result<Self, GameError> {
+        let mut tree = Self::new(board);
+        let mut at = tree.root();
+        for (player, turn) in line {
+            at = tree.play(at, *player, turn.clone())?;
+        }
+        Ok(tree)
+    }
+
+    pub fn root(&self) -> NodeId {
+        NodeId(0)
+    }
+
+    pub fn contains(&self, id: NodeId) -> bool {
+        matches!(self.nodes.get(id.0), Some(Some(_)))
+    }
+
+    fn node(&self, id: NodeId) -> &Node {
+        self.nodes[id.0].as_ref().expect("variation was deleted")
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> &mut Node {
+        self.nodes[id.0].as_mut().expect("variation was deleted")
+    }
+
+    /// The position at a node.
+    pub fn board(&self, id: NodeId) -> Board {
+        let mut board = self.board.clone();
+        board
+            .restore(&self.node(id).position)
+            .expect("snapshots in a tree share one board size");
+        board
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self.node(id).parent
+    }
+
+    /// Continuations from a node, starting with the main one.
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        &self.node(id).children
+    }
+
+    /// The turn that led to a node, and who played it.
+    pub fn step(&self, id: NodeId) -> Option<(Player, &Turn)> {
+        self.node(id)
+            .step
+            .as_ref()
+            .map(|(player, turn)| (*player, turn))
+    }
+
+    /// Play a turn from a node. If that continuation already exists,
+    /// its node is returned; otherwise it is added as the last variation.
+    pub fn play(&mut self, at: NodeId, player: Player, turn: Turn) -> Result<NodeId, GameError> {
+        let existing = self
+            .children(at)
+            .iter()
+            .find(|child| self.step(**child) == Some((player, &turn)));
+        if let Some(child) = existing {
+            return Ok(*child);
+        }
+
+        let mut board = self.board(at);
+        board.take_turn(&turn, player)?;
+        let id = NodeId(self.nodes.len());
+        self.nodes.push(Some(Node {
+            parent: Some(at),
+            step: Some((player, turn)),
+            position: board.snapshot(),
+            children: vec![],
+        }));
+        self.node_mut(at).children.push(id);
+        Ok(id)
+    }
+
+    /// Turns from the root that lead to a node.
+    pub fn line_to(&self, id: NodeId) -> Vec<(Player, Turn)> {
+        let mut line = vec![];
+        let mut at = Some(id);
+        while let Some(id) = at {
+            if let Some(step) = &self.node(id).step {
+                line.push(step.clone());
+            }
+            at = self.parent(id);
+        }
+        line.reverse();
+        line
+    }
+
+    /// Nodes that follow a node when always taking the main continuation.
+    pub fn main_line(&self, from: NodeId) -> Vec<NodeId> {
+        let mut line = vec![];
+        let mut at = from;
+        while let Some(child) = self.children(at).first() {
+            line.push(*child);
+            at = *child;
+        }
+        line
+    }
+
+    /// Make a variation the main continuation of its parent.
+    pub fn promote(&mut self, id: NodeId) {
+        if let Some(parent) = self.parent(id) {
+            let siblings = &mut self.node_mut(parent).children;
+            siblings.retain(|x| *x != id);
+            siblings.insert(0, id);
+        }
+    }
+
+    /// Remove a variation and everything after it. The root cannot be deleted.
+    pub fn delete(&mut self, id: NodeId) -> Result<(), GameError> {
+        let parent = self.parent(id).ok_or(GameError::InvalidSetup)?;
+        self.node_mut(parent).children.retain(|x| *x != id);
+        let mut pending = vec![id];
+        while let Some(id) = pending.pop() {
+            if let Some(node) = self.nodes[id.0].take() {
+                pending.extend(node.children);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cell, Point};
+
+    fn turn(text: &str) -> Turn {
+        text.parse().unwrap()
+    }
+
+    fn tree() -> VariationTree {
+        VariationTree::from_line(
+            Board::default(),
+            &[
+                (Player::Head, turn("4/10-5/11")),
+                (Player::Tail, turn("14/16-13/15")),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_main_line() {
+        let tree = tree();
+        let line = tree.main_line(tree.root());
+        assert_eq!(line.len(), 2);
+        assert_eq!(
+            tree.step(line[1]),
+            Some((Player::Tail, &turn("14/16-13/15")))
+        );
+        assert_eq!(tree.line_to(line[1]).len(), 2);
+        assert_eq!(
+            tree.board(line[0]).get_piece(Point::new(5, 11)),
+            Some(Cell::Occupied(Player::Head))
+        );
+        assert_eq!(tree.board(tree.root()), Board::default());
+    }
+
+    #[test]
+    fn test_branch_and_promote() {
+        let mut tree = tree();
+        let first = tree.main_line(tree.root())[0];
+        let side = tree.play(first, Player::Tail, turn("14/10-13/9")).unwrap();
+        assert_eq!(tree.children(first).len(), 2);
+        assert_eq!(tree.children(first)[1], side);
+        assert_eq!(tree.play(first, Player::Tail, turn("14/10-13/9")), Ok(side));
+
+        tree.promote(side);
+        assert_eq!(tree.main_line(tree.root()), vec![first, side]);
+        assert_eq!(
+            tree.line_to(side),
+            vec![
+                (Player::Head, turn("4/10-5/11")),
+                (Player::Tail, turn("14/10-13/9"))
+            ],
+        );
+    }
+
+    #[test]
+    fn test_illegal_branch() {
+        let mut tree = tree();
+        let root = tree.root();
+        assert_eq!(
+            tree.play(root, Player::Head, turn("1/13-2/12")),
+            Err(GameError::OccupiedTarget {
+                point: Point::new(2, 12),
+                occupant: Player::Head,
+            }),
+        );
+        assert_eq!(tree.children(root).len(), 1);
+    }
+
+    #[test]
+    fn test_delete() {
+        let mut tree = tree();
+        let root = tree.root();
+        let first = tree.main_line(root)[0];
+        let second = tree.main_line(root)[1];
+        assert_eq!(tree.delete(root), Err(GameError::InvalidSetup));
+        assert_eq!(tree.delete(first), Ok(()));
+        assert!(!tree.contains(first));
+        assert!(!tree.contains(second));
+        assert_eq!(tree.children(root), &[]);
+    }
+}
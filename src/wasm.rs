@@ -0,0 +1,222 @@
+//! Bindings for running games in a browser, built with the `wasm` feature.
+//!
+//! JavaScript sees these as `Game` and `Board`. Points and turns are passed
+//! in the usual notation, such as `4/10-5/11`, players are passed by name,
+//! such as `Head`, and anything more involved comes back as a JSON string
+//! that can be handed to `JSON.parse`. Errors are thrown as strings with
+//! the same messages as everywhere else.
+
+use crate::ndjson::string;
+use crate::notation::player_named;
+use crate::{Board, Cell, Config, Game, GameState, Player, Point, StartLayout, Turn};
+use std::fmt::Display;
+use wasm_bindgen::prelude::*;
+
+fn js_error(error: impl Display) -> JsValue {
+    JsValue::from_str(&error.to_string())
+}
+
+fn player(name: &str) -> Result<Player, JsValue> {
+    player_named(name).ok_or_else(|| js_error(format!("Unknown player: {}", name)))
+}
+
+fn turns(turns: &[Turn]) -> String {
+    let turns: Vec<String> = turns.iter().map(|x| string(&x.to_string())).collect();
+    format!("[{}]", turns.join(","))
+}
+
+/// Every spot on the board, with the name of the player there or `null`.
+fn spots(board: &Board) -> String {
+    let spots: Vec<String> = board
+        .cells()
+        .map(|(point, cell)| {
+            let player = match cell {
+                Cell::Occupied(player) => format!("\"{:?}\"", player),
+                Cell::Empty => String::from("null"),
+            };
+            format!("{{\"point\":\"{}\",\"player\":{}}}", point, player)
+        })
+        .collect();
+    format!("[{}]", spots.join(","))
+}
+
+#[wasm_bindgen(js_name = Board)]
+pub struct WasmBoard {
+    board: Board,
+}
+
+#[wasm_bindgen(js_class = Board)]
+impl WasmBoard {
+    /// The starting position for 2, 3, 4, or 6 players.
+    #[wasm_bindgen(constructor)]
+    pub fn new(players: usize) -> Result<WasmBoard, JsValue> {
+        let layout = StartLayout::for_players(players)
+            .ok_or_else(|| js_error(format!("Unsupported number of players: {}", players)))?;
+        Ok(Self {
+            board: Board::new(Config::default().with_layout(layout)),
+        })
+    }
+
+    #[wasm_bindgen(js_name = fromFen)]
+    pub fn from_fen(fen: &str) -> Result<WasmBoard, JsValue> {
+        let (board, _) = Board::from_fen(fen).map_err(js_error)?;
+        Ok(Self { board })
+    }
+
+    pub fn fen(&self) -> String {
+        self.board.to_fen(None)
+    }
+
+    /// Every spot, as `[{"point": "1/13", "player": "Head"}, ...]`.
+    pub fn spots(&self) -> String {
+        spots(&self.board)
+    }
+
+    /// Every turn that a player could take, as a JSON array of turns.
+    #[wasm_bindgen(js_name = legalTurns)]
+    pub fn legal_turns(&self, player_name: &str) -> Result<String, JsValue> {
+        Ok(turns(&self.board.legal_turns(player(player_name)?)))
+    }
+
+    /// Every turn for the piece at a point, such as `5/11`, with one for
+    /// each spot it can reach, as a JSON array of turns.
+    pub fn destinations(&self, point: &str) -> Result<String, JsValue> {
+        let point: Point = point.parse().map_err(js_error)?;
+        Ok(turns(&self.board.destinations(point)))
+    }
+
+    /// Play a turn without any of the turn order that a game has.
+    #[wasm_bindgen(js_name = takeTurn)]
+    pub fn take_turn(&mut self, turn: &str, player_name: &str) -> Result<(), JsValue> {
+        let turn: Turn = turn.parse().map_err(js_error)?;
+        self.board
+            .take_turn(&turn, player(player_name)?)
+            .map_err(js_error)
+    }
+}
+
+#[wasm_bindgen(js_name = Game)]
+pub struct WasmGame {
+    game: Game,
+}
+
+#[wasm_bindgen(js_class = Game)]
+impl WasmGame {
+    /// A new game for 2, 3, 4, or 6 players on the standard board.
+    #[wasm_bindgen(constructor)]
+    pub fn new(players: usize) -> Result<WasmGame, JsValue> {
+        let board = WasmBoard::new(players)?.board;
+        let layout = board.config().layout();
+        Ok(Self {
+            game: Game::new(board, layout.players()),
+        })
+    }
+
+    pub fn board(&self) -> WasmBoard {
+        WasmBoard {
+            board: self.game.board.clone(),
+        }
+    }
+
+    /// Name of the player to move, or `undefined` once the game is over.
+    #[wasm_bindgen(js_name = currentPlayer)]
+    pub fn current_player(&self) -> Option<String> {
+        self.game.current_player().map(|x| format!("{:?}", x))
+    }
+
+    /// Every turn that the player to move could take, as a JSON array.
+    #[wasm_bindgen(js_name = legalTurns)]
+    pub fn legal_turns(&self) -> String {
+        match self.game.current_player() {
+            Some(player) => turns(&self.game.board.legal_turns(player)),
+            None => String::from("[]"),
+        }
+    }
+
+    /// Play a turn for the player to move, such as `3/11>5/13>5/9`.
+    #[wasm_bindgen(js_name = playTurn)]
+    pub fn play_turn(&mut self, turn: &str) -> Result<(), JsValue> {
+        let turn: Turn = turn.parse().map_err(js_error)?;
+        self.game.play_turn(&turn).map_err(js_error)
+    }
+
+    /// Take back the last turn. Returns false if there was none.
+    pub fn undo(&mut self) -> bool {
+        self.game.undo().is_some()
+    }
+
+    /// Take a player out of the game. Returns false if they were not in it.
+    pub fn resign(&mut self, player_name: &str) -> Result<bool, JsValue> {
+        Ok(self.game.resign(player(player_name)?))
+    }
+
+    /// Everything a client needs to draw the game, as
+    /// `{"state": ..., "toMove": ..., "standings": [...], "history": [...], "spots": [...]}`.
+    /// The state is `playing`, `finished`, or `drawn`, and the standings
+    /// are as in `Game::standings`.
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> String {
+        let state = match self.game.state() {
+            GameState::InProgress => "playing",
+            GameState::Finished => "finished",
+            GameState::Drawn(_) => "drawn",
+        };
+        let to_move = self
+            .game
+            .current_player()
+            .map_or(String::from("null"), |x| format!("\"{:?}\"", x));
+        let standings: Vec<String> = self
+            .game
+            .standings()
+            .iter()
+            .map(|x| format!("\"{:?}\"", x))
+            .collect();
+        let history: Vec<Turn> = self.game.history().iter().map(|x| x.turn.clone()).collect();
+        format!(
+            "{{\"state\":\"{}\",\"toMove\":{},\"standings\":[{}],\"history\":{},\"spots\":{}}}",
+            state,
+            to_move,
+            standings.join(","),
+            turns(&history),
+            spots(&self.game.board)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_game() {
+        let mut game = WasmGame::new(2).unwrap();
+        assert_eq!(game.current_player(), Some(String::from("Head")));
+        assert!(game.legal_turns().contains("\"4/10-5/11\""));
+        game.play_turn("4/10-5/11").unwrap();
+        assert_eq!(game.current_player(), Some(String::from("Tail")));
+
+        let json = game.to_json();
+        assert!(json.starts_with(
+            "{\"state\":\"playing\",\"toMove\":\"Tail\",\"standings\":[\"Head\",\"Tail\"],\"history\":[\"4/10-5/11\"],"
+        ));
+        assert!(json.contains("{\"point\":\"5/11\",\"player\":\"Head\"}"));
+        assert!(json.contains("{\"point\":\"4/10\",\"player\":null}"));
+
+        assert!(game.undo());
+        assert!(!game.undo());
+        assert_eq!(game.board().fen(), WasmBoard::new(2).unwrap().fen());
+    }
+
+    #[test]
+    fn test_board() {
+        let mut board = WasmBoard::new(3).unwrap();
+        board.take_turn("4/10-5/11", "Head").unwrap();
+        let copy = WasmBoard::from_fen(&board.fen()).unwrap();
+        assert_eq!(copy.spots(), board.spots());
+        assert_eq!(board.legal_turns("Tail").unwrap(), "[]");
+        assert_eq!(
+            WasmBoard::new(2).unwrap().destinations("4/10").unwrap(),
+            "[\"4/10-5/9\",\"4/10-5/11\"]"
+        );
+    }
+}